@@ -0,0 +1,260 @@
+use super::Error;
+use crate::expr::{Array, Expression, Object, ObjectKey, ObjectValue};
+use crate::template::Template;
+use crate::{Decorate, Span};
+use serde::de::value::{MapAccessDeserializer, StringDeserializer};
+use serde::de::{self, IntoDeserializer};
+use serde::forward_to_deserialize_any;
+use std::ops::Range;
+
+pub(super) struct ExpressionDeserializer {
+    expr: Expression,
+    span: Option<Range<usize>>,
+}
+
+impl ExpressionDeserializer {
+    pub(super) fn new(expr: Expression) -> ExpressionDeserializer {
+        let span = expr.span();
+        ExpressionDeserializer { expr, span }
+    }
+
+    // Returns the string that expressions which are not literal values are deserialized as.
+    fn into_string(self) -> Result<String, Error> {
+        match self.expr {
+            Expression::String(s) => Ok(s.into_value()),
+            Expression::Template(template) => Ok(Template::from(template).to_string()),
+            Expression::HeredocTemplate(mut heredoc) => {
+                heredoc.dedent();
+                Ok(heredoc.template.to_string())
+            }
+            Expression::Parenthesis(parens) => {
+                ExpressionDeserializer::new(parens.into_inner()).into_string()
+            }
+            Expression::Null(_)
+            | Expression::Bool(_)
+            | Expression::Number(_)
+            | Expression::Array(_)
+            | Expression::Object(_) => Err(self.invalid_type(&"a string")),
+            mut expr => {
+                expr.decor_mut().clear();
+                Ok(format!("${{{expr}}}"))
+            }
+        }
+    }
+
+    fn invalid_type(&self, exp: &dyn de::Expected) -> Error {
+        let unexp = match &self.expr {
+            Expression::Null(_) => de::Unexpected::Unit,
+            Expression::Bool(b) => de::Unexpected::Bool(*b.value()),
+            Expression::Number(_) => de::Unexpected::Other("number"),
+            Expression::String(s) => de::Unexpected::Str(s.value()),
+            Expression::Array(_) => de::Unexpected::Seq,
+            Expression::Object(_) => de::Unexpected::Map,
+            _ => de::Unexpected::Other("expression"),
+        };
+
+        de::Error::invalid_type(unexp, exp)
+    }
+
+    fn with_span<T>(span: Option<Range<usize>>, result: Result<T, Error>) -> Result<T, Error> {
+        result.map_err(|err| err.with_span(span))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ExpressionDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let span = self.span.clone();
+
+        let result = match self.expr {
+            Expression::Null(_) => visitor.visit_unit(),
+            Expression::Bool(b) => visitor.visit_bool(b.into_value()),
+            Expression::Number(n) => n
+                .into_value()
+                .deserialize_any(visitor)
+                .map_err(de::Error::custom),
+            Expression::String(s) => visitor.visit_string(s.into_value()),
+            Expression::Array(array) => visitor.visit_seq(ArrayAccess::new(array)),
+            Expression::Object(object) => visitor.visit_map(ObjectAccess::new(object)),
+            Expression::Parenthesis(parens) => {
+                ExpressionDeserializer::new(parens.into_inner()).deserialize_any(visitor)
+            }
+            expr => match ExpressionDeserializer::new(expr).into_string() {
+                Ok(s) => visitor.visit_string(s),
+                Err(err) => Err(err),
+            },
+        };
+
+        ExpressionDeserializer::with_span(span, result)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.expr {
+            Expression::Null(_)
+            | Expression::Bool(_)
+            | Expression::Number(_)
+            | Expression::Array(_)
+            | Expression::Object(_) => self.deserialize_any(visitor),
+            _ => {
+                let span = self.span.clone();
+                let result = self.into_string().and_then(|s| visitor.visit_string(s));
+                ExpressionDeserializer::with_span(span, result)
+            }
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.expr {
+            Expression::Null(_) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let span = self.span.clone();
+
+        let result = match self.expr {
+            Expression::String(s) => visitor.visit_enum(s.into_value().into_deserializer()),
+            Expression::Object(object) => {
+                visitor.visit_enum(MapAccessDeserializer::new(ObjectAccess::new(object)))
+            }
+            Expression::Parenthesis(parens) => {
+                return ExpressionDeserializer::new(parens.into_inner())
+                    .deserialize_enum(name, variants, visitor)
+            }
+            _ => Err(self.invalid_type(&"a string or an object")),
+        };
+
+        ExpressionDeserializer::with_span(span, result)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier
+    }
+}
+
+struct ArrayAccess {
+    iter: crate::expr::IntoIter,
+}
+
+impl ArrayAccess {
+    fn new(array: Array) -> ArrayAccess {
+        ArrayAccess {
+            iter: array.into_iter(),
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for ArrayAccess {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        self.iter
+            .next()
+            .map(|expr| seed.deserialize(ExpressionDeserializer::new(expr)))
+            .transpose()
+    }
+}
+
+struct ObjectAccess {
+    iter: crate::expr::ObjectIntoIter,
+    value: Option<ObjectValue>,
+}
+
+impl ObjectAccess {
+    fn new(object: Object) -> ObjectAccess {
+        ObjectAccess {
+            iter: object.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for ObjectAccess {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+
+                let (key, span) = match key {
+                    ObjectKey::Ident(ident) => (ident.to_string(), ident.span()),
+                    ObjectKey::Expression(expr) => {
+                        let deserializer = ExpressionDeserializer::new(expr);
+                        let span = deserializer.span.clone();
+                        (deserializer.into_string()?, span)
+                    }
+                };
+
+                seed.deserialize(StringDeserializer::<Error>::new(key))
+                    .map(Some)
+                    .map_err(|err| err.with_span(span))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+
+        seed.deserialize(ExpressionDeserializer::new(value.into_expr()))
+    }
+}
@@ -0,0 +1,503 @@
+//! Deserialize Rust data structures directly from HCL documents.
+//!
+//! The [`from_body`] function deserializes a [`Body`] into any type implementing
+//! [`serde::Deserialize`] without converting it into another representation first.
+//!
+//! Bodies are mapped to Rust data structures following the [HCL JSON
+//! Specification][hcl-json-spec], just like the deserializer of the [`hcl`](https://docs.rs/hcl)
+//! crate does:
+//!
+//! - Attributes become map entries with the attribute key as map key.
+//! - Blocks become map entries with the block identifier as map key. Each block label adds
+//!   another level of nesting. Blocks with the same identifier and labels are merged into a
+//!   sequence of block bodies.
+//! - Expressions which are not literal values, like variables or function calls, are
+//!   deserialized as strings containing the expression wrapped in `${` and `}`.
+//!
+//! Errors carry the byte range of the attribute, block or expression that caused them, if the
+//! body was produced by the parser. See [`Error::span`] for an example.
+//!
+//! This module is only available if the `serde` feature is enabled.
+//!
+//! [hcl-json-spec]: https://github.com/hashicorp/hcl/blob/main/json/spec.md
+//!
+//! # Example
+//!
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use hcl_edit::structure::Body;
+//! use serde::Deserialize;
+//! use std::collections::HashMap;
+//!
+//! #[derive(Deserialize, Debug, PartialEq)]
+//! struct Config {
+//!     version: u8,
+//!     service: HashMap<String, Service>,
+//! }
+//!
+//! #[derive(Deserialize, Debug, PartialEq)]
+//! struct Service {
+//!     port: u16,
+//!     image: String,
+//! }
+//!
+//! let input = r#"
+//! version = 2
+//!
+//! service "api" {
+//!   port  = 8080
+//!   image = "api:${var.tag}"
+//! }
+//! "#;
+//!
+//! let body: Body = input.parse()?;
+//! let config: Config = hcl_edit::de::from_body(body)?;
+//!
+//! let expected = Config {
+//!     version: 2,
+//!     service: HashMap::from([(
+//!         "api".into(),
+//!         Service {
+//!             port: 8080,
+//!             image: "api:${var.tag}".into(),
+//!         },
+//!     )]),
+//! };
+//!
+//! assert_eq!(config, expected);
+//! #     Ok(())
+//! # }
+//! ```
+
+mod expr;
+
+use self::expr::ExpressionDeserializer;
+use crate::expr::Expression;
+use crate::structure::{Body, Structure};
+use crate::Span;
+use serde::de::value::{MapAccessDeserializer, StrDeserializer};
+use serde::de::{self, DeserializeOwned, IntoDeserializer};
+use std::fmt;
+use std::ops::Range;
+use vecmap::VecMap;
+
+/// The error type returned when deserialization fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    message: String,
+    span: Option<Range<usize>>,
+}
+
+impl Error {
+    fn new(message: impl fmt::Display) -> Error {
+        Error {
+            message: message.to_string(),
+            span: None,
+        }
+    }
+
+    // Attaches a span to errors that do not carry one yet. Errors are created at the innermost
+    // node that caused them, so this keeps the most precise span.
+    fn with_span(mut self, span: Option<Range<usize>>) -> Error {
+        if self.span.is_none() {
+            self.span = span;
+        }
+
+        self
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the byte range of the attribute, block or expression in the parsed input which
+    /// caused the error, if available.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hcl_edit::structure::Body;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, Debug)]
+    /// struct Config {
+    ///     port: u16,
+    /// }
+    ///
+    /// let input = "port = \"http\"\n";
+    /// let body: Body = input.parse().unwrap();
+    ///
+    /// let err = hcl_edit::de::from_body::<Config>(body).unwrap_err();
+    /// let span = err.span().unwrap();
+    ///
+    /// assert_eq!(&input[span], "\"http\"");
+    /// ```
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.span.clone()
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Error::new(msg)
+    }
+}
+
+/// Deserialize an instance of type `T` from a [`Body`].
+///
+/// See the [module-level documentation](self) for how the body is mapped to `T`.
+///
+/// # Errors
+///
+/// This function fails with an error if the body does not match the structure of `T`.
+pub fn from_body<T>(body: Body) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(Node::Map(body_nodes(body)))
+}
+
+/// Deserialize an instance of type `T` from an [`Expression`].
+///
+/// # Errors
+///
+/// This function fails with an error if the expression does not match the structure of `T`.
+pub fn from_expression<T>(expr: Expression) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(ExpressionDeserializer::new(expr))
+}
+
+type Nodes = VecMap<String, (Option<Range<usize>>, Node)>;
+
+// The JSON representation of a body, see the HCL JSON specification for details.
+enum Node {
+    Map(Nodes),
+    Blocks(Vec<(Body, Option<Range<usize>>)>),
+    Expr(Expression),
+}
+
+fn body_nodes(body: Body) -> Nodes {
+    let mut nodes = Nodes::new();
+
+    for structure in body {
+        match structure {
+            Structure::Attribute(attr) => {
+                let span = attr.span();
+                let key = attr.key.into_value().to_string();
+                nodes.insert(key, (span, Node::Expr(attr.value)));
+            }
+            Structure::Block(block) => {
+                let span = block.span();
+                let mut node = Node::Blocks(vec![(block.body, span.clone())]);
+
+                for label in block.labels.into_iter().rev() {
+                    let mut map = Nodes::new();
+                    map.insert(label.as_str().to_owned(), (span.clone(), node));
+                    node = Node::Map(map);
+                }
+
+                let key = block.ident.into_value().to_string();
+                node.merge_into(&mut nodes, key, span);
+            }
+        }
+    }
+
+    nodes
+}
+
+impl Node {
+    fn merge_into(self, nodes: &mut Nodes, key: String, span: Option<Range<usize>>) {
+        match nodes.get_mut(&key) {
+            Some((_, existing)) => existing.merge(self),
+            None => {
+                nodes.insert(key, (span, self));
+            }
+        }
+    }
+
+    fn merge(&mut self, other: Node) {
+        match (self, other) {
+            (Node::Map(lhs), Node::Map(rhs)) => {
+                for (key, (span, node)) in rhs {
+                    node.merge_into(lhs, key, span);
+                }
+            }
+            (Node::Blocks(lhs), Node::Blocks(mut rhs)) => lhs.append(&mut rhs),
+            (lhs, rhs) => *lhs = rhs,
+        }
+    }
+
+    // Unwraps a single block body into a map.
+    fn normalize(self) -> (Node, Option<Range<usize>>) {
+        match self {
+            Node::Blocks(mut bodies) if bodies.len() == 1 => {
+                let (body, span) = bodies.remove(0);
+                (Node::Map(body_nodes(body)), span)
+            }
+            node => (node, None),
+        }
+    }
+}
+
+impl IntoDeserializer<'_, Error> for Node {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+// Deserializer methods that do not need special treatment of maps and blocks.
+macro_rules! forward_to_expression {
+    ($($method:ident$(($($arg:ident: $ty:ty),*))?)*) => {
+        $(
+            fn $method<V>(self, $($($arg: $ty,)*)? visitor: V) -> Result<V::Value, Error>
+            where
+                V: de::Visitor<'de>,
+            {
+                match self {
+                    Node::Expr(expr) => ExpressionDeserializer::new(expr)
+                        .$method($($($arg,)*)? visitor),
+                    node => node.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Node {
+    type Error = Error;
+
+    forward_to_expression! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_unit
+        deserialize_unit_struct(name: &'static str)
+        deserialize_identifier
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.normalize() {
+            (Node::Map(map), span) => visitor
+                .visit_map(MapAccess::new(map))
+                .map_err(|err| err.with_span(span)),
+            (Node::Blocks(bodies), _) => visitor.visit_seq(SeqAccess::new(bodies)),
+            (Node::Expr(expr), _) => ExpressionDeserializer::new(expr).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Node::Expr(expr) => ExpressionDeserializer::new(expr).deserialize_option(visitor),
+            node => visitor.visit_some(node),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Node::Expr(expr) => {
+                ExpressionDeserializer::new(expr).deserialize_newtype_struct(name, visitor)
+            }
+            node => visitor.visit_newtype_struct(node),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Node::Expr(expr) => ExpressionDeserializer::new(expr).deserialize_seq(visitor),
+            // Unlike in `deserialize_any`, a single block is not unwrapped here.
+            Node::Blocks(bodies) => visitor.visit_seq(SeqAccess::new(bodies)),
+            node @ Node::Map(_) => node.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Node::Expr(expr) => ExpressionDeserializer::new(expr).deserialize_map(visitor),
+            node => node.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Node::Expr(expr) => {
+                ExpressionDeserializer::new(expr).deserialize_struct(name, fields, visitor)
+            }
+            node => node.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.normalize() {
+            (Node::Expr(expr), _) => {
+                ExpressionDeserializer::new(expr).deserialize_enum(name, variants, visitor)
+            }
+            (Node::Map(map), span) => visitor
+                .visit_enum(MapAccessDeserializer::new(MapAccess::new(map)))
+                .map_err(|err| err.with_span(span)),
+            (Node::Blocks(_), _) => Err(de::Error::invalid_type(
+                de::Unexpected::Seq,
+                &"a single block or an object",
+            )),
+        }
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+}
+
+struct MapAccess {
+    iter: vecmap::map::IntoIter<String, (Option<Range<usize>>, Node)>,
+    value: Option<(Option<Range<usize>>, Node)>,
+}
+
+impl MapAccess {
+    fn new(nodes: Nodes) -> MapAccess {
+        MapAccess {
+            iter: nodes.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, (span, node))) => {
+                let key = seed
+                    .deserialize(StrDeserializer::<Error>::new(&key))
+                    .map_err(|err| err.with_span(span.clone()))?;
+                self.value = Some((span, node));
+                Ok(Some(key))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let (span, node) = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+
+        seed.deserialize(node).map_err(|err| err.with_span(span))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct SeqAccess {
+    iter: std::vec::IntoIter<(Body, Option<Range<usize>>)>,
+}
+
+impl SeqAccess {
+    fn new(bodies: Vec<(Body, Option<Range<usize>>)>) -> SeqAccess {
+        SeqAccess {
+            iter: bodies.into_iter(),
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for SeqAccess {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((body, span)) => seed
+                .deserialize(Node::Map(body_nodes(body)))
+                .map(Some)
+                .map_err(|err| err.with_span(span)),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
@@ -1,5 +1,5 @@
 use crate::expr::Expression;
-use crate::{Decor, Decorate, Decorated, Ident};
+use crate::{Decor, Decorate, Decorated, Ident, RawString};
 use std::ops::Range;
 
 /// A for expression is a construct for constructing a collection by projecting the items from
@@ -22,6 +22,7 @@ pub struct ForExpr {
     /// be evaluated as normal, while if `false` the element will be skipped.
     pub cond: Option<ForCond>,
 
+    trailing: RawString,
     decor: Decor,
     span: Option<Range<usize>>,
 }
@@ -36,11 +37,24 @@ impl ForExpr {
             value_expr: value_expr.into(),
             grouping: false,
             cond: None,
+            trailing: RawString::default(),
             decor: Decor::default(),
             span: None,
         }
     }
 
+    /// Return a reference to raw trailing decor before the `for` expression's closing bracket.
+    #[inline]
+    pub fn trailing(&self) -> &RawString {
+        &self.trailing
+    }
+
+    /// Set the raw trailing decor before the `for` expression's closing bracket.
+    #[inline]
+    pub fn set_trailing(&mut self, trailing: impl Into<RawString>) {
+        self.trailing = trailing.into();
+    }
+
     pub(crate) fn despan(&mut self, input: &str) {
         self.decor.despan(input);
         self.intro.despan(input);
@@ -54,6 +68,8 @@ impl ForExpr {
         if let Some(cond) = &mut self.cond {
             cond.despan(input);
         }
+
+        self.trailing.despan(input);
     }
 }
 
@@ -64,6 +80,7 @@ impl PartialEq for ForExpr {
             && self.value_expr == other.value_expr
             && self.grouping == other.grouping
             && self.cond == other.cond
+            && self.trailing == other.trailing
     }
 }
 
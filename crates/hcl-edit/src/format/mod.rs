@@ -0,0 +1,384 @@
+//! Rewrite the whitespace of HCL documents into canonical style.
+//!
+//! The canonical style matches the output of `terraform fmt` (or `hclwrite.Format` in the Go HCL
+//! library):
+//!
+//! - Nested blocks and multi-line expressions are indented by two spaces per level.
+//! - The `=` of consecutive single-line attributes is aligned, as are line comments that trail
+//!   consecutive lines.
+//! - Operators are surrounded by single spaces, commas are followed by a single space, and there
+//!   is no whitespace directly inside of brackets and parentheses.
+//!
+//! In addition to that, consecutive blocks are always separated by a blank line.
+//!
+//! Only whitespace is changed: comments, blank lines, the contents of heredocs and the line
+//! structure of expressions are left as they are.
+//!
+//! # Example
+//!
+//! ```
+//! use hcl_edit::format;
+//! use hcl_edit::structure::Body;
+//!
+//! let input = r#"
+//! resource "aws_instance" "web" {
+//! ami = "ami-a1b2c3d4" # The image.
+//! instance_type="t2.micro" # The size.
+//! tags={Name="web"}
+//! }
+//! output "ip" { value = aws_instance.web.public_ip }
+//! "#;
+//!
+//! let mut body: Body = input.parse()?;
+//! format::format_body(&mut body)?;
+//!
+//! let expected = r#"
+//! resource "aws_instance" "web" {
+//!   ami           = "ami-a1b2c3d4" # The image.
+//!   instance_type = "t2.micro"     # The size.
+//!   tags          = { Name = "web" }
+//! }
+//!
+//! output "ip" { value = aws_instance.web.public_ip }
+//! "#;
+//!
+//! assert_eq!(body.to_string(), expected);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#[cfg(test)]
+mod tests;
+mod token;
+
+use self::token::{Kind, Token};
+use crate::parser;
+use crate::structure::Body;
+use crate::{Decorate, RawString};
+
+/// Formats a [`Body`] into canonical style.
+///
+/// This rewrites the whitespace of all structures and expressions within the body, see the
+/// [module-level documentation](self) for the rules that are applied. Comments stay attached to
+/// the structures and expressions they were attached to before.
+///
+/// Span information of the formatted body refers to the formatted output and not to the original
+/// input anymore. Redefined attributes are kept as they are.
+///
+/// # Errors
+///
+/// Returns an error if the body does not encode to valid HCL, for example if the decor of the body
+/// or any of its parts contains something other than whitespace and comments, or if a string
+/// contains template sequences that do not form a valid template. The body is left unformatted
+/// in this case, apart from the blank lines inserted between consecutive blocks.
+pub fn format_body(body: &mut Body) -> Result<(), parser::Error> {
+    separate_blocks(body);
+
+    let input = body.to_string();
+    let mut tokens = token::lex(&input);
+    format_tokens(&mut tokens);
+
+    let mut output = String::with_capacity(input.len());
+
+    for token in &tokens {
+        output.push_str(&" ".repeat(token.spaces_before));
+        output.push_str(token.text);
+    }
+
+    *body = parser::parse_body_allow_redefined(&output)?;
+    Ok(())
+}
+
+// Ensures that there is a blank line between consecutive blocks of a body and all nested bodies.
+// If the second block has leading comments, the blank line is inserted before them.
+fn separate_blocks(body: &mut Body) {
+    let mut prev_is_block = false;
+
+    for mut structure in body.iter_mut() {
+        let is_block = structure.is_block();
+
+        if prev_is_block && is_block {
+            let decor = structure.decor_mut();
+            let prefix = decor.prefix().map_or("", RawString::as_str);
+
+            if !has_blank_line(prefix) {
+                let prefix = format!("\n{prefix}");
+                decor.set_prefix(prefix);
+            }
+        }
+
+        if let Some(block) = structure.as_block_mut() {
+            separate_blocks(&mut block.body);
+        }
+
+        prev_is_block = is_block;
+    }
+}
+
+// Returns `true` if any complete line within the decor prefix consists of whitespace only. The
+// last line of the prefix is not complete since it is followed by the structure itself.
+fn has_blank_line(prefix: &str) -> bool {
+    let mut lines: Vec<&str> = prefix.split('\n').collect();
+    lines.pop();
+    lines.iter().any(|line| line.trim().is_empty())
+}
+
+// A line of tokens which is split into up to three cells:
+//
+// - `lead`: all tokens up to the `=` of a single-line attribute, or the whole line otherwise.
+// - `assign`: the `=` and the attribute value.
+// - `comment`: a trailing line comment.
+//
+// The columns of the `assign` and `comment` cells are aligned across consecutive lines.
+#[derive(Default)]
+struct Line {
+    lead: std::ops::Range<usize>,
+    assign: Option<std::ops::Range<usize>>,
+    comment: Option<usize>,
+}
+
+fn format_tokens(tokens: &mut [Token]) {
+    let lines = lines(tokens);
+    format_indent(tokens, &lines);
+    format_spaces(tokens, &lines);
+    format_cells(tokens, &lines);
+}
+
+fn lines(tokens: &[Token]) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.is_newline() {
+            lines.push(start..i + 1);
+            start = i + 1;
+        }
+    }
+
+    if start < tokens.len() {
+        lines.push(start..tokens.len());
+    }
+
+    lines
+        .into_iter()
+        .map(|mut lead| {
+            let mut line = Line::default();
+
+            if lead.len() > 1 && tokens[lead.end - 1].kind == Kind::Comment {
+                lead.end -= 1;
+                line.comment = Some(lead.end);
+            }
+
+            // Only treat the `=` as an attribute assignment if the value expression is complete
+            // within the line. Values that open brackets span multiple lines and are indented
+            // instead of aligned.
+            if let Some(pos) = (lead.start + 1..lead.end).find(|&i| tokens[i].kind == Kind::Equal) {
+                if net_brackets(&tokens[pos..lead.end]) == 0 {
+                    line.assign = Some(pos..lead.end);
+                    lead.end = pos;
+                }
+            }
+
+            line.lead = lead;
+            line
+        })
+        .collect()
+}
+
+// Sums up the bracket changes of the tokens. Heredoc contents are not taken into account.
+fn net_brackets(tokens: &[Token]) -> isize {
+    let mut net = 0;
+
+    for token in tokens {
+        net += token.kind.bracket_change();
+
+        if token.kind == Kind::OHeredoc {
+            break;
+        }
+    }
+
+    net
+}
+
+fn format_indent(tokens: &mut [Token], lines: &[Line]) {
+    const SPACES_PER_LEVEL: usize = 2;
+
+    // Each entry is the number of brackets that were opened by a line that increased the
+    // indentation level.
+    let mut indents: Vec<isize> = Vec::new();
+
+    for line in lines {
+        if line.lead.is_empty() {
+            continue;
+        }
+
+        let first = line.lead.start;
+
+        if tokens[first].kind == Kind::Newline {
+            tokens[first].spaces_before = 0;
+            continue;
+        }
+
+        let end = line
+            .assign
+            .as_ref()
+            .map_or(line.lead.end, |assign| assign.end);
+        let net = net_brackets(&tokens[first..end]);
+
+        if net > 0 {
+            tokens[first].spaces_before = indents.len() * SPACES_PER_LEVEL;
+            indents.push(net);
+        } else {
+            let mut closed = -net;
+
+            while closed > 0 {
+                let Some(last) = indents.last_mut() else {
+                    break;
+                };
+
+                if closed < *last {
+                    *last -= closed;
+                    closed = 0;
+                } else {
+                    closed -= *last;
+                    indents.pop();
+                }
+            }
+
+            tokens[first].spaces_before = indents.len() * SPACES_PER_LEVEL;
+        }
+    }
+}
+
+fn format_spaces(tokens: &mut [Token], lines: &[Line]) {
+    for line in lines {
+        set_spaces(tokens, line.lead.clone());
+
+        if let Some(assign) = &line.assign {
+            // Separate the `=` from the attribute key.
+            tokens[assign.start].spaces_before = 1;
+            set_spaces(tokens, assign.clone());
+        }
+    }
+}
+
+// Sets the spaces before every token of the cell except the first one.
+fn set_spaces(tokens: &mut [Token], cell: std::ops::Range<usize>) {
+    for i in cell.start + 1..cell.end {
+        let before = (i >= cell.start + 2).then(|| tokens[i - 2].kind);
+        let space = space_after(&tokens[i - 1], before, tokens[i].kind);
+        tokens[i].spaces_before = usize::from(space);
+    }
+}
+
+// Decides whether a space is needed between `subject` and `after`. The `before` token precedes
+// `subject` and is used to tell negation from subtraction.
+//
+// The match arms are checked in order, so arms with identical bodies can not be merged.
+#[allow(clippy::match_same_arms)]
+fn space_after(subject: &Token, before: Option<Kind>, after: Kind) -> bool {
+    let is_open = |kind: Kind| kind.bracket_change() > 0;
+    let is_close = |kind: Kind| kind.bracket_change() < 0;
+
+    match (subject.kind, after) {
+        // No spaces before newlines, commas and ellipses, and none around attribute access dots.
+        (_, Kind::Newline | Kind::Dot | Kind::Comma | Kind::Ellipsis)
+        | (Kind::Dot, _)
+        // Don't split a function name from its arguments or namespace.
+        | (Kind::Ident, Kind::OParen | Kind::DoubleColon)
+        | (Kind::DoubleColon, Kind::Ident) => false,
+        (Kind::Comma, _) => true,
+        // No extra spaces within templates.
+        (Kind::QuotedLit | Kind::StringLit | Kind::OQuote | Kind::OHeredoc, _)
+        | (_, Kind::QuotedLit | Kind::StringLit | Kind::CQuote | Kind::CHeredoc) => false,
+        // The `in` keyword of a for expression may be followed by a tuple: `[for x in [a] : x]`.
+        (Kind::Ident, _) if subject.text == "in" && before == Some(Kind::Ident) => true,
+        (Kind::Ident | Kind::Number, Kind::OBrack) => false,
+        (subject, Kind::OBrack) if is_close(subject) => false,
+        // A minus which does not follow something that could end an expression is a negation.
+        (Kind::Minus, _) => !matches!(
+            before,
+            None | Some(
+                Kind::OParen
+                    | Kind::OBrace
+                    | Kind::OBrack
+                    | Kind::Equal
+                    | Kind::Colon
+                    | Kind::Comma
+                    | Kind::Question
+                    | Kind::Plus
+                    | Kind::Star
+                    | Kind::Slash
+                    | Kind::Percent
+                    | Kind::Minus
+                    | Kind::EqualOp
+                    | Kind::NotEqual
+                    | Kind::GreaterThan
+                    | Kind::GreaterThanEq
+                    | Kind::LessThan
+                    | Kind::LessThanEq
+                    | Kind::And
+                    | Kind::Or
+                    | Kind::Bang
+            )
+        ),
+        (Kind::Bang, _) => false,
+        // Braces have spaces on both sides, except for empty braces: `{}`.
+        (Kind::OBrace, Kind::CBrace) => false,
+        (Kind::OBrace, _) | (_, Kind::CBrace) => true,
+        // Separate a sole object constructor within a template sequence from its braces.
+        (Kind::TemplateInterp | Kind::TemplateControl, Kind::OBrace)
+        | (Kind::CBrace, Kind::TemplateSeqEnd) => true,
+        (Kind::TemplateSeqEnd, Kind::TemplateInterp | Kind::TemplateControl) => false,
+        (subject, after) if is_open(subject) || is_close(after) => false,
+        _ => true,
+    }
+}
+
+fn format_cells(tokens: &mut [Token], lines: &[Line]) {
+    let columns = |tokens: &[Token], range: &std::ops::Range<usize>| -> usize {
+        tokens[range.clone()].iter().map(Token::columns).sum()
+    };
+
+    // Align the `=` of consecutive attributes first, since this moves the trailing comments.
+    for chain in chains(lines, |line| line.assign.is_some()) {
+        let max = chain
+            .iter()
+            .map(|line| columns(tokens, &line.lead))
+            .max()
+            .unwrap_or_default();
+
+        for line in chain {
+            let spaces = max - columns(tokens, &line.lead) + 1;
+            let assign = line.assign.as_ref().unwrap();
+            tokens[assign.start].spaces_before = spaces;
+        }
+    }
+
+    let line_columns = |tokens: &[Token], line: &Line| {
+        columns(tokens, &line.lead)
+            + line
+                .assign
+                .as_ref()
+                .map_or(0, |assign| columns(tokens, assign))
+    };
+
+    for chain in chains(lines, |line| line.comment.is_some()) {
+        let max = chain
+            .iter()
+            .map(|line| line_columns(tokens, line))
+            .max()
+            .unwrap_or_default();
+
+        for line in chain {
+            let spaces = max - line_columns(tokens, line) + 1;
+            tokens[line.comment.unwrap()].spaces_before = spaces;
+        }
+    }
+}
+
+// Splits the lines into runs of consecutive lines that match the predicate.
+fn chains(lines: &[Line], pred: impl Fn(&Line) -> bool) -> impl Iterator<Item = &[Line]> {
+    lines
+        .split(move |line| !pred(line))
+        .filter(|chain| !chain.is_empty())
+}
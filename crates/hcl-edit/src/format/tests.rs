@@ -0,0 +1,277 @@
+use super::format_body;
+use crate::parser;
+use crate::structure::{Attribute, Block, Body};
+use crate::{Ident, Number};
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::Path;
+
+#[track_caller]
+fn format(input: &str) -> String {
+    let mut body: Body = input.parse().unwrap();
+    format_body(&mut body).unwrap();
+    body.to_string()
+}
+
+// The expected output of these tests was produced by `terraform fmt`, except for the blank lines
+// that separate consecutive blocks.
+#[test]
+fn canonical_style() {
+    let input = indoc! {r#"
+        # leading comment
+        a=1
+        bb    =   2 # c1
+        ccc= [1,2,3] // c2
+        d = {
+        foo="bar"
+          "baz" : 1,
+              nested = { x = -1, y = 2-1 }
+        }
+        block "label"   "other"{
+        a=[for x in [1,2]:x*2 if x>1]
+        b ={for k,v in var.m:k=>v...}
+          /* inline */ c = !var.x?-1:1
+          e = f(a,b...)
+          g = "${a}${b} x %{~if c~} y %{else} z %{endif~}"
+          h = <<EOT
+          keep   this ${  a  }
+        EOT
+          i = 1
+          nested {
+          x = 1
+          }
+          another {}
+          other { x = 1 }
+        }
+    "#};
+
+    let expected = indoc! {r#"
+        # leading comment
+        a   = 1
+        bb  = 2         # c1
+        ccc = [1, 2, 3] // c2
+        d = {
+          foo = "bar"
+          "baz" : 1,
+          nested = { x = -1, y = 2 - 1 }
+        }
+        block "label" "other" {
+          a              = [for x in [1, 2] : x * 2 if x > 1]
+          b              = { for k, v in var.m : k => v... }
+          /* inline */ c = !var.x ? -1 : 1
+          e              = f(a, b...)
+          g              = "${a}${b} x %{~if c~} y %{else} z %{endif~}"
+          h              = <<EOT
+          keep   this ${a}
+        EOT
+          i              = 1
+          nested {
+            x = 1
+          }
+
+          another {}
+
+          other { x = 1 }
+        }
+    "#};
+
+    assert_eq!(format(input), expected);
+}
+
+#[test]
+fn multiline_expressions() {
+    let input = indoc! {r"
+        locals {
+        a = merge(
+        {
+        x = 1
+        long_key = 2
+        },
+        var.other,
+        )
+          # comment breaks chain
+          cc = 1
+          dddd = <<-EOT
+            hello ${ var.x }
+              world
+            EOT
+          e = 2
+          f = var.cond ? {
+            a = 1
+          } : {
+            b = 2
+          }
+          j = f(
+            1,  # one
+            22, # two
+          )
+          k = [ -1, - 2, a -b ]
+        }
+    "};
+
+    let expected = indoc! {r"
+        locals {
+          a = merge(
+            {
+              x        = 1
+              long_key = 2
+            },
+            var.other,
+          )
+          # comment breaks chain
+          cc   = 1
+          dddd = <<-EOT
+            hello ${var.x}
+              world
+            EOT
+          e    = 2
+          f = var.cond ? {
+            a = 1
+            } : {
+            b = 2
+          }
+          j = f(
+            1,  # one
+            22, # two
+          )
+          k = [-1, -2, a - b]
+        }
+    "};
+
+    assert_eq!(format(input), expected);
+}
+
+#[test]
+fn separate_blocks() {
+    let input = indoc! {r"
+        a {}
+        b {
+        c {}
+        d = 1
+        e {}
+        # comment
+        f {}
+
+        g {}
+        }
+        h {}
+        /* block comment */
+        i {}
+    "};
+
+    let expected = indoc! {r"
+        a {}
+
+        b {
+          c {}
+          d = 1
+          e {}
+
+          # comment
+          f {}
+
+          g {}
+        }
+
+        h {}
+
+        /* block comment */
+        i {}
+    "};
+
+    assert_eq!(format(input), expected);
+}
+
+#[test]
+fn default_decor() {
+    let body = Body::builder()
+        .attribute(Attribute::new(Ident::new("foo"), Number::from(1)))
+        .attribute(Attribute::new(Ident::new("foobar"), "baz"))
+        .block(
+            Block::builder(Ident::new("block"))
+                .label("label")
+                .attribute(Attribute::new(Ident::new("bar"), true))
+                .block(Block::new(Ident::new("nested"))),
+        )
+        .block(Block::new(Ident::new("empty")))
+        .build();
+
+    let expected = indoc! {r#"
+        foo    = 1
+        foobar = "baz"
+        block "label" {
+          bar = true
+          nested {
+          }
+        }
+
+        empty {
+        }
+    "#};
+
+    let mut formatted = body.clone();
+    format_body(&mut formatted).unwrap();
+
+    assert_eq!(formatted.to_string(), expected);
+    assert_eq!(formatted, body);
+}
+
+#[test]
+fn redefined_attributes() {
+    let mut body = parser::parse_body_allow_redefined("a=1\na  = 2\n").unwrap();
+    format_body(&mut body).unwrap();
+    assert_eq!(body.to_string(), "a = 1\na = 2\n");
+}
+
+// Compares the formatted specsuite and testdata inputs with the output of `hclwrite.Format`,
+// which is committed in `tests/hclwrite`. It was produced with `terraform fmt` (Terraform
+// v1.11.4), except for the empty input, for which `terraform fmt` emits a single newline.
+// Invalid specsuite inputs are skipped.
+#[test]
+fn hclwrite_reference() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let specsuite_dir = manifest_dir.join("../specsuite/tests");
+
+    let mut inputs: Vec<(String, String)> = testdata::load()
+        .unwrap()
+        .into_iter()
+        .map(|test| (format!("testdata-{}", test.name()), test.input))
+        .collect();
+
+    for path in ["empty.hcl", "expressions"].map(|name| specsuite_dir.join(name)) {
+        let paths = if path.is_dir() {
+            fs::read_dir(&path)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .collect()
+        } else {
+            vec![path]
+        };
+
+        for path in paths {
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+
+            if path.extension().is_some_and(|ext| ext == "hcl") && !name.starts_with("invalid-") {
+                let input = fs::read_to_string(&path).unwrap();
+                inputs.push((format!("specsuite-{name}"), input));
+            }
+        }
+    }
+
+    assert_eq!(inputs.len(), 10);
+
+    for (name, input) in inputs {
+        let path = manifest_dir.join("tests/hclwrite").join(&name);
+        let reference = fs::read_to_string(&path).unwrap();
+
+        // Unlike `hclwrite.Format`, consecutive blocks are always separated by a blank line.
+        let mut expected: Body = reference.parse().unwrap();
+        super::separate_blocks(&mut expected);
+        let expected = expected.to_string();
+
+        let formatted = format(&input);
+        assert_eq!(formatted, expected, "{name}");
+        assert_eq!(format(&formatted), formatted, "{name}");
+    }
+}
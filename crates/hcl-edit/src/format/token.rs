@@ -0,0 +1,346 @@
+//! A lexer that splits HCL source into the tokens that the formatter operates on.
+//!
+//! The token kinds mirror the ones of the lexer used by `hclwrite` so that the formatting rules
+//! can be expressed in the same way. Whitespace that separates tokens is dropped, it is
+//! recomputed by the formatter.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Kind {
+    Newline,
+    Comment,
+    Ident,
+    Number,
+    OQuote,
+    CQuote,
+    QuotedLit,
+    StringLit,
+    OHeredoc,
+    CHeredoc,
+    TemplateInterp,
+    TemplateControl,
+    TemplateSeqEnd,
+    OBrace,
+    CBrace,
+    OBrack,
+    CBrack,
+    OParen,
+    CParen,
+    Dot,
+    Comma,
+    Ellipsis,
+    Equal,
+    Colon,
+    DoubleColon,
+    Question,
+    Minus,
+    Bang,
+    Plus,
+    Star,
+    Slash,
+    Percent,
+    EqualOp,
+    NotEqual,
+    LessThan,
+    LessThanEq,
+    GreaterThan,
+    GreaterThanEq,
+    And,
+    Or,
+    Other,
+}
+
+impl Kind {
+    pub(super) fn bracket_change(self) -> isize {
+        match self {
+            Kind::OBrace
+            | Kind::OBrack
+            | Kind::OParen
+            | Kind::TemplateControl
+            | Kind::TemplateInterp => 1,
+            Kind::CBrace | Kind::CBrack | Kind::CParen | Kind::TemplateSeqEnd => -1,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Token<'a> {
+    pub(super) kind: Kind,
+    pub(super) text: &'a str,
+    pub(super) spaces_before: usize,
+}
+
+impl Token<'_> {
+    // Returns `true` for newlines and line comments, which include their terminating newline.
+    pub(super) fn is_newline(&self) -> bool {
+        match self.kind {
+            Kind::Newline => true,
+            Kind::Comment => self.text.ends_with('\n'),
+            _ => false,
+        }
+    }
+
+    pub(super) fn columns(&self) -> usize {
+        self.spaces_before + self.text.chars().count()
+    }
+}
+
+pub(super) fn lex(input: &str) -> Vec<Token<'_>> {
+    let mut lexer = Lexer {
+        input,
+        pos: 0,
+        tokens: Vec::new(),
+    };
+    lexer.expr(false);
+    lexer.tokens
+}
+
+struct Lexer<'a> {
+    input: &'a str,
+    pos: usize,
+    tokens: Vec<Token<'a>>,
+}
+
+impl<'a> Lexer<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn push(&mut self, kind: Kind, len: usize) {
+        let text = &self.input[self.pos..self.pos + len];
+        self.tokens.push(Token {
+            kind,
+            text,
+            spaces_before: 0,
+        });
+        self.pos += len;
+    }
+
+    // Lexes expression tokens until the end of the input. Within a template sequence, lexing
+    // stops after the closing brace of the sequence.
+    fn expr(&mut self, in_sequence: bool) {
+        let mut depth = 0usize;
+
+        while let Some(ch) = self.rest().chars().next() {
+            let rest = self.rest();
+
+            match ch {
+                ' ' | '\t' => self.pos += 1,
+                '\r' if rest.starts_with("\r\n") => self.push(Kind::Newline, 2),
+                '\n' => self.push(Kind::Newline, 1),
+                '#' => self.push(Kind::Comment, line_len(rest)),
+                '/' if rest.starts_with("//") => self.push(Kind::Comment, line_len(rest)),
+                '/' if rest.starts_with("/*") => {
+                    let len = rest.find("*/").map_or(rest.len(), |pos| pos + 2);
+                    self.push(Kind::Comment, len);
+                }
+                '"' => {
+                    self.push(Kind::OQuote, 1);
+                    self.template(true);
+                    if self.rest().starts_with('"') {
+                        self.push(Kind::CQuote, 1);
+                    }
+                }
+                '<' if rest.starts_with("<<") => match heredoc_start(rest) {
+                    Some((len, delimiter)) => {
+                        self.push(Kind::OHeredoc, len);
+                        self.heredoc(delimiter);
+                    }
+                    None => self.push(Kind::Other, 2),
+                },
+                '{' => {
+                    depth += 1;
+                    self.push(Kind::OBrace, 1);
+                }
+                '}' if in_sequence && depth == 0 => {
+                    self.push(Kind::TemplateSeqEnd, 1);
+                    return;
+                }
+                '~' if in_sequence && depth == 0 && rest.starts_with("~}") => {
+                    self.push(Kind::TemplateSeqEnd, 2);
+                    return;
+                }
+                '}' => {
+                    depth = depth.saturating_sub(1);
+                    self.push(Kind::CBrace, 1);
+                }
+                ch if ch.is_ascii_digit() => self.push(Kind::Number, number_len(rest)),
+                ch if ch.is_alphabetic() || ch == '_' => {
+                    let len = rest
+                        .find(|ch: char| !(ch.is_alphanumeric() || ch == '_' || ch == '-'))
+                        .unwrap_or(rest.len());
+                    self.push(Kind::Ident, len);
+                }
+                _ => {
+                    let (kind, len) = operator(rest);
+                    self.push(kind, len);
+                }
+            }
+        }
+    }
+
+    // Lexes the template of a quoted string up to, but excluding, the closing quote.
+    fn template(&mut self, quoted: bool) {
+        let literal_kind = if quoted {
+            Kind::QuotedLit
+        } else {
+            Kind::StringLit
+        };
+
+        let mut len = 0;
+
+        loop {
+            let rest = &self.rest()[len..];
+
+            let Some(ch) = rest.chars().next() else {
+                break;
+            };
+
+            match ch {
+                '"' if quoted => break,
+                '\\' if quoted => {
+                    len += 1 + rest[1..].chars().next().map_or(0, char::len_utf8);
+                }
+                '\n' if !quoted => {
+                    len += 1;
+                    break;
+                }
+                '$' | '%' if rest[1..].starts_with(ch) && rest[2..].starts_with('{') => len += 3,
+                '$' | '%' if rest[1..].starts_with('{') => {
+                    if len > 0 {
+                        self.push(literal_kind, len);
+                        len = 0;
+                    }
+
+                    let kind = if ch == '$' {
+                        Kind::TemplateInterp
+                    } else {
+                        Kind::TemplateControl
+                    };
+
+                    let start_len = if self.rest()[2..].starts_with('~') {
+                        3
+                    } else {
+                        2
+                    };
+                    self.push(kind, start_len);
+                    self.expr(true);
+                }
+                ch => len += ch.len_utf8(),
+            }
+        }
+
+        if len > 0 {
+            self.push(literal_kind, len);
+        }
+    }
+
+    // Lexes the content lines of a heredoc and its closing delimiter.
+    fn heredoc(&mut self, delimiter: &str) {
+        while !self.rest().is_empty() {
+            let line = &self.rest()[..line_len(self.rest())];
+
+            if line.trim() == delimiter {
+                let len = line.trim_end().len();
+                self.push(Kind::CHeredoc, len);
+                return;
+            }
+
+            self.template(false);
+        }
+    }
+}
+
+// Returns the length of the first line in `s`, including the newline character.
+fn line_len(s: &str) -> usize {
+    s.find('\n').map_or(s.len(), |pos| pos + 1)
+}
+
+fn number_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let digits = |start: usize| {
+        bytes[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+
+    let mut len = digits(0);
+
+    if bytes.get(len) == Some(&b'.') && matches!(bytes.get(len + 1), Some(b) if b.is_ascii_digit())
+    {
+        len += 1 + digits(len + 1);
+    }
+
+    if matches!(bytes.get(len), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(len + 1), Some(b'+' | b'-')));
+        let exp = digits(len + 1 + sign);
+
+        if exp > 0 {
+            len += 1 + sign + exp;
+        }
+    }
+
+    len
+}
+
+// Returns the length of the heredoc introducer including the newline, and the delimiter.
+fn heredoc_start(s: &str) -> Option<(usize, &str)> {
+    let start = if s.starts_with("<<-") { 3 } else { 2 };
+    let line = &s[..line_len(s)];
+    let delimiter = line[start..].trim_end();
+
+    if line.ends_with('\n')
+        && !delimiter.is_empty()
+        && delimiter
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '-')
+    {
+        Some((line.len(), delimiter))
+    } else {
+        None
+    }
+}
+
+fn operator(s: &str) -> (Kind, usize) {
+    const OPERATORS: [(&str, Kind); 9] = [
+        ("...", Kind::Ellipsis),
+        ("==", Kind::EqualOp),
+        ("!=", Kind::NotEqual),
+        ("<=", Kind::LessThanEq),
+        (">=", Kind::GreaterThanEq),
+        ("&&", Kind::And),
+        ("||", Kind::Or),
+        ("=>", Kind::Other),
+        ("::", Kind::DoubleColon),
+    ];
+
+    if let Some((op, kind)) = OPERATORS.iter().find(|(op, _)| s.starts_with(op)) {
+        return (*kind, op.len());
+    }
+
+    let ch = s.chars().next().unwrap();
+
+    let kind = match ch {
+        '[' => Kind::OBrack,
+        ']' => Kind::CBrack,
+        '(' => Kind::OParen,
+        ')' => Kind::CParen,
+        '.' => Kind::Dot,
+        ',' => Kind::Comma,
+        '=' => Kind::Equal,
+        ':' => Kind::Colon,
+        '?' => Kind::Question,
+        '-' => Kind::Minus,
+        '!' => Kind::Bang,
+        '+' => Kind::Plus,
+        '*' => Kind::Star,
+        '/' => Kind::Slash,
+        '%' => Kind::Percent,
+        '<' => Kind::LessThan,
+        '>' => Kind::GreaterThan,
+        _ => Kind::Other,
+    };
+
+    (kind, ch.len_utf8())
+}
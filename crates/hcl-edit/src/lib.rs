@@ -16,12 +16,17 @@ extern crate alloc;
 #[macro_use]
 mod macros;
 
+#[cfg(feature = "serde")]
+pub mod de;
 mod encode;
 pub mod expr;
+pub mod format;
 pub mod parser;
 mod raw_string;
 #[doc(hidden)]
 pub mod repr;
+#[cfg(feature = "serde")]
+pub mod ser;
 pub mod structure;
 pub mod template;
 mod util;
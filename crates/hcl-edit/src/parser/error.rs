@@ -1,5 +1,6 @@
-use super::context::Context;
+use super::context::{Context, Expected};
 use std::fmt;
+use std::ops::Range;
 use winnow::{
     error::{ContextError, FromExternalError},
     stream::{AsBytes, Offset},
@@ -37,6 +38,30 @@ impl Error {
     pub fn location(&self) -> &Location {
         &self.inner.location
     }
+
+    /// Returns the byte range of the unexpected token in the input.
+    ///
+    /// The range is empty if the parser unexpectedly reached the end of the input.
+    pub fn span(&self) -> Range<usize> {
+        self.inner.span.clone()
+    }
+
+    /// Returns the full error message.
+    pub fn message(&self) -> &str {
+        &self.inner.message
+    }
+
+    /// Returns a short label describing the error, e.g. `invalid attribute` or `unexpected token`.
+    pub fn label(&self) -> &str {
+        &self.inner.label
+    }
+
+    /// Returns descriptions of the tokens that the parser expected at the error location.
+    ///
+    /// The returned slice is empty if the parser cannot provide any hints.
+    pub fn expected(&self) -> &[String] {
+        &self.inner.expected
+    }
 }
 
 impl std::error::Error for Error {}
@@ -50,8 +75,11 @@ impl fmt::Display for Error {
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct ErrorInner {
     message: String,
+    label: String,
+    expected: Vec<String>,
     line: String,
     location: Location,
+    span: Range<usize>,
 }
 
 impl ErrorInner {
@@ -59,12 +87,17 @@ impl ErrorInner {
     where
         I: AsBytes + Offset,
     {
-        let (line, location) = locate_error(input.as_bytes(), err.input.as_bytes());
+        let remaining_input = err.input.as_bytes();
+        let (line, location) = locate_error(input.as_bytes(), remaining_input);
+        let span = location.offset..location.offset + token_len(remaining_input);
 
         ErrorInner {
             message: err.to_string(),
+            label: err.label(remaining_input.is_empty()),
+            expected: err.expected().map(ToString::to_string).collect(),
             line: String::from_utf8_lossy(line).to_string(),
             location,
+            span,
         }
     }
 
@@ -154,6 +187,13 @@ fn locate_error<'a>(input: &'a [u8], remaining_input: &'a [u8]) -> (&'a [u8], Lo
     )
 }
 
+// Returns the length in bytes of the first UTF-8 encoded char in `input`.
+fn token_len(input: &[u8]) -> usize {
+    (1..=input.len().min(4))
+        .find(|&len| std::str::from_utf8(&input[..len]).is_ok())
+        .unwrap_or(0)
+}
+
 #[derive(Debug)]
 pub(super) struct ParseError<I> {
     input: I,
@@ -170,6 +210,28 @@ impl<I> ParseError<I> {
             cause: None,
         }
     }
+
+    fn expression(&self) -> Option<&'static str> {
+        self.context.iter().find_map(|c| match c {
+            Context::Expression(c) => Some(*c),
+            Context::Expected(_) => None,
+        })
+    }
+
+    fn expected(&self) -> impl Iterator<Item = &Expected> {
+        self.context.iter().filter_map(|c| match c {
+            Context::Expected(c) => Some(c),
+            Context::Expression(_) => None,
+        })
+    }
+
+    fn label(&self, eof: bool) -> String {
+        match self.expression() {
+            Some(expression) => format!("invalid {expression}"),
+            None if eof => String::from("unexpected end of input"),
+            None => String::from("unexpected token"),
+        }
+    }
 }
 
 impl<I> PartialEq for ParseError<I>
@@ -220,19 +282,8 @@ where
 
 impl<I> fmt::Display for ParseError<I> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let expression = self.context.iter().find_map(|c| match c {
-            Context::Expression(c) => Some(c),
-            Context::Expected(_) => None,
-        });
-
-        let expected = self
-            .context
-            .iter()
-            .filter_map(|c| match c {
-                Context::Expected(c) => Some(c),
-                Context::Expression(_) => None,
-            })
-            .collect::<Vec<_>>();
+        let expression = self.expression();
+        let expected = self.expected().collect::<Vec<_>>();
 
         if let Some(expression) = expression {
             write!(f, "invalid {expression}; ")?;
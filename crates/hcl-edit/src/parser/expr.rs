@@ -13,7 +13,7 @@ use crate::{
     expr::{
         Array, BinaryOperator, Expression, ForCond, ForExpr, ForIntro, FuncArgs, FuncCall, Null,
         Object, ObjectKey, ObjectValue, ObjectValueAssignment, ObjectValueTerminator, Parenthesis,
-        Splat, TraversalOperator, UnaryOperator,
+        Splat, TraversalOperator, UnaryOp, UnaryOperator,
     },
     template::HeredocTemplate,
     Decorate, Decorated, Formatted, Ident, RawString, SetSpan, Spanned,
@@ -224,16 +224,44 @@ fn unary_op<'i, 's>(
     state: &'s RefCell<ExprParseState>,
 ) -> impl Parser<Input<'i>, (), ParseError<Input<'i>>> + 's {
     move |input: Input<'i>| {
-        preceded(
-            (spanned(unary_operator.map(Spanned::new)), sp.span())
-                .map(|(operator, span)| state.borrow_mut().on_unary_op(operator, span)),
-            expr_term(state),
+        (
+            spanned(unary_operator.map(Spanned::new)),
+            prefix_decorated(sp, unary_operand),
         )
-        .void()
-        .parse_next(input)
+            .map(|(operator, expr)| {
+                state
+                    .borrow_mut()
+                    .on_expr_term(UnaryOp::new(operator, expr));
+            })
+            .parse_next(input)
     }
 }
 
+// Traversal operators bind tighter than unary operators, e.g. `!var.enabled` negates the result of
+// the traversal. The operand is thus parsed into a separate state which is not affected by a
+// following conditional or binary operation.
+fn unary_operand(input: Input) -> IResult<Input, Expression> {
+    let state = RefCell::new(ExprParseState::default());
+    let (mut input, span) = expr_term(&state).span().parse_next(input)?;
+    state.borrow_mut().on_span(span);
+
+    loop {
+        let (remaining_input, suffix) = sp.span().parse_next(input)?;
+
+        match take::<_, _, ParseError<_>>(2usize).parse_next(remaining_input) {
+            // See `expr_inner` for why `..` must not be parsed as traversal operator.
+            Ok((_, b"..")) => break,
+            Ok((_, [b'.' | b'[', _])) => {
+                state.borrow_mut().on_ws(suffix);
+                (input, ()) = traversal(&state).parse_next(remaining_input)?;
+            }
+            _ => break,
+        }
+    }
+
+    Ok((input, state.into_inner().into_expr()))
+}
+
 fn unary_operator(input: Input) -> IResult<Input, UnaryOperator> {
     dispatch! {any;
         b'-' => success(UnaryOperator::Neg),
@@ -369,17 +397,21 @@ fn for_object_expr<'i, 's>(
             ),
             opt(b"..."),
             opt(for_cond),
+            raw_string(ws),
         )
-            .map(|(intro, (key_expr, value_expr), grouping, cond)| {
-                let mut expr = ForExpr::new(intro, value_expr);
-                expr.key_expr = Some(key_expr);
-                expr.grouping = grouping.is_some();
-                expr.cond = cond;
-
-                state
-                    .borrow_mut()
-                    .on_expr_term(Expression::ForExpr(Box::new(expr)));
-            })
+            .map(
+                |(intro, (key_expr, value_expr), grouping, cond, trailing)| {
+                    let mut expr = ForExpr::new(intro, value_expr);
+                    expr.key_expr = Some(key_expr);
+                    expr.grouping = grouping.is_some();
+                    expr.cond = cond;
+                    expr.set_trailing(trailing);
+
+                    state
+                        .borrow_mut()
+                        .on_expr_term(Expression::ForExpr(Box::new(expr)));
+                },
+            )
             .parse_next(input)
     }
 }
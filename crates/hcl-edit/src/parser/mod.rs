@@ -14,8 +14,14 @@ mod tests;
 mod trivia;
 
 pub use self::error::{Error, Location};
-use self::{error::ParseError, expr::expr, structure::body, template::template};
+use self::{
+    error::ParseError,
+    expr::expr,
+    structure::{body, recovering_body},
+    template::template,
+};
 use crate::{expr::Expression, structure::Body, template::Template};
+use std::cell::RefCell;
 use winnow::{stream::Located, Parser};
 
 type Input<'a> = Located<&'a [u8]>;
@@ -28,11 +34,99 @@ type IResult<I, O, E = ParseError<I>> = winnow::IResult<I, O, E>;
 ///
 /// Returns an error if the input does not resemble a valid HCL body.
 pub fn parse_body(input: &str) -> Result<Body, Error> {
-    let mut body = parse_complete(input, body)?;
+    let mut body = parse_complete(input, body(false))?;
     body.despan(input);
     Ok(body)
 }
 
+/// Parse an input into a [`Body`](crate::structure::Body) without rejecting redefined attributes.
+///
+/// The HCL specification requires attribute keys to be unique within a body, which is enforced by
+/// [`parse_body`]. This function keeps all definitions of a redefined attribute in the returned
+/// body instead, leaving it up to the caller how to resolve them.
+///
+/// # Example
+///
+/// ```
+/// use hcl_edit::parser;
+///
+/// let input = "foo = 1\nfoo = 2\n";
+///
+/// assert!(parser::parse_body(input).is_err());
+///
+/// let body = parser::parse_body_allow_redefined(input)?;
+/// assert_eq!(body.attributes().filter(|attr| attr.has_key("foo")).count(), 2);
+/// # Ok::<(), parser::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the input does not resemble a valid HCL body.
+pub fn parse_body_allow_redefined(input: &str) -> Result<Body, Error> {
+    let mut body = parse_complete(input, body(true))?;
+    body.despan(input);
+    Ok(body)
+}
+
+/// Parse an input into a [`Body`](crate::structure::Body) while recovering from syntax errors.
+///
+/// Instead of stopping at the first syntax error, the parser skips over invalid attributes and
+/// blocks and continues with the next structure. Blocks with a valid header keep all valid
+/// structures of their body. This is useful for editor integrations where a single typo should
+/// not hide the rest of the file.
+///
+/// Returns the best-effort body together with all errors that were encountered, in the order in
+/// which they appear in the input. The list of errors is empty if the input is a valid HCL body.
+///
+/// # Example
+///
+/// ```
+/// use hcl_edit::parser;
+///
+/// let input = r#"
+/// a = 1
+/// b = [2,
+/// c = 3
+///
+/// block {
+///   d = )
+///   e = 4
+/// }
+/// "#;
+///
+/// let (body, errors) = parser::parse_body_recovering(input);
+///
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[0].location().line(), 4);
+/// assert_eq!(errors[1].location().line(), 7);
+///
+/// assert!(body.has_attribute("a"));
+/// assert!(body.has_attribute("c"));
+/// assert!(body.get_blocks("block").next().unwrap().body.has_attribute("e"));
+/// ```
+pub fn parse_body_recovering(input: &str) -> (Body, Vec<Error>) {
+    let errors = RefCell::new(Vec::new());
+    let located = Input::new(input.as_bytes());
+
+    let mut body = match recovering_body(&errors, false).parse(located) {
+        Ok(body) => body,
+        Err(err) => {
+            errors.borrow_mut().push(err);
+            Body::new()
+        }
+    };
+
+    body.despan(input);
+
+    let errors = errors
+        .into_inner()
+        .iter()
+        .map(|err| Error::from_parse_error(&located, err))
+        .collect();
+
+    (body, errors)
+}
+
 /// Parse an input into an [`Expression`](crate::expr::Expression).
 ///
 /// # Errors
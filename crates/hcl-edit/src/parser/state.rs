@@ -1,8 +1,5 @@
 use crate::{
-    expr::{
-        BinaryOp, BinaryOperator, Conditional, Expression, Traversal, TraversalOperator, UnaryOp,
-        UnaryOperator,
-    },
+    expr::{BinaryOp, BinaryOperator, Conditional, Expression, Traversal, TraversalOperator},
     structure::{Body, Structure},
     Decorate, Decorated, RawString, SetSpan, Spanned,
 };
@@ -11,6 +8,7 @@ use std::ops::Range;
 
 #[derive(Debug, Default)]
 pub(super) struct BodyParseState<'a> {
+    allow_redefined: bool,
     attribute_keys: FnvHashSet<&'a str>,
     current: Option<Structure>,
     structures: Vec<Structure>,
@@ -18,8 +16,19 @@ pub(super) struct BodyParseState<'a> {
 }
 
 impl<'a> BodyParseState<'a> {
+    pub(super) fn new(allow_redefined: bool) -> BodyParseState<'a> {
+        BodyParseState {
+            allow_redefined,
+            ..Default::default()
+        }
+    }
+
+    pub(super) fn allow_redefined(&self) -> bool {
+        self.allow_redefined
+    }
+
     pub(super) fn is_redefined(&mut self, key: &'a str) -> bool {
-        !self.attribute_keys.insert(key)
+        !self.attribute_keys.insert(key) && !self.allow_redefined
     }
 
     pub(super) fn on_ws(&mut self, span: Range<usize>) {
@@ -50,6 +59,12 @@ impl<'a> BodyParseState<'a> {
         self.structures.push(current);
     }
 
+    // Discards the pending structure and whitespace after the parser skipped over invalid input.
+    pub(super) fn on_recover(&mut self) {
+        self.current = None;
+        self.ws = None;
+    }
+
     pub(super) fn into_body(self) -> Body {
         Body::from_vec_unchecked(self.structures)
     }
@@ -57,7 +72,6 @@ impl<'a> BodyParseState<'a> {
 
 #[derive(Debug, Default)]
 pub(super) struct ExprParseState {
-    unary: Option<Spanned<UnaryOperator>>,
     current: Option<Expression>,
     ws: Option<Range<usize>>,
 }
@@ -79,9 +93,6 @@ impl ExprParseState {
 
     pub(super) fn on_expr_term(&mut self, expr: impl Into<Expression>) {
         let mut expr = expr.into();
-        if let Some(operator) = self.unary.take() {
-            expr = Expression::UnaryOp(Box::new(UnaryOp::new(operator, expr)));
-        }
 
         if let Some(prefix) = self.ws.take() {
             expr.decor_mut().set_prefix(RawString::from_span(prefix));
@@ -90,11 +101,6 @@ impl ExprParseState {
         self.current = Some(expr);
     }
 
-    pub(super) fn on_unary_op(&mut self, operator: Spanned<UnaryOperator>, ws_span: Range<usize>) {
-        self.unary = Some(operator);
-        self.on_ws(ws_span);
-    }
-
     pub(super) fn on_traversal(&mut self, operators: Vec<Decorated<TraversalOperator>>) {
         let mut expr_term = self.current.take().unwrap();
 
@@ -1,5 +1,6 @@
 use super::{
     context::{cut_char, cut_str_ident, Context, Expected},
+    error::ParseError,
     expr::expr,
     repr::{decorated, prefix_decorated, suffix_decorated},
     state::BodyParseState,
@@ -17,39 +18,224 @@ use std::cell::RefCell;
 use winnow::{
     ascii::line_ending,
     combinator::{alt, cut_err, delimited, eof, fail, opt, peek, preceded, repeat, terminated},
-    stream::Location,
+    error::ErrMode,
+    stream::{AsBytes, Location, Stream},
     token::{any, one_of},
     Parser,
 };
 
-pub(super) fn body(input: Input) -> IResult<Input, Body> {
-    let state = RefCell::new(BodyParseState::default());
-
-    let (input, (span, suffix)) = (
-        void(repeat(
-            0..,
-            terminated(
-                (
-                    ws.span().map(|span| state.borrow_mut().on_ws(span)),
-                    structure(&state),
-                    (sp, opt(line_comment))
-                        .span()
-                        .map(|span| state.borrow_mut().on_ws(span)),
-                ),
-                cut_err(alt((line_ending, eof)).map(|_| state.borrow_mut().on_line_ending()))
-                    .context(Context::Expected(Expected::Description("newline")))
-                    .context(Context::Expected(Expected::Description("eof"))),
+// Parses a body. If `allow_redefined` is `true`, redefined attribute keys within the body and
+// all nested block bodies are not rejected.
+pub(super) fn body<'i>(allow_redefined: bool) -> impl FnMut(Input<'i>) -> IResult<Input<'i>, Body> {
+    move |input: Input<'i>| {
+        let state = RefCell::new(BodyParseState::new(allow_redefined));
+
+        let (input, (span, suffix)) = (
+            void(repeat(0.., line_structure(&state))).span(),
+            raw_string(ws),
+        )
+            .parse_next(input)?;
+
+        let mut body = state.into_inner().into_body();
+        body.set_span(span);
+        body.decor_mut().set_suffix(suffix);
+        Ok((input, body))
+    }
+}
+
+// Parses a body like `body`, but instead of failing on the first invalid structure, the error is
+// collected and the parser skips ahead to the next structure.
+//
+// If `nested` is `true`, parsing stops in front of the closing brace of the enclosing block.
+// Otherwise, the whole input is consumed.
+pub(super) fn recovering_body<'i, 'e>(
+    errors: &'e RefCell<Vec<ParseError<Input<'i>>>>,
+    nested: bool,
+) -> impl FnMut(Input<'i>) -> IResult<Input<'i>, Body> + 'e {
+    move |mut input: Input<'i>| {
+        let state = RefCell::new(BodyParseState::default());
+        let start = input.location();
+
+        loop {
+            let (remaining_input, leading_ws) = ws.recognize().parse_next(input)?;
+            let remaining = remaining_input.as_bytes();
+
+            if remaining.is_empty() || (nested && remaining[0] == b'}') {
+                break;
+            }
+
+            let err = match line_structure(&state).parse_next(input) {
+                Ok((remaining_input, ())) => {
+                    input = remaining_input;
+                    continue;
+                }
+                Err(ErrMode::Backtrack(err) | ErrMode::Cut(err)) => err,
+                Err(ErrMode::Incomplete(_)) => ParseError::new(remaining_input),
+            };
+
+            // Blocks whose header is valid are recovered by parsing their body in recovering mode.
+            // The original error is only kept if it was not reported while recovering the body.
+            let errors_len = errors.borrow().len();
+
+            if let Ok((remaining_input, ())) = recovering_block(&state, errors).parse_next(input) {
+                if errors.borrow().len() == errors_len {
+                    errors.borrow_mut().push(err);
+                }
+                input = remaining_input;
+            } else {
+                errors.borrow_mut().truncate(errors_len);
+                errors.borrow_mut().push(err);
+                state.borrow_mut().on_recover();
+                let indent = leading_ws.iter().rev().take_while(|&&b| b != b'\n').count();
+                let skip = skip_structure(remaining, indent, nested);
+                input = remaining_input.next_slice(skip).0;
+            }
+        }
+
+        let end = input.location();
+        let (input, suffix) = raw_string(ws).parse_next(input)?;
+        let mut body = state.into_inner().into_body();
+        body.set_span(start..end);
+        body.decor_mut().set_suffix(suffix);
+        Ok((input, body))
+    }
+}
+
+fn recovering_block<'i, 's, 'e>(
+    state: &'s RefCell<BodyParseState<'i>>,
+    errors: &'e RefCell<Vec<ParseError<Input<'i>>>>,
+) -> impl FnMut(Input<'i>) -> IResult<Input<'i>, ()> + 's
+where
+    'e: 's,
+{
+    move |input: Input<'i>| {
+        let (input, prefix) = ws.span().parse_next(input)?;
+        let start = input.location();
+        let (input, ident) = suffix_decorated(ident, sp).parse_next(input)?;
+        let (input, labels) = block_labels(input)?;
+        let (input, mut body) = delimited(
+            b'{',
+            prefix_decorated(
+                (sp, opt(line_comment)),
+                preceded(line_ending, recovering_body(errors, true)),
             ),
-        ))
-        .span(),
-        raw_string(ws),
-    )
+            b'}',
+        )
         .parse_next(input)?;
+        let end = input.location();
+        let (input, suffix) = (sp, opt(line_comment)).span().parse_next(input)?;
+        let (input, _) = alt((line_ending, eof)).parse_next(input)?;
+
+        body.set_prefer_oneline(false);
+        let mut block = Block::new(ident);
+        block.labels = labels;
+        block.body = body;
+        block.set_span(start..end);
 
-    let mut body = state.into_inner().into_body();
-    body.set_span(span);
-    body.decor_mut().set_suffix(suffix);
-    Ok((input, body))
+        let mut state = state.borrow_mut();
+        state.on_ws(prefix);
+        state.on_structure(Structure::Block(block));
+        state.on_ws(suffix);
+        state.on_line_ending();
+        Ok((input, ()))
+    }
+}
+
+// Returns the number of bytes to skip to get past an invalid structure at the start of `input`.
+// `indent` is the indentation of the line on which the structure starts.
+//
+// This skips to the end of the line, unless a bracket that was opened on that line is still
+// unclosed, in which case the following lines are skipped as well until the bracket is closed.
+// Skipping stops early in front of lines that look like the start of the next structure at the
+// same or a lower indentation, or like the closing brace of an enclosing block.
+fn skip_structure(input: &[u8], indent: usize, nested: bool) -> usize {
+    let mut depth = 0usize;
+    let mut index = 0;
+
+    while index < input.len() {
+        match input[index] {
+            b'"' => {
+                index += 1;
+                while index < input.len() && !matches!(input[index], b'"' | b'\n') {
+                    if input[index] == b'\\' {
+                        index += 1;
+                    }
+                    index += 1;
+                }
+
+                // Unterminated string, continue with the line ending.
+                if index < input.len() && input[index] == b'\n' {
+                    continue;
+                }
+            }
+            b'#' => {
+                index += line_len(&input[index..]);
+                continue;
+            }
+            b'/' if input[index..].starts_with(b"//") => {
+                index += line_len(&input[index..]);
+                continue;
+            }
+            b'/' if input[index..].starts_with(b"/*") => {
+                index += input[index..]
+                    .windows(2)
+                    .position(|w| w == b"*/")
+                    .map_or(input.len() - index, |pos| pos + 1);
+            }
+            b'{' | b'[' | b'(' => depth += 1,
+            b'}' if depth == 0 && nested => return index,
+            b'}' | b']' | b')' => depth = depth.saturating_sub(1),
+            b'\n' => {
+                let next_line = &input[index + 1..];
+                let next_indent = next_line
+                    .iter()
+                    .take_while(|b| matches!(b, b' ' | b'\t'))
+                    .count();
+
+                let stop = depth == 0
+                    || match next_line.get(next_indent) {
+                        Some(&b) if is_id_start(b) => next_indent <= indent,
+                        Some(b'}') => next_indent < indent,
+                        _ => false,
+                    };
+
+                if stop {
+                    return index + 1;
+                }
+            }
+            _ => {}
+        }
+
+        index += 1;
+    }
+
+    input.len()
+}
+
+// Returns the length of the line at the start of `input`, excluding the line ending.
+fn line_len(input: &[u8]) -> usize {
+    input
+        .iter()
+        .position(|&b| b == b'\n')
+        .unwrap_or(input.len())
+}
+
+fn line_structure<'i, 's>(
+    state: &'s RefCell<BodyParseState<'i>>,
+) -> impl Parser<Input<'i>, (), ParseError<Input<'i>>> + 's {
+    terminated(
+        (
+            ws.span().map(|span| state.borrow_mut().on_ws(span)),
+            structure(state),
+            (sp, opt(line_comment))
+                .span()
+                .map(|span| state.borrow_mut().on_ws(span)),
+        ),
+        cut_err(alt((line_ending, eof)).map(|_| state.borrow_mut().on_line_ending()))
+            .context(Context::Expected(Expected::Description("newline")))
+            .context(Context::Expected(Expected::Description("eof"))),
+    )
+    .void()
 }
 
 fn structure<'i, 's>(
@@ -63,6 +249,8 @@ fn structure<'i, 's>(
         let (input, suffix) = raw_string(sp).parse_next(input)?;
         let (input, ch) = peek(any).parse_next(input)?;
 
+        let allow_redefined = state.borrow().allow_redefined();
+
         let (input, mut structure) = match ch {
             b'=' => {
                 if state.borrow_mut().is_redefined(ident) {
@@ -81,7 +269,7 @@ fn structure<'i, 's>(
                 (input, Structure::Attribute(attr))
             }
             b'{' => {
-                let (input, body) = block_body(input)?;
+                let (input, body) = block_body(allow_redefined).parse_next(input)?;
                 let mut ident = Decorated::new(Ident::new_unchecked(ident));
                 ident.decor_mut().set_suffix(suffix);
                 let mut block = Block::new(ident);
@@ -90,7 +278,7 @@ fn structure<'i, 's>(
             }
             ch if ch == b'"' || is_id_start(ch) => {
                 let (input, labels) = block_labels(input)?;
-                let (input, body) = block_body(input)?;
+                let (input, body) = block_body(allow_redefined).parse_next(input)?;
                 let mut ident = Decorated::new(Ident::new_unchecked(ident));
                 ident.decor_mut().set_suffix(suffix);
                 let mut block = Block::new(ident);
@@ -136,30 +324,35 @@ fn block_label(input: Input) -> IResult<Input, BlockLabel> {
     .parse_next(input)
 }
 
-fn block_body(input: Input) -> IResult<Input, Body> {
-    let attribute =
-        (suffix_decorated(ident, sp), attribute_expr).map(|(key, expr)| Attribute::new(key, expr));
-
-    delimited(
-        cut_char('{'),
-        alt((
-            // Multiline block.
-            prefix_decorated((sp, opt(line_comment)), preceded(line_ending, body)),
-            // One-line block.
-            (opt(decorated(sp, attribute, sp)), raw_string(sp)).map(|(attr, suffix)| {
-                let mut body = Body::new();
-                body.set_prefer_oneline(true);
-                body.decor_mut().set_suffix(suffix);
-                if let Some(attr) = attr {
-                    body.push(attr);
-                }
-                body
-            }),
-        )),
-        cut_char('}')
-            .context(Context::Expression("block body"))
-            .context(Context::Expected(Expected::Char('\n')))
-            .context(Context::Expected(Expected::Description("identifier"))),
-    )
-    .parse_next(input)
+fn block_body<'i>(allow_redefined: bool) -> impl FnMut(Input<'i>) -> IResult<Input<'i>, Body> {
+    move |input: Input<'i>| {
+        let attribute = (suffix_decorated(ident, sp), attribute_expr)
+            .map(|(key, expr)| Attribute::new(key, expr));
+
+        delimited(
+            cut_char('{'),
+            alt((
+                // Multiline block.
+                prefix_decorated(
+                    (sp, opt(line_comment)),
+                    preceded(line_ending, body(allow_redefined)),
+                ),
+                // One-line block.
+                (opt(decorated(sp, attribute, sp)), raw_string(sp)).map(|(attr, suffix)| {
+                    let mut body = Body::new();
+                    body.set_prefer_oneline(true);
+                    body.decor_mut().set_suffix(suffix);
+                    if let Some(attr) = attr {
+                        body.push(attr);
+                    }
+                    body
+                }),
+            )),
+            cut_char('}')
+                .context(Context::Expression("block body"))
+                .context(Context::Expected(Expected::Char('\n')))
+                .context(Context::Expected(Expected::Description("identifier"))),
+        )
+        .parse_next(input)
+    }
 }
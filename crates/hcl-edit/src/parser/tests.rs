@@ -1,7 +1,7 @@
 use super::expr::expr;
-use super::parse_complete;
 use super::structure::body;
 use super::template::template;
+use super::{parse_body_recovering, parse_complete};
 use crate::{expr::Expression, Formatted, Number};
 use indoc::indoc;
 use pretty_assertions::assert_eq;
@@ -60,6 +60,10 @@ fn roundtrip_expr() {
         "foo(bar...)",
         "foo(bar,)",
         "foo( )",
+        "! var.enabled",
+        "-a[0] + 1",
+        "{ for k, v in var.m : k => v... }",
+        "{ for k, v in var.m : k => v... if v != null }",
     ];
 
     for input in inputs {
@@ -106,10 +110,45 @@ fn roundtrip_body() {
     }
 
     for input in inputs {
-        assert_roundtrip!(input, body);
+        assert_roundtrip!(input, body(false));
     }
 }
 
+#[test]
+fn unary_op_precedence() {
+    let parsed = parse_complete("!var.enabled ? 1 : 0", expr).unwrap();
+    let cond = parsed.as_conditional().unwrap();
+    let unary = cond.cond_expr.as_unary_op().unwrap();
+    assert!(unary.expr.is_traversal());
+
+    let parsed = parse_complete("-a[0] + 1", expr).unwrap();
+    let binary = parsed.as_binary_op().unwrap();
+    let unary = binary.lhs_expr.as_unary_op().unwrap();
+    assert!(unary.expr.is_traversal());
+}
+
+#[test]
+fn dedent_heredoc() {
+    let input = indoc! {r"
+        <<-EOT
+            ${foo}
+          %{ for x in xs }
+            ${x}
+          %{ endfor }
+          EOT"};
+
+    let mut parsed = parse_complete(input, expr).unwrap();
+    parsed.despan(input);
+
+    let heredoc = parsed.as_heredoc_template().unwrap();
+    assert_eq!(heredoc.indent(), Some(2));
+    assert_eq!(
+        heredoc.template.to_string(),
+        "  ${foo}\n%{ for x in xs }\n  ${x}\n%{ endfor }\n"
+    );
+    assert_eq!(parsed.to_string(), input);
+}
+
 #[test]
 fn roundtrip_template() {
     let inputs = [
@@ -144,3 +183,66 @@ fn invalid_exprs() {
         );
     }
 }
+
+#[test]
+fn recover_body() {
+    let input = indoc! {r#"
+        a = 1
+        b = [2,
+        c = 3
+        redefined = 1
+        redefined = 2
+        "unterminated = 4
+        block "label" {
+          nested {
+            d = )
+            e = 5 # comment
+          }
+          f = 6
+        }
+        g = 7 h = 8
+        }
+        broken { i = [ }
+        j = 9
+    "#};
+
+    let (body, errors) = parse_body_recovering(input);
+
+    let locations: Vec<_> = errors
+        .iter()
+        .map(|err| (err.location().line(), err.location().column()))
+        .collect();
+    assert_eq!(
+        locations,
+        [(3, 3), (5, 1), (6, 1), (9, 9), (14, 7), (15, 1), (16, 16)]
+    );
+
+    let keys: Vec<_> = body.attributes().map(|attr| attr.key.as_str()).collect();
+    assert_eq!(keys, ["a", "c", "redefined", "j"]);
+
+    let block = body.get_blocks("block").next().unwrap();
+    assert!(block.body.has_attribute("f"));
+    let nested = block.body.get_blocks("nested").next().unwrap();
+    assert!(nested.body.has_attribute("e"));
+
+    assert_eq!(nested.body.to_string(), "    e = 5 # comment\n  ");
+
+    let err = &errors[4];
+    assert_eq!(err.span(), 143..144);
+    assert_eq!(err.label(), "unexpected token");
+    assert_eq!(err.expected(), ["newline", "eof"]);
+
+    let err = &errors[1];
+    assert_eq!(err.label(), "invalid attribute");
+    assert_eq!(
+        err.expected(),
+        ["unique attribute key; found redefined attribute"]
+    );
+
+    // Valid input does not produce any errors.
+    for test in testdata::load().unwrap() {
+        let (body, errors) = parse_body_recovering(&test.input);
+        assert!(errors.is_empty());
+        assert_eq!(body.to_string(), test.input);
+    }
+}
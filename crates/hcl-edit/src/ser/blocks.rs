@@ -0,0 +1,316 @@
+use super::in_internal_serialization;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops;
+use vecmap::VecMap;
+
+// The same markers are used by the serializer of the `hcl` crate.
+pub(super) const BLOCK_MARKER: &str = "$hcl::Block";
+pub(super) const LABELED_BLOCK_MARKER: &str = "$hcl::LabeledBlock";
+
+/// A transparent wrapper type which hints [`to_body`][super::to_body] to serialize `T` as an HCL
+/// block.
+///
+/// When passed to any other serializer, a `Block<T>` serializes exactly like `T`.
+///
+/// A `Block<T>` can only be used in the *value position of a map-like structure*, e.g. as the
+/// value type of a map or as the value of a struct field. The serialized block's identifier will
+/// be the respective map key or struct field name.
+///
+/// The wrapped `T` must either be *map-like* (e.g. a map or struct), or *sequence-like* (e.g. a
+/// vector) with map-like elements. In the latter case, multiple blocks with the same identifier
+/// are produced.
+///
+/// For more convenient usage, see the [`block`][super::block] function.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hcl_edit::ser::Block;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Config {
+///     user: Block<Vec<User>>,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     name: &'static str,
+/// }
+///
+/// let config = Config {
+///     user: Block::new(vec![User { name: "john" }, User { name: "jane" }]),
+/// };
+///
+/// let expected = r#"
+/// user {
+///   name = "john"
+/// }
+///
+/// user {
+///   name = "jane"
+/// }
+/// "#.trim_start();
+///
+/// assert_eq!(hcl_edit::ser::to_body(&config)?.to_string(), expected);
+/// #    Ok(())
+/// # }
+/// ```
+pub struct Block<T>(T);
+
+impl<T> Block<T> {
+    /// Create a new `Block<T>` from a `T`.
+    pub fn new(value: T) -> Block<T> {
+        Block(value)
+    }
+
+    /// Consume the `Block` and return the wrapped `T`.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> ops::Deref for Block<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for Block<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Clone for Block<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Block(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for Block<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Block").field(&self.0).finish()
+    }
+}
+
+impl<T> Serialize for Block<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if in_internal_serialization() {
+            serializer.serialize_newtype_struct(BLOCK_MARKER, &self.0)
+        } else {
+            self.0.serialize(serializer)
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Block<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Block)
+    }
+}
+
+/// A transparent wrapper type which hints [`to_body`][super::to_body] to serialize `T` as a
+/// labeled HCL block.
+///
+/// When passed to any other serializer, a `LabeledBlock<T>` serializes exactly like `T`.
+///
+/// Like [`Block<T>`], a `LabeledBlock<T>` can only be used in the *value position of a map-like
+/// structure*. The wrapped `T` must either be *map-like* (e.g. a map or struct), or
+/// *sequence-like* with map-like elements. The keys of the map become the first label of the
+/// blocks, and the values may be another `LabeledBlock<T>` to produce blocks with multiple
+/// labels.
+///
+/// For more convenient usage, see the [`labeled_block`][super::labeled_block] and
+/// [`doubly_labeled_block`][super::doubly_labeled_block] functions.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hcl_edit::ser::LabeledBlock;
+/// use serde::Serialize;
+/// use std::collections::BTreeMap;
+///
+/// #[derive(Serialize)]
+/// struct Config {
+///     user: LabeledBlock<BTreeMap<&'static str, User>>,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     email: &'static str,
+/// }
+///
+/// let config = Config {
+///     user: LabeledBlock::new(BTreeMap::from([
+///         ("jane", User { email: "janedoe@example.com" }),
+///         ("john", User { email: "johndoe@example.com" }),
+///     ])),
+/// };
+///
+/// let expected = r#"
+/// user "jane" {
+///   email = "janedoe@example.com"
+/// }
+///
+/// user "john" {
+///   email = "johndoe@example.com"
+/// }
+/// "#.trim_start();
+///
+/// assert_eq!(hcl_edit::ser::to_body(&config)?.to_string(), expected);
+/// #    Ok(())
+/// # }
+/// ```
+pub struct LabeledBlock<T>(T);
+
+impl<T> LabeledBlock<T> {
+    /// Create a new `LabeledBlock<T>` from a `T`.
+    pub fn new(value: T) -> LabeledBlock<T> {
+        LabeledBlock(value)
+    }
+
+    /// Consume the `LabeledBlock` and return the wrapped `T`.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> ops::Deref for LabeledBlock<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for LabeledBlock<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Clone for LabeledBlock<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        LabeledBlock(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for LabeledBlock<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LabeledBlock").field(&self.0).finish()
+    }
+}
+
+impl<T> Serialize for LabeledBlock<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if in_internal_serialization() {
+            serializer.serialize_newtype_struct(LABELED_BLOCK_MARKER, &self.0)
+        } else {
+            self.0.serialize(serializer)
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for LabeledBlock<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(LabeledBlock)
+    }
+}
+
+/// Hints [`to_body`][super::to_body] to serialize `T` as an HCL block.
+///
+/// This function is intended to be used in the `#[serde(serialize_with)]` attribute and wraps `T`
+/// with a [`Block<T>`].
+///
+/// # Errors
+///
+/// Serialization fails if the type's shape makes it impossible to represent it as an HCL block.
+pub fn block<T, S>(value: T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: serde::Serializer,
+{
+    Block::new(value).serialize(serializer)
+}
+
+/// Hints [`to_body`][super::to_body] to serialize `T` as a labeled HCL block.
+///
+/// This function is intended to be used in the `#[serde(serialize_with)]` attribute and wraps `T`
+/// with a [`LabeledBlock<T>`].
+///
+/// # Errors
+///
+/// Serialization fails if the type's shape makes it impossible to represent it as a labeled HCL
+/// block.
+pub fn labeled_block<T, S>(value: T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: serde::Serializer,
+{
+    LabeledBlock::new(value).serialize(serializer)
+}
+
+/// Hints [`to_body`][super::to_body] to serialize `T` as an HCL block with two labels.
+///
+/// This function is intended to be used in the `#[serde(serialize_with)]` attribute and wraps `T`
+/// and each value of `T` with a [`LabeledBlock<T>`].
+///
+/// # Errors
+///
+/// Serialization fails if the type's shape makes it impossible to represent it as an HCL block
+/// with two labels.
+pub fn doubly_labeled_block<T, K, V, S>(value: T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: IntoIterator<Item = (K, V)>,
+    K: Serialize + Eq,
+    V: Serialize,
+    S: serde::Serializer,
+{
+    let value: VecMap<K, LabeledBlock<V>> = value
+        .into_iter()
+        .map(|(k, v)| (k, LabeledBlock::new(v)))
+        .collect();
+    labeled_block(value, serializer)
+}
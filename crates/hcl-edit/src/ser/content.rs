@@ -0,0 +1,355 @@
+//! An intermediate representation of serialized values.
+//!
+//! Whether a value becomes an attribute, a block or an expression depends on where it appears,
+//! which is only known once the surrounding value has been serialized. Values are therefore
+//! serialized into a `Content` tree first, which is converted into HCL afterwards.
+
+use super::Error;
+use crate::Number;
+use serde::ser::{self, Serialize};
+
+pub(super) enum Content {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Seq(Vec<Content>),
+    Map(Vec<(Content, Content)>),
+    Newtype(&'static str, Box<Content>),
+    UnitVariant(&'static str),
+    NewtypeVariant(&'static str, Box<Content>),
+    TupleVariant(&'static str, Vec<Content>),
+    StructVariant(&'static str, Vec<(Content, Content)>),
+}
+
+pub(super) struct ContentSerializer;
+
+impl ser::Serializer for ContentSerializer {
+    type Ok = Content;
+    type Error = Error;
+
+    type SerializeSeq = SerializeSeq;
+    type SerializeTuple = SerializeSeq;
+    type SerializeTupleStruct = SerializeSeq;
+    type SerializeTupleVariant = SerializeTupleVariant;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeStructVariant;
+
+    fn serialize_bool(self, v: bool) -> Result<Content, Error> {
+        Ok(Content::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Content, Error> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Content, Error> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Content, Error> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Content, Error> {
+        Ok(Content::Number(v.into()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Content, Error> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Content, Error> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Content, Error> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Content, Error> {
+        Ok(Content::Number(v.into()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Content, Error> {
+        self.serialize_f64(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Content, Error> {
+        Ok(Number::from_f64(v).map_or(Content::Null, Content::Number))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Content, Error> {
+        Ok(Content::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Content, Error> {
+        Ok(Content::String(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Content, Error> {
+        Ok(Content::Seq(
+            v.iter().map(|b| Content::Number((*b).into())).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> Result<Content, Error> {
+        Ok(Content::Null)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Content, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Content, Error> {
+        Ok(Content::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Content, Error> {
+        Ok(Content::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Content, Error> {
+        Ok(Content::UnitVariant(variant))
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Content, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(Content::Newtype(name, Box::new(value.serialize(self)?)))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Content, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(Content::NewtypeVariant(
+            variant,
+            Box::new(value.serialize(self)?),
+        ))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeSeq, Error> {
+        Ok(SerializeSeq {
+            elements: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeSeq, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeSeq, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeTupleVariant, Error> {
+        Ok(SerializeTupleVariant {
+            variant,
+            elements: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap, Error> {
+        Ok(SerializeMap {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeMap, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeStructVariant, Error> {
+        Ok(SerializeStructVariant {
+            variant,
+            entries: Vec::with_capacity(len),
+        })
+    }
+}
+
+pub(super) struct SerializeSeq {
+    elements: Vec<Content>,
+}
+
+impl ser::SerializeSeq for SerializeSeq {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.elements.push(value.serialize(ContentSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        Ok(Content::Seq(self.elements))
+    }
+}
+
+impl ser::SerializeTuple for SerializeSeq {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeSeq {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+pub(super) struct SerializeTupleVariant {
+    variant: &'static str,
+    elements: Vec<Content>,
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.elements.push(value.serialize(ContentSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        Ok(Content::TupleVariant(self.variant, self.elements))
+    }
+}
+
+pub(super) struct SerializeMap {
+    entries: Vec<(Content, Content)>,
+    next_key: Option<Content>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.next_key = Some(key.serialize(ContentSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self.next_key.take();
+        let key = key.expect("serialize_value called before serialize_key");
+        self.entries
+            .push((key, value.serialize(ContentSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        Ok(Content::Map(self.entries))
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.entries.push((
+            Content::String(key.to_owned()),
+            value.serialize(ContentSerializer)?,
+        ));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        Ok(Content::Map(self.entries))
+    }
+}
+
+pub(super) struct SerializeStructVariant {
+    variant: &'static str,
+    entries: Vec<(Content, Content)>,
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant {
+    type Ok = Content;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.entries.push((
+            Content::String(key.to_owned()),
+            value.serialize(ContentSerializer)?,
+        ));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Content, Error> {
+        Ok(Content::StructVariant(self.variant, self.entries))
+    }
+}
@@ -0,0 +1,430 @@
+//! Serialize Rust data structures into HCL documents that can be edited further.
+//!
+//! The [`to_body`] function serializes any type implementing [`serde::Serialize`] into a
+//! [`Body`]. Contrary to serializing into a string, the resulting body can be modified, merged
+//! into an existing document or decorated with comments before it is written out.
+//!
+//! The rules for mapping Rust data structures to HCL are the same as for the serializer of the
+//! [`hcl`](https://docs.rs/hcl) crate: at the top level, map-like types are serialized as a body
+//! and each entry becomes an attribute. The [`Block`] and [`LabeledBlock`] wrapper types, and the
+//! [`block`], [`labeled_block`] and [`doubly_labeled_block`] functions can be used to serialize
+//! values as HCL blocks instead. The equivalent types and functions of `hcl::ser` are supported
+//! as well.
+//!
+//! This module is only available if the `serde` feature is enabled.
+//!
+//! # Example
+//!
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use hcl_edit::structure::Body;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct Config {
+//!     #[serde(serialize_with = "hcl_edit::ser::labeled_block")]
+//!     service: Service,
+//! }
+//!
+//! #[derive(Serialize)]
+//! struct Service {
+//!     api: Api,
+//! }
+//!
+//! #[derive(Serialize)]
+//! struct Api {
+//!     port: u16,
+//!     hosts: Vec<&'static str>,
+//! }
+//!
+//! let config = Config {
+//!     service: Service {
+//!         api: Api {
+//!             port: 8080,
+//!             hosts: vec!["a.example.com", "b.example.com"],
+//!         },
+//!     },
+//! };
+//!
+//! let mut body: Body = "# Generated, do not edit.\nversion = 2\n".parse()?;
+//!
+//! body.extend(hcl_edit::ser::to_body(&config)?);
+//!
+//! let expected = r#"
+//! ## Generated, do not edit.
+//! version = 2
+//! service "api" {
+//!   port = 8080
+//!   hosts = [
+//!     "a.example.com",
+//!     "b.example.com"
+//!   ]
+//! }
+//! "#.trim_start();
+//!
+//! assert_eq!(body.to_string(), expected);
+//! #     Ok(())
+//! # }
+//! ```
+
+mod blocks;
+mod content;
+
+pub use self::blocks::{block, doubly_labeled_block, labeled_block, Block, LabeledBlock};
+use self::blocks::{BLOCK_MARKER, LABELED_BLOCK_MARKER};
+use self::content::{Content, ContentSerializer};
+use crate::expr::{Array, Expression, Null, Object, ObjectKey, ObjectValue, ObjectValueTerminator};
+use crate::structure::{self, Attribute, BlockLabel, Body, Structure};
+use crate::util::escape_template;
+use crate::{format, Decorate, Decorated, Ident};
+use hcl_primitives::ident::is_ident;
+use serde::Serialize;
+use std::cell::Cell;
+use std::fmt;
+
+thread_local! {
+    static INTERNAL_SERIALIZATION: Cell<bool> = const { Cell::new(false) };
+}
+
+// Also used by the `hcl` crate, whose block wrapper types emit the same markers while this is
+// `true`.
+#[doc(hidden)]
+pub fn in_internal_serialization() -> bool {
+    INTERNAL_SERIALIZATION.with(Cell::get)
+}
+
+fn with_internal_serialization<R, F: FnOnce() -> R>(f: F) -> R {
+    struct Reset(bool);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            INTERNAL_SERIALIZATION.with(|flag| flag.set(self.0));
+        }
+    }
+
+    let _reset = Reset(INTERNAL_SERIALIZATION.with(|flag| flag.replace(true)));
+    f()
+}
+
+/// The error type returned when serialization fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    message: String,
+}
+
+impl Error {
+    fn new(message: impl fmt::Display) -> Error {
+        Error {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl serde::ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Error::new(msg)
+    }
+}
+
+/// Serialize the given value into a [`Body`].
+///
+/// The returned body is formatted in canonical style using
+/// [`format_body`][crate::format::format_body], see the [module-level documentation](self) for an
+/// example.
+///
+/// # Errors
+///
+/// Serialization fails if the type cannot be represented as an HCL body, for example if it is not
+/// map-like, if a map key is not a valid HCL identifier or if an attribute is defined more than
+/// once within the same body.
+///
+/// Strings are serialized as literals: the template sequences `${` and `%{` are escaped as `$${`
+/// and `%%{`.
+pub fn to_body<T>(value: &T) -> Result<Body, Error>
+where
+    T: ?Sized + Serialize,
+{
+    let content = with_internal_serialization(|| value.serialize(ContentSerializer))?;
+
+    let mut structures = Vec::new();
+    body_structures(content, &mut structures)?;
+
+    let mut body = new_body(structures)?;
+    format::format_body(&mut body).map_err(Error::new)?;
+    Ok(body)
+}
+
+fn body_structures(content: Content, out: &mut Vec<Structure>) -> Result<(), Error> {
+    match content {
+        Content::Map(entries) => {
+            for (key, value) in entries {
+                structures(parse_ident(&label(key)?)?, value, out)?;
+            }
+        }
+        Content::Seq(elements) => {
+            for element in elements {
+                body_structures(element, out)?;
+            }
+        }
+        Content::Newtype(_, value) => body_structures(*value, out)?,
+        Content::NewtypeVariant(variant, value) => structures(parse_ident(variant)?, *value, out)?,
+        Content::TupleVariant(variant, elements) => {
+            out.push(Attribute::new(parse_ident(variant)?, array(elements)?).into());
+        }
+        Content::StructVariant(variant, entries) => {
+            out.push(new_block(parse_ident(variant)?, entries)?.into());
+        }
+        _ => {
+            return Err(Error::new(
+                "value is not map-like and cannot be serialized as body",
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+// Serializes the value of a map entry, which is an attribute unless the value is marked as
+// block.
+fn structures(key: Ident, value: Content, out: &mut Vec<Structure>) -> Result<(), Error> {
+    match value {
+        Content::Newtype(BLOCK_MARKER, value) => blocks(key, *value, out),
+        Content::Newtype(LABELED_BLOCK_MARKER, value) => labeled_blocks(key, *value, out),
+        Content::Newtype(_, value) => structures(key, *value, out),
+        value => {
+            out.push(Attribute::new(key, expression(value)?).into());
+            Ok(())
+        }
+    }
+}
+
+fn blocks(ident: Ident, value: Content, out: &mut Vec<Structure>) -> Result<(), Error> {
+    match value {
+        Content::Map(entries) => out.push(new_block(ident, entries)?.into()),
+        Content::Seq(elements) => {
+            for element in elements {
+                blocks(ident.clone(), element, out)?;
+            }
+        }
+        Content::Newtype(LABELED_BLOCK_MARKER, value) => labeled_blocks(ident, *value, out)?,
+        Content::Newtype(_, value) => blocks(ident, *value, out)?,
+        Content::NewtypeVariant(variant, value) => {
+            let mut structures = Vec::new();
+            self::structures(parse_ident(variant)?, *value, &mut structures)?;
+            out.push(block_from_structures(ident, structures)?.into());
+        }
+        Content::TupleVariant(variant, elements) => {
+            let variant = parse_ident(variant)?;
+            let mut structures = Vec::new();
+
+            for element in elements {
+                blocks(variant.clone(), element, &mut structures)?;
+            }
+
+            out.push(block_from_structures(ident, structures)?.into());
+        }
+        Content::StructVariant(variant, entries) => {
+            let inner = new_block(parse_ident(variant)?, entries)?;
+            out.push(block_from_structures(ident, vec![inner.into()])?.into());
+        }
+        _ => {
+            return Err(Error::new(format!(
+                "value of block `{ident}` is not map-like"
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+fn labeled_blocks(ident: Ident, value: Content, out: &mut Vec<Structure>) -> Result<(), Error> {
+    match value {
+        Content::Map(entries) => {
+            for (key, value) in entries {
+                let label = label(key)?;
+                prefixed_blocks(ident.clone(), value, out, &[label])?;
+            }
+        }
+        Content::Seq(elements) => {
+            for element in elements {
+                labeled_blocks(ident.clone(), element, out)?;
+            }
+        }
+        Content::Newtype(_, value) => labeled_blocks(ident, *value, out)?,
+        Content::NewtypeVariant(variant, value) => {
+            prefixed_blocks(ident, *value, out, &[variant.to_owned()])?;
+        }
+        Content::TupleVariant(variant, elements) => {
+            for element in elements {
+                prefixed_blocks(ident.clone(), element, out, &[variant.to_owned()])?;
+            }
+        }
+        Content::StructVariant(variant, entries) => {
+            for (key, value) in entries {
+                let labels = [variant.to_owned(), label(key)?];
+                prefixed_blocks(ident.clone(), value, out, &labels)?;
+            }
+        }
+        _ => {
+            return Err(Error::new(format!(
+                "value of labeled block `{ident}` is not map-like"
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+// Serializes the value as blocks and adds the labels in front of their existing labels.
+fn prefixed_blocks(
+    ident: Ident,
+    value: Content,
+    out: &mut Vec<Structure>,
+    labels: &[String],
+) -> Result<(), Error> {
+    let mut structures = Vec::new();
+    blocks(ident, value, &mut structures)?;
+
+    for structure in structures {
+        if let Structure::Block(mut block) = structure {
+            block.labels.splice(
+                0..0,
+                labels
+                    .iter()
+                    .map(|label| BlockLabel::from(escape_template(label))),
+            );
+            out.push(block.into());
+        }
+    }
+
+    Ok(())
+}
+
+fn new_block(ident: Ident, entries: Vec<(Content, Content)>) -> Result<structure::Block, Error> {
+    let mut structures = Vec::with_capacity(entries.len());
+    body_structures(Content::Map(entries), &mut structures)?;
+    block_from_structures(ident, structures)
+}
+
+fn block_from_structures(
+    ident: Ident,
+    structures: Vec<Structure>,
+) -> Result<structure::Block, Error> {
+    let mut body = new_body(structures)?;
+    body.set_prefer_oneline(body.is_empty());
+
+    let mut block = structure::Block::new(ident);
+    block.body = body;
+    Ok(block)
+}
+
+// Collects the structures into a body. Unlike `Body::from_iter`, which drops redefined
+// attributes, this fails if an attribute is defined more than once.
+fn new_body(structures: Vec<Structure>) -> Result<Body, Error> {
+    let mut body = Body::with_capacity(structures.len());
+
+    for structure in structures {
+        body.try_push(structure).map_err(|attr| {
+            Error::new(format!(
+                "attribute `{}` is defined more than once",
+                attr.key
+            ))
+        })?;
+    }
+
+    Ok(body)
+}
+
+fn expression(content: Content) -> Result<Expression, Error> {
+    let expr = match content {
+        Content::Null => Expression::Null(Decorated::new(Null)),
+        Content::Bool(b) => Expression::from(b),
+        Content::Number(n) => Expression::from(n),
+        Content::String(s) => Expression::from(escape_template(&s)),
+        Content::Seq(elements) => array(elements)?,
+        Content::Map(entries) => object(entries)?,
+        Content::Newtype(_, value) => expression(*value)?,
+        Content::UnitVariant(variant) => Expression::from(variant),
+        Content::NewtypeVariant(variant, value) => {
+            object(vec![(Content::String(variant.to_owned()), *value)])?
+        }
+        Content::TupleVariant(variant, elements) => object(vec![(
+            Content::String(variant.to_owned()),
+            Content::Seq(elements),
+        )])?,
+        Content::StructVariant(variant, entries) => object(vec![(
+            Content::String(variant.to_owned()),
+            Content::Map(entries),
+        )])?,
+    };
+
+    Ok(expr)
+}
+
+// Arrays and objects are laid out across multiple lines, the indentation is added when the body
+// is formatted.
+fn array(elements: Vec<Content>) -> Result<Expression, Error> {
+    let mut array = Array::with_capacity(elements.len());
+
+    for element in elements {
+        let mut expr = expression(element)?;
+        expr.decor_mut().set_prefix("\n");
+        array.push(expr);
+    }
+
+    if !array.is_empty() {
+        array.set_trailing("\n");
+    }
+
+    Ok(Expression::from(array))
+}
+
+fn object(entries: Vec<(Content, Content)>) -> Result<Expression, Error> {
+    let mut object = Object::with_capacity(entries.len());
+
+    for (i, (key, value)) in entries.into_iter().enumerate() {
+        let key = label(key)?;
+        let mut key = if is_ident(&key) {
+            ObjectKey::from(Ident::new_unchecked(key))
+        } else {
+            ObjectKey::from(Expression::from(escape_template(&key)))
+        };
+
+        if i == 0 {
+            key.decor_mut().set_prefix("\n");
+        }
+
+        let mut value = ObjectValue::new(expression(value)?);
+        value.set_terminator(ObjectValueTerminator::Newline);
+        object.insert(key, value);
+    }
+
+    Ok(Expression::from(object))
+}
+
+fn parse_ident(name: &str) -> Result<Ident, Error> {
+    Ident::try_new(name).map_err(|_| Error::new(format!("invalid identifier `{name}`")))
+}
+
+// Returns the string representation of a map key.
+fn label(content: Content) -> Result<String, Error> {
+    match content {
+        Content::String(s) => Ok(s),
+        Content::Number(n) => Ok(n.to_string()),
+        Content::Bool(b) => Ok(b.to_string()),
+        Content::UnitVariant(variant) => Ok(variant.to_owned()),
+        Content::Newtype(_, value) => label(*value),
+        _ => Err(Error::new("map key must be a string")),
+    }
+}
@@ -340,34 +340,38 @@ impl HeredocTemplate {
 
     /// Dedent the heredoc.
     pub fn dedent(&mut self) {
+        let mut literals = Vec::new();
+        collect_literals(&mut self.template, &mut literals);
+
         let mut indent: Option<usize> = None;
         let mut skip_first = false;
 
-        for element in self.template.iter() {
-            if let Element::Literal(literal) = element {
-                let leading_ws = min_leading_whitespace(literal, skip_first);
-                indent = Some(indent.map_or(leading_ws, |indent| indent.min(leading_ws)));
+        for literal in &literals {
+            if let Some(literal) = literal {
+                // Literals without any dedentable lines, like a newline following an
+                // interpolation, must not contribute to the indent.
+                if let Some(leading_ws) = min_leading_whitespace(literal, skip_first) {
+                    indent = Some(indent.map_or(leading_ws, |indent| indent.min(leading_ws)));
+                }
                 skip_first = !literal.ends_with('\n');
             } else {
                 skip_first = true;
             }
         }
 
-        if let Some(indent) = indent {
-            skip_first = false;
+        let indent = indent.unwrap_or(0);
+        skip_first = false;
 
-            for element in self.template.iter_mut() {
-                if let Element::Literal(literal) = element {
-                    let dedented = dedent_by(literal, indent, skip_first);
-                    *literal.as_mut() = dedented.into();
-                    skip_first = !literal.ends_with('\n');
-                } else {
-                    skip_first = true;
-                }
+        for literal in literals {
+            if let Some(literal) = literal {
+                *literal = dedent_by(literal, indent, skip_first).into_owned();
+                skip_first = !literal.ends_with('\n');
+            } else {
+                skip_first = true;
             }
-
-            self.set_indent(indent);
         }
+
+        self.set_indent(indent);
     }
 
     pub(crate) fn despan(&mut self, input: &str) {
@@ -377,6 +381,33 @@ impl HeredocTemplate {
     }
 }
 
+// Collects all literals of a template in source order, descending into the templates of
+// directives. Interpolations and directive tags in between are represented by `None`.
+fn collect_literals<'a>(template: &'a mut Template, literals: &mut Vec<Option<&'a mut String>>) {
+    for element in template.iter_mut() {
+        match element {
+            Element::Literal(literal) => literals.push(Some(literal.as_mut())),
+            Element::Interpolation(_) => literals.push(None),
+            Element::Directive(Directive::If(dir)) => {
+                literals.push(None);
+                collect_literals(&mut dir.if_expr.template, literals);
+
+                if let Some(else_expr) = &mut dir.else_expr {
+                    literals.push(None);
+                    collect_literals(&mut else_expr.template, literals);
+                }
+
+                literals.push(None);
+            }
+            Element::Directive(Directive::For(dir)) => {
+                literals.push(None);
+                collect_literals(&mut dir.for_expr.template, literals);
+                literals.push(None);
+            }
+        }
+    }
+}
+
 impl PartialEq for HeredocTemplate {
     fn eq(&self, other: &Self) -> bool {
         self.delimiter == other.delimiter
@@ -1,5 +1,35 @@
 use std::borrow::Cow;
 
+// Escapes the sequences which would introduce a template interpolation or directive, i.e. `${`
+// becomes `$${` and `%{` becomes `%%{`. Sequences which are already escaped are kept as they are,
+// since strings store them in their escaped form.
+#[cfg(feature = "serde")]
+pub(crate) fn escape_template(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(pos) = rest.find(['$', '%']) {
+        let (literal, tail) = rest.split_at(pos);
+        let marker = &tail[..1];
+        escaped.push_str(literal);
+
+        rest = if tail[1..].starts_with(marker) && tail[2..].starts_with('{') {
+            escaped.push_str(&tail[..3]);
+            &tail[3..]
+        } else if tail[1..].starts_with('{') {
+            escaped.push_str(marker);
+            escaped.push_str(&tail[..2]);
+            &tail[2..]
+        } else {
+            escaped.push_str(marker);
+            &tail[1..]
+        };
+    }
+
+    escaped.push_str(rest);
+    escaped
+}
+
 pub(crate) fn dedent_by(s: &str, n: usize, skip_first: bool) -> Cow<str> {
     let mut dedented = String::with_capacity(s.len());
 
@@ -23,11 +53,8 @@ pub(crate) fn dedent_by(s: &str, n: usize, skip_first: bool) -> Cow<str> {
     Cow::Owned(dedented)
 }
 
-pub(crate) fn min_leading_whitespace(s: &str, skip_first: bool) -> usize {
-    if s.is_empty() {
-        return 0;
-    }
-
+// Returns `None` if there are no lines to consider for dedenting.
+pub(crate) fn min_leading_whitespace(s: &str, skip_first: bool) -> Option<usize> {
     let mut leading_ws: Option<usize> = None;
 
     // Find the minimum number of possible leading units of whitespace that can be be stripped off
@@ -42,7 +69,7 @@ pub(crate) fn min_leading_whitespace(s: &str, skip_first: bool) -> usize {
         if line_leading_ws == 0 {
             // Fast path: no dedent needed if we encounter a non-empty line which starts with a
             // non-whitespace character.
-            return 0;
+            return Some(0);
         }
 
         leading_ws = Some(leading_ws.map_or(line_leading_ws, |leading_ws| {
@@ -50,7 +77,7 @@ pub(crate) fn min_leading_whitespace(s: &str, skip_first: bool) -> usize {
         }));
     }
 
-    leading_ws.unwrap_or(0)
+    leading_ws
 }
 
 pub(crate) fn indent_by(s: &str, n: usize, skip_first: bool) -> String {
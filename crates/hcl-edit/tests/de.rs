@@ -0,0 +1,285 @@
+#![cfg(feature = "serde")]
+
+use hcl_edit::de::{from_body, from_expression, Error};
+use hcl_edit::expr::Expression;
+use hcl_edit::structure::Body;
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+fn parse(input: &str) -> Body {
+    input.parse().unwrap()
+}
+
+#[test]
+fn attributes() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        name: String,
+        port: u16,
+        ratio: f64,
+        enabled: bool,
+        tags: BTreeMap<String, String>,
+        hosts: Vec<String>,
+        nothing: Option<u8>,
+        heredoc: String,
+    }
+
+    let input = indoc! {r#"
+        name    = "api"
+        port    = (8080)
+        ratio   = 0.5
+        enabled = true
+        tags = {
+          env         = "prod"
+          "team name" = "ops"
+        }
+        hosts   = ["a", "b"]
+        nothing = null
+        heredoc = <<-EOT
+          hello
+          world
+        EOT
+    "#};
+
+    let expected = Config {
+        name: "api".into(),
+        port: 8080,
+        ratio: 0.5,
+        enabled: true,
+        tags: BTreeMap::from([
+            ("env".into(), "prod".into()),
+            ("team name".into(), "ops".into()),
+        ]),
+        hosts: vec!["a".into(), "b".into()],
+        nothing: None,
+        heredoc: "hello\nworld\n".into(),
+    };
+
+    assert_eq!(from_body::<Config>(parse(input)).unwrap(), expected);
+}
+
+#[test]
+fn expressions() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        variable: String,
+        func_call: String,
+        template: String,
+        conditional: Vec<String>,
+    }
+
+    let input = indoc! {r#"
+        variable    = var.name
+        func_call   = upper("api")
+        template    = "${var.name}-api"
+        conditional = [var.enabled ? 1 : 0]
+    "#};
+
+    let expected = Config {
+        variable: "${var.name}".into(),
+        func_call: "${upper(\"api\")}".into(),
+        template: "${var.name}-api".into(),
+        conditional: vec!["${var.enabled ? 1 : 0}".into()],
+    };
+
+    assert_eq!(from_body::<Config>(parse(input)).unwrap(), expected);
+
+    let expr: Expression = "[1, 2, 3]".parse().unwrap();
+    assert_eq!(from_expression::<Vec<u8>>(expr).unwrap(), vec![1, 2, 3]);
+}
+
+#[test]
+fn blocks() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        settings: Settings,
+        service: BTreeMap<String, Service>,
+        resource: BTreeMap<String, BTreeMap<String, Resource>>,
+        rule: Vec<Rule>,
+        single: Vec<Rule>,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Settings {
+        debug: bool,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Service {
+        port: u16,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Resource {
+        ami: String,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Rule {
+        action: String,
+    }
+
+    let input = indoc! {r#"
+        settings {
+          debug = true
+        }
+
+        service "api" {
+          port = 8080
+        }
+
+        service "web" {
+          port = 80
+        }
+
+        resource "aws_instance" "a" {
+          ami = "ami-1"
+        }
+
+        resource "aws_instance" "b" {
+          ami = "ami-2"
+        }
+
+        rule {
+          action = "allow"
+        }
+
+        rule {
+          action = "deny"
+        }
+
+        single {
+          action = "log"
+        }
+    "#};
+
+    let expected = Config {
+        settings: Settings { debug: true },
+        service: BTreeMap::from([
+            ("api".into(), Service { port: 8080 }),
+            ("web".into(), Service { port: 80 }),
+        ]),
+        resource: BTreeMap::from([(
+            "aws_instance".into(),
+            BTreeMap::from([
+                (
+                    "a".into(),
+                    Resource {
+                        ami: "ami-1".into(),
+                    },
+                ),
+                (
+                    "b".into(),
+                    Resource {
+                        ami: "ami-2".into(),
+                    },
+                ),
+            ]),
+        )]),
+        rule: vec![
+            Rule {
+                action: "allow".into(),
+            },
+            Rule {
+                action: "deny".into(),
+            },
+        ],
+        single: vec![Rule {
+            action: "log".into(),
+        }],
+    };
+
+    assert_eq!(from_body::<Config>(parse(input)).unwrap(), expected);
+}
+
+#[test]
+fn enums() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    enum Mode {
+        Fast,
+        Slow { delay: u32 },
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        a: Mode,
+        b: Mode,
+        c: Mode,
+    }
+
+    let input = indoc! {r#"
+        a = "Fast"
+        b = { Slow = { delay = 10 } }
+
+        c Slow {
+          delay = 20
+        }
+    "#};
+
+    let expected = Config {
+        a: Mode::Fast,
+        b: Mode::Slow { delay: 10 },
+        c: Mode::Slow { delay: 20 },
+    };
+
+    assert_eq!(from_body::<Config>(parse(input)).unwrap(), expected);
+}
+
+#[test]
+fn error_spans() {
+    #[derive(Deserialize, Debug)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct Config {
+        service: Service,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct Service {
+        name: String,
+        port: u16,
+    }
+
+    fn error(input: &str) -> (Error, &str) {
+        let err = from_body::<Config>(parse(input)).unwrap_err();
+        let span = err.span().expect("missing span");
+        (err, &input[span])
+    }
+
+    let input = indoc! {r#"
+        service {
+          name = "api"
+          port = "http"
+        }
+    "#};
+
+    let (err, spanned) = error(input);
+    assert_eq!(err.message(), "invalid type: string \"http\", expected u16");
+    assert_eq!(spanned, "\"http\"");
+
+    let input = indoc! {r#"
+        service {
+          name  = "api"
+          port  = 80
+          extra = true
+        }
+    "#};
+
+    let (err, spanned) = error(input);
+    assert!(err.message().starts_with("unknown field `extra`"));
+    assert_eq!(spanned, "extra = true");
+
+    let input = indoc! {r#"
+        service {
+          name = "api"
+        }
+    "#};
+
+    let (err, spanned) = error(input);
+    assert_eq!(err.message(), "missing field `port`");
+    assert!(spanned.starts_with("service {"));
+}
@@ -0,0 +1,5 @@
+heredoc {
+  data = <<EOF
+data = <<EOF # This is actually valid
+EOF
+}
@@ -0,0 +1,6 @@
+heredoc {
+  data = <<-EOF
+  Indent was stripped.
+    And it was the correct amount.
+  EOF
+}
@@ -0,0 +1,284 @@
+#![cfg(feature = "serde")]
+
+use hcl_edit::ser::{to_body, Block, LabeledBlock};
+use hcl_edit::structure::Body;
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[test]
+fn attributes() {
+    #[derive(Serialize)]
+    struct Config {
+        name: &'static str,
+        port: u16,
+        ratio: f64,
+        enabled: bool,
+        tags: BTreeMap<&'static str, &'static str>,
+        hosts: Vec<&'static str>,
+        empty: Vec<u8>,
+        nothing: Option<u8>,
+    }
+
+    let config = Config {
+        name: "api",
+        port: 8080,
+        ratio: 0.5,
+        enabled: true,
+        tags: BTreeMap::from([("env", "prod"), ("team name", "ops")]),
+        hosts: vec!["a", "b"],
+        empty: Vec::new(),
+        nothing: None,
+    };
+
+    let expected = indoc! {r#"
+        name    = "api"
+        port    = 8080
+        ratio   = 0.5
+        enabled = true
+        tags = {
+          env         = "prod"
+          "team name" = "ops"
+        }
+        hosts = [
+          "a",
+          "b"
+        ]
+        empty   = []
+        nothing = null
+    "#};
+
+    let body = to_body(&config).unwrap();
+    assert_eq!(body.to_string(), expected);
+    assert_eq!(expected.parse::<Body>().unwrap().to_string(), expected);
+}
+
+#[test]
+fn blocks() {
+    #[derive(Serialize)]
+    struct Config {
+        #[serde(serialize_with = "hcl_edit::ser::doubly_labeled_block")]
+        resource: BTreeMap<&'static str, BTreeMap<&'static str, Instance>>,
+        provider: Block<Vec<Provider>>,
+        variable: LabeledBlock<BTreeMap<&'static str, Variable>>,
+    }
+
+    #[derive(Serialize)]
+    struct Instance {
+        ami: &'static str,
+        #[serde(serialize_with = "hcl_edit::ser::block")]
+        ebs_block_device: Vec<Device>,
+    }
+
+    #[derive(Serialize)]
+    struct Device {
+        device_name: &'static str,
+    }
+
+    #[derive(Serialize)]
+    struct Provider {
+        region: &'static str,
+    }
+
+    #[derive(Serialize)]
+    struct Variable {}
+
+    let config = Config {
+        resource: BTreeMap::from([(
+            "aws_instance",
+            BTreeMap::from([(
+                "web",
+                Instance {
+                    ami: "ami-123",
+                    ebs_block_device: vec![
+                        Device {
+                            device_name: "sda1",
+                        },
+                        Device {
+                            device_name: "sda2",
+                        },
+                    ],
+                },
+            )]),
+        )]),
+        provider: Block::new(vec![
+            Provider {
+                region: "eu-central-1",
+            },
+            Provider {
+                region: "us-east-1",
+            },
+        ]),
+        variable: LabeledBlock::new(BTreeMap::from([("region", Variable {})])),
+    };
+
+    let expected = indoc! {r#"
+        resource "aws_instance" "web" {
+          ami = "ami-123"
+          ebs_block_device {
+            device_name = "sda1"
+          }
+
+          ebs_block_device {
+            device_name = "sda2"
+          }
+        }
+
+        provider {
+          region = "eu-central-1"
+        }
+
+        provider {
+          region = "us-east-1"
+        }
+
+        variable "region" {}
+    "#};
+
+    assert_eq!(to_body(&config).unwrap().to_string(), expected);
+}
+
+#[test]
+fn splice_into_document() {
+    #[derive(Serialize)]
+    struct Locals {
+        region: &'static str,
+        zones: u8,
+    }
+
+    let mut body: Body = indoc! {r#"
+        # Managed by hand.
+        terraform {
+          required_version = ">= 1.0" # Pinned.
+        }
+    "#}
+    .parse()
+    .unwrap();
+
+    let generated = to_body(&Locals {
+        region: "eu-central-1",
+        zones: 3,
+    })
+    .unwrap();
+
+    let terraform = body.blocks_mut().next().unwrap();
+    terraform.body.extend(generated);
+    hcl_edit::format::format_body(&mut body).unwrap();
+
+    assert_eq!(
+        body.to_string(),
+        indoc! {r#"
+            # Managed by hand.
+            terraform {
+              required_version = ">= 1.0" # Pinned.
+              region           = "eu-central-1"
+              zones            = 3
+            }
+        "#}
+    );
+}
+
+#[test]
+fn enums() {
+    #[derive(Serialize)]
+    enum Mode {
+        Fast,
+        Limit(u32),
+        Range(u32, u32),
+        Custom { name: &'static str },
+    }
+
+    #[derive(Serialize)]
+    struct Config {
+        a: Mode,
+        b: Mode,
+        c: Mode,
+        d: Mode,
+    }
+
+    let config = Config {
+        a: Mode::Fast,
+        b: Mode::Limit(1),
+        c: Mode::Range(1, 2),
+        d: Mode::Custom { name: "x" },
+    };
+
+    let expected = indoc! {r#"
+        a = "Fast"
+        b = {
+          Limit = 1
+        }
+        c = {
+          Range = [
+            1,
+            2
+          ]
+        }
+        d = {
+          Custom = {
+            name = "x"
+          }
+        }
+    "#};
+
+    assert_eq!(to_body(&config).unwrap().to_string(), expected);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        to_body(&1).unwrap_err().to_string(),
+        "value is not map-like and cannot be serialized as body"
+    );
+    assert_eq!(
+        to_body(&BTreeMap::from([("not valid", 1)]))
+            .unwrap_err()
+            .to_string(),
+        "invalid identifier `not valid`"
+    );
+
+    #[derive(Serialize)]
+    struct Config {
+        #[serde(serialize_with = "hcl_edit::ser::block")]
+        block: u8,
+    }
+
+    assert_eq!(
+        to_body(&Config { block: 1 }).unwrap_err().to_string(),
+        "value of block `block` is not map-like"
+    );
+}
+
+#[test]
+fn template_sequences() {
+    let value = BTreeMap::from([
+        ("curl", "curl -w \"%{http_code}\" ${URL}"),
+        ("escaped", "$${literal}"),
+        ("multiline", "x ${y} %{z}\nq"),
+    ]);
+
+    let expected = indoc! {r#"
+        curl      = "curl -w \"%%{http_code}\" $${URL}"
+        escaped   = "$${literal}"
+        multiline = "x $${y} %%{z}\nq"
+    "#};
+
+    let body = to_body(&value).unwrap();
+    assert_eq!(body.to_string(), expected);
+
+    // The escaped sequences are parsed as literal strings and not as templates.
+    let parsed: Body = expected.parse().unwrap();
+    assert!(parsed.attributes().all(|attr| attr.value.is_str()));
+    assert_eq!(parsed.to_string(), expected);
+}
+
+#[test]
+fn duplicate_attributes() {
+    let value = vec![BTreeMap::from([("a", 1)]), BTreeMap::from([("a", 2)])];
+
+    assert_eq!(
+        to_body(&value).unwrap_err().to_string(),
+        "attribute `a` is defined more than once"
+    );
+}
@@ -5,6 +5,9 @@
 //!
 //! [hcl-json-spec]: https://github.com/hashicorp/hcl/blob/main/json/spec.md
 
+pub(crate) mod spanned;
+
+pub use self::spanned::Spanned;
 use crate::structure::IntoJsonSpec;
 use crate::{parser, Body, Error, Identifier, Result};
 use serde::de::value::StringDeserializer;
@@ -27,7 +30,7 @@ impl Deserializer {
     ///
     /// [Error]: ../error/enum.Error.html
     pub fn from_str(input: &str) -> Result<Self> {
-        let body = parser::parse(input)?;
+        let body = parser::parse_with_spans(input)?;
         Ok(Deserializer { body })
     }
 }
@@ -215,7 +218,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     where
         V: de::Visitor<'de>,
     {
-        self.body.into_json_spec().deserialize_any(visitor)
+        self.body.into_json_node().deserialize_any(visitor)
     }
 
     fn deserialize_newtype_struct<V>(
@@ -232,7 +235,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         } else {
             // Generic deserialization according to the HCL JSON spec.
             self.body
-                .into_json_spec()
+                .into_json_node()
                 .deserialize_newtype_struct(name, visitor)
         }
     }
@@ -247,7 +250,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         V: de::Visitor<'de>,
     {
         self.body
-            .into_json_spec()
+            .into_json_node()
             .deserialize_enum(name, variants, visitor)
     }
 
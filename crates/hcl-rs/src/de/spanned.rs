@@ -0,0 +1,203 @@
+use crate::Span;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::{Serialize, Serializer};
+use std::cell::RefCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops;
+
+pub(crate) const SPANNED_MARKER: &str = "$hcl::Spanned";
+
+thread_local! {
+    // The span of the node that is about to be deserialized into a `Spanned<T>`. It is passed
+    // alongside the node because serde has no way to attach extra data to a newtype struct.
+    static PENDING_SPAN: RefCell<Option<Span>> = const { RefCell::new(None) };
+}
+
+/// A wrapper type which captures the [`Span`] of the HCL attribute or block that `T` was
+/// deserialized from.
+///
+/// The span is only available if the input was deserialized via one of the functions that parse
+/// HCL text, like [`hcl::from_str`][crate::from_str], or from a [`Body`][crate::Body] that was
+/// parsed via [`hcl::parse_with_spans`][crate::parse_with_spans]. Values which do not map to a
+/// single attribute or block, like array elements, object values or the bodies of multiple
+/// blocks that were merged into a sequence, do not have a span either.
+///
+/// When passed to a deserializer other than the one from this crate, a `Spanned<T>` deserializes
+/// exactly like `T` and does not have a span. It always serializes exactly like `T`.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hcl::de::Spanned;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     service: Spanned<Service>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Service {
+///     port: Spanned<u16>,
+/// }
+///
+/// let input = r#"
+/// service {
+///   port = 80
+/// }
+/// "#;
+///
+/// let config: Config = hcl::from_str(input)?;
+///
+/// let service_span = config.service.span().unwrap();
+/// assert_eq!(service_span.start().line, 2);
+///
+/// let port_span = config.service.port.span().unwrap();
+/// assert_eq!(&input[port_span.range()], "port = 80");
+/// assert_eq!(format!("main.hcl:{port_span}"), "main.hcl:3:3");
+/// assert_eq!(*config.service.port, 80);
+/// #   Ok(())
+/// # }
+/// ```
+pub struct Spanned<T> {
+    value: T,
+    span: Option<Span>,
+}
+
+impl<T> Spanned<T> {
+    /// Create a new `Spanned<T>` from a `T` without a span.
+    pub fn new(value: T) -> Spanned<T> {
+        Spanned { value, span: None }
+    }
+
+    /// Returns the span of the attribute or block the value was deserialized from, if available.
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
+    /// Returns a reference to the wrapped `T`.
+    pub fn get_ref(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns a mutable reference to the wrapped `T`.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Consume the `Spanned` and return the wrapped `T`.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> ops::Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> ops::DerefMut for Spanned<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T> Clone for Spanned<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Spanned {
+            value: self.value.clone(),
+            span: self.span.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Spanned<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Spanned")
+            .field("value", &self.value)
+            .field("span", &self.span)
+            .finish()
+    }
+}
+
+// The span is not considered for equality.
+impl<T> PartialEq for Spanned<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T> Eq for Spanned<T> where T: Eq {}
+
+impl<T> Serialize for Spanned<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Spanned<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SpannedVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> de::Visitor<'de> for SpannedVisitor<T>
+        where
+            T: Deserialize<'de>,
+        {
+            type Value = Spanned<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a spanned value")
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                // Take the span before deserializing the value since it may contain
+                // `Spanned<T>`s itself.
+                let span = take_pending_span();
+
+                T::deserialize(deserializer).map(|value| Spanned { value, span })
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(SPANNED_MARKER, SpannedVisitor(PhantomData))
+    }
+}
+
+/// Sets the span that is picked up by the next `Spanned<T>` that is deserialized.
+///
+/// Must be called right before visiting a newtype struct with the name `SPANNED_MARKER`.
+pub(crate) fn set_pending_span(span: Option<Span>) {
+    PENDING_SPAN.with(|pending| *pending.borrow_mut() = span);
+}
+
+fn take_pending_span() -> Option<Span> {
+    PENDING_SPAN.with(RefCell::take)
+}
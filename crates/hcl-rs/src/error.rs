@@ -1,7 +1,5 @@
 //! The `Error` and `Result` types used by this crate.
 use crate::eval;
-use crate::parser::Rule;
-use pest::{error::LineColLocation, Span};
 use serde::{de, ser};
 use std::fmt::{self, Display};
 use std::io;
@@ -21,6 +19,14 @@ pub enum Error {
         /// An optional location context where the error happened in the input.
         location: Option<Location>,
     },
+    /// Represents an error that was caused by the block or attribute at `path` during
+    /// deserialization.
+    AtPath {
+        /// The path to the block or attribute that caused the error.
+        path: Path,
+        /// The underlying error.
+        err: Box<Error>,
+    },
     /// Represents the error emitted when the `Deserializer` hits an unexpected end of input.
     Eof,
     /// Represents an error that resulted from invalid UTF8 input.
@@ -50,10 +56,95 @@ impl Error {
         }
     }
 
+    // Attaches a location to error messages that do not carry one yet.
+    pub(crate) fn with_location(self, loc: Option<&Location>) -> Error {
+        match self {
+            Error::Message {
+                msg,
+                location: None,
+            } => Error::Message {
+                msg,
+                location: loc.cloned(),
+            },
+            Error::AtPath { path, err } => Error::AtPath {
+                path,
+                err: Box::new(err.with_location(loc)),
+            },
+            err => err,
+        }
+    }
+
+    // Prepends a segment to the path of error messages. If `labeled` is `true`, the first segment
+    // of the existing path is turned into a block label.
+    pub(crate) fn with_path_segment(self, segment: PathSegment, labeled: bool) -> Error {
+        let (mut path, err) = match self {
+            Error::Message { .. } => (Path::default(), Box::new(self)),
+            Error::AtPath { path, err } => (path, err),
+            _ => return self,
+        };
+
+        if labeled {
+            if let Some(PathSegment::Key(key)) = path.segments.first_mut() {
+                path.segments[0] = PathSegment::Label(std::mem::take(key));
+            }
+        }
+
+        path.segments.insert(0, segment);
+        Error::AtPath { path, err }
+    }
+
     /// Returns the `Location` in the input where the error happened, if available.
     pub fn location(&self) -> Option<&Location> {
         match self {
             Error::Message { location, .. } => location.as_ref(),
+            Error::AtPath { err, .. } => err.location(),
+            Error::Eval(err) => err.span().map(crate::Span::start),
+            _ => None,
+        }
+    }
+
+    /// Returns the path to the block or attribute that caused a deserialization error, if
+    /// available.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hcl::error::PathSegment;
+    /// use serde::Deserialize;
+    /// use std::collections::HashMap;
+    ///
+    /// #[derive(Deserialize, Debug)]
+    /// struct Config {
+    ///     service: HashMap<String, Service>,
+    /// }
+    ///
+    /// #[derive(Deserialize, Debug)]
+    /// struct Service {
+    ///     port: u16,
+    /// }
+    ///
+    /// let input = r#"
+    /// service "api" {
+    ///   port = "http"
+    /// }
+    /// "#;
+    ///
+    /// let err = hcl::from_str::<Config>(input).unwrap_err();
+    /// let path = err.path().unwrap();
+    ///
+    /// assert_eq!(
+    ///     path.segments(),
+    ///     [
+    ///         PathSegment::Key("service".into()),
+    ///         PathSegment::Label("api".into()),
+    ///         PathSegment::Key("port".into()),
+    ///     ]
+    /// );
+    /// assert_eq!(path.to_string(), r#"service "api" > port"#);
+    /// ```
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Error::AtPath { path, .. } => Some(path),
             _ => None,
         }
     }
@@ -66,11 +157,10 @@ impl Display for Error {
             Error::Io(err) => Display::fmt(err, f),
             Error::Utf8(err) => Display::fmt(err, f),
             Error::Message { msg, location } => match location {
-                Some(loc) => {
-                    write!(f, "{msg} in line {}, col {}", loc.line, loc.col)
-                }
+                Some(loc) => write!(f, "{msg} in line {}, col {}", loc.line, loc.col),
                 None => write!(f, "{msg}"),
             },
+            Error::AtPath { path, err } => write!(f, "{path}: {err}"),
             Error::InvalidEscape(c) => write!(f, "invalid escape sequence '\\{c}'"),
             Error::InvalidUnicodeCodePoint(u) => {
                 write!(f, "invalid unicode code point '\\u{u}'")
@@ -93,14 +183,28 @@ impl From<Utf8Error> for Error {
     }
 }
 
-impl From<pest::error::Error<Rule>> for Error {
-    fn from(err: pest::error::Error<Rule>) -> Self {
-        let (line, col) = match err.line_col {
-            LineColLocation::Pos((l, c)) | LineColLocation::Span((l, c), (_, _)) => (l, c),
-        };
+impl From<hcl_edit::parser::Error> for Error {
+    fn from(err: hcl_edit::parser::Error) -> Self {
+        let location = err.location();
+        let (line, col) = (location.line(), location.column());
+        let spacing = " ".repeat(line.to_string().len());
+
+        // Keep the message layout of the former pest-based parser.
+        let msg = format!(
+            "{s}--> {line}:{col}\n\
+             {s} |\n\
+             {line} | {source}\n\
+             {s} | {caret:>col$}---\n\
+             {s} |\n\
+             {s} = {message}",
+            s = spacing,
+            source = err.line(),
+            caret = '^',
+            message = err.message(),
+        );
 
         Error::Message {
-            msg: err.to_string(),
+            msg,
             location: Some(Location { line, col }),
         }
     }
@@ -135,9 +239,51 @@ pub struct Location {
     pub col: usize,
 }
 
-impl From<Span<'_>> for Location {
-    fn from(span: Span<'_>) -> Self {
-        let (line, col) = span.start_pos().line_col();
-        Location { line, col }
+/// The path to the block or attribute that caused a deserialization error.
+///
+/// The `Display` implementation renders block identifiers and attribute keys separated by `>`,
+/// followed by the block labels in quotes and the index of blocks that were deserialized into a
+/// sequence, e.g. `service "api" > listener[1] > port`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+impl Path {
+    /// Returns the segments of the path, starting at the top-level body.
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) => {
+                    if i > 0 {
+                        f.write_str(" > ")?;
+                    }
+
+                    f.write_str(key)?;
+                }
+                PathSegment::Label(label) => write!(f, " {label:?}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+
+        Ok(())
     }
 }
+
+/// A segment of a [`Path`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PathSegment {
+    /// A block identifier or an attribute key.
+    Key(String),
+    /// A block label.
+    Label(String),
+    /// The index of a block in a sequence of blocks with the same identifier and labels.
+    Index(usize),
+}
@@ -27,6 +27,21 @@ impl Error {
         }
     }
 
+    // Attaches the span of the structure in which the error occurred, unless the error already
+    // carries a span of a more deeply nested structure.
+    pub(super) fn with_span(mut self, span: Option<&Span>) -> Error {
+        if self.inner.span.is_none() {
+            self.inner.span = span.cloned();
+        }
+        self
+    }
+
+    // Prepends `segment` to the path of the structure in which the error occurred.
+    pub(super) fn prepend_path(mut self, segment: PathSegment) -> Error {
+        self.inner.path.insert(0, segment);
+        self
+    }
+
     pub(super) fn unexpected<T>(value: T, expected: &'static str) -> Error
     where
         T: Into<Value>,
@@ -44,6 +59,22 @@ impl Error {
         self.inner.expr.as_ref()
     }
 
+    /// Return the path to the attribute whose evaluation caused the error.
+    ///
+    /// The path is only populated for errors collected via
+    /// [`evaluate_in_place`][Evaluate::evaluate_in_place]. It is empty otherwise.
+    pub fn path(&self) -> &[PathSegment] {
+        &self.inner.path
+    }
+
+    /// Return the location of the attribute whose evaluation caused the error.
+    ///
+    /// This is only available if the evaluated structure was parsed with span tracking enabled.
+    /// See the documentation of [`Span`] for more details.
+    pub fn span(&self) -> Option<&Span> {
+        self.inner.span.as_ref()
+    }
+
     /// Consume the `Error` and return the `ErrorKind`.
     pub fn into_kind(self) -> ErrorKind {
         self.inner.kind
@@ -77,26 +108,172 @@ impl std::error::Error for Error {}
 struct ErrorInner {
     kind: ErrorKind,
     expr: Option<Expression>,
+    path: Vec<PathSegment>,
+    span: Option<Span>,
 }
 
 impl ErrorInner {
     fn new(kind: ErrorKind, expr: Option<Expression>) -> ErrorInner {
-        ErrorInner { kind, expr }
+        ErrorInner {
+            kind,
+            expr,
+            path: Vec::new(),
+            span: None,
+        }
     }
 }
 
 impl fmt::Display for ErrorInner {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.path.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" > ")?;
+            }
+
+            write!(f, "{segment}")?;
+
+            if i == self.path.len() - 1 {
+                f.write_str(": ")?;
+            }
+        }
+
         write!(f, "{}", self.kind)?;
 
         if let Some(expr) = &self.expr {
             write!(f, " in expression `{expr}`")?;
         }
 
+        if let Some(span) = &self.span {
+            let start = span.start();
+            write!(f, " in line {}, col {}", start.line, start.col)?;
+        }
+
         Ok(())
     }
 }
 
+/// A collection of errors that occurred while evaluating a structure in place.
+///
+/// Returned by [`evaluate_in_place`][Evaluate::evaluate_in_place]. It always contains at least
+/// one error.
+#[derive(Debug, Clone)]
+pub struct Errors {
+    inner: Vec<Error>,
+}
+
+impl Errors {
+    /// Returns the number of errors.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if there are no errors.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns an iterator over all errors.
+    pub fn iter(&self) -> std::slice::Iter<'_, Error> {
+        self.inner.iter()
+    }
+
+    /// Consumes `self` and returns the contained errors as a `Vec`.
+    pub fn into_vec(self) -> Vec<Error> {
+        self.inner
+    }
+}
+
+impl fmt::Display for Errors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.len() == 1 {
+            return fmt::Display::fmt(&self.inner[0], f);
+        }
+
+        writeln!(f, "{} errors occurred:", self.len())?;
+
+        for error in self {
+            writeln!(f, "- {error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Error> for Errors {
+    fn from(error: Error) -> Self {
+        Errors { inner: vec![error] }
+    }
+}
+
+impl FromIterator<Error> for Errors {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Error>,
+    {
+        Errors {
+            inner: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Error> for Errors {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Error>,
+    {
+        self.inner.extend(iter);
+    }
+}
+
+impl IntoIterator for Errors {
+    type Item = Error;
+    type IntoIter = std::vec::IntoIter<Error>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Errors {
+    type Item = &'a Error;
+    type IntoIter = std::slice::Iter<'a, Error>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl std::error::Error for Errors {}
+
+/// A segment of the path to the structure in which an evaluation error occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A block, identified by its identifier and labels.
+    Block(Identifier, Vec<BlockLabel>),
+    /// An attribute, identified by its key.
+    Attribute(Identifier),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Block(ident, labels) => {
+                f.write_str(ident)?;
+
+                for label in labels {
+                    match label {
+                        BlockLabel::Identifier(ident) => write!(f, " {ident}")?,
+                        BlockLabel::String(string) => write!(f, " {string:?}")?,
+                    }
+                }
+
+                Ok(())
+            }
+            PathSegment::Attribute(key) => f.write_str(key),
+        }
+    }
+}
+
 /// An enum representing all kinds of errors that can happen during the evaluation of HCL
 /// expressions and templates.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -122,8 +299,13 @@ pub enum ErrorKind {
     KeyExists(String),
     /// A function call in an expression returned an error.
     FuncCall(Identifier, String),
+    /// A [`VariableResolver`][super::VariableResolver] failed to resolve a variable.
+    ResolveVar(Identifier, String),
     /// It was attempted to evaluate a raw expression.
     RawExpression,
+    /// Attributes evaluated by an [`OrderedEvaluator`][super::OrderedEvaluator] reference each
+    /// other. Contains the keys of all attributes in the cycle, in reference order.
+    ReferenceCycle(Vec<Identifier>),
 }
 
 impl From<Error> for ErrorKind {
@@ -171,7 +353,21 @@ impl fmt::Display for ErrorKind {
             ErrorKind::FuncCall(name, msg) => {
                 write!(f, "error calling function `{name}`: {msg}")
             }
+            ErrorKind::ResolveVar(name, msg) => {
+                write!(f, "error resolving variable `{name}`: {msg}")
+            }
             ErrorKind::RawExpression => f.write_str("raw expressions cannot be evaluated"),
+            ErrorKind::ReferenceCycle(keys) => {
+                f.write_str("reference cycle: ")?;
+                // Repeat the first key at the end to close the cycle.
+                for (i, key) in keys.iter().chain(keys.first()).enumerate() {
+                    if i > 0 {
+                        f.write_str(" -> ")?;
+                    }
+                    write!(f, "`{key}`")?;
+                }
+                Ok(())
+            }
         }
     }
 }
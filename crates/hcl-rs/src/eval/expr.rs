@@ -2,17 +2,25 @@ use super::*;
 use std::collections::VecDeque;
 
 pub(super) fn evaluate_bool(expr: &Expression, ctx: &Context) -> EvalResult<bool> {
-    match expr.evaluate(ctx)? {
+    expect_bool(expr.evaluate(ctx)?, ctx)
+}
+
+pub(super) fn expect_bool(value: Value, ctx: &Context) -> EvalResult<bool> {
+    match value {
         Value::Bool(value) => Ok(value),
         other => Err(ctx.error(Error::unexpected(other, "a boolean"))),
     }
 }
 
+pub(super) fn evaluate_object_key(expr: &Expression, ctx: &Context) -> EvalResult<String> {
+    expect_object_key(expr.evaluate(ctx)?, ctx)
+}
+
 // It's not formally defined, but the go HCL implementation allows object key expressions to
 // evaluate to either a string, boolean value or number and will then convert all of these to
 // string. Any other value shall produce an error.
-pub(super) fn evaluate_object_key(expr: &Expression, ctx: &Context) -> EvalResult<String> {
-    match expr.evaluate(ctx)? {
+pub(super) fn expect_object_key(value: Value, ctx: &Context) -> EvalResult<String> {
+    match value {
         Value::String(value) => Ok(value),
         Value::Bool(value) => Ok(value.to_string()),
         Value::Number(value) => Ok(value.to_string()),
@@ -119,7 +127,11 @@ fn evaluate_object_value(mut value: Value, key: &str, ctx: &Context) -> EvalResu
 }
 
 fn evaluate_collection(expr: &Expression, ctx: &Context) -> EvalResult<Vec<(Value, Value)>> {
-    match expr.evaluate(ctx)? {
+    expect_collection(expr.evaluate(ctx)?, ctx)
+}
+
+pub(super) fn expect_collection(value: Value, ctx: &Context) -> EvalResult<Vec<(Value, Value)>> {
+    match value {
         Value::Array(array) => Ok(array
             .into_iter()
             .enumerate()
@@ -1,12 +1,139 @@
+use super::{Context, Evaluate};
+use crate::expr::Expression;
+use crate::types::{self, Type};
 use crate::Value;
 use std::fmt;
 use std::iter;
 use std::ops;
 use std::slice;
+use std::sync::Arc;
 
-/// A type alias for the signature of functions expected by the [`FuncDef`] type.
+/// A type alias for the signature of plain functions accepted by the [`FuncDef`] type.
+///
+/// Besides plain functions, `FuncDef` also accepts closures and any other type implementing the
+/// [`Function`] trait.
 pub type Func = fn(FuncArgs) -> Result<Value, String>;
 
+/// A trait for functions that can be called in HCL expressions.
+///
+/// It is implemented for all closures (and plain functions) with the signature
+/// `Fn(FuncArgs) -> Result<Value, String>` that are `Send + Sync`. This allows functions to
+/// capture state, e.g. a lookup table or a client.
+///
+/// Implement this trait directly for types that need more control over how they are called.
+///
+/// # Example
+///
+/// ```
+/// use hcl::eval::{FuncArgs, FuncDef, Function, ParamType};
+/// use hcl::Value;
+/// use std::collections::HashMap;
+///
+/// struct Lookup {
+///     table: HashMap<String, Value>,
+/// }
+///
+/// impl Function for Lookup {
+///     fn call(&self, args: FuncArgs) -> Result<Value, String> {
+///         let key = args[0].as_str().unwrap();
+///         Ok(self.table.get(key).cloned().unwrap_or(Value::Null))
+///     }
+/// }
+///
+/// let lookup = Lookup {
+///     table: HashMap::from([("foo".into(), Value::from("bar"))]),
+/// };
+///
+/// let func_def = FuncDef::builder()
+///     .param(ParamType::String)
+///     .build(lookup);
+/// ```
+pub trait Function: Send + Sync {
+    /// Calls the function with the provided arguments.
+    ///
+    /// The arguments are already validated against the parameters of the [`FuncDef`] that holds
+    /// the function.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if the function call failed.
+    fn call(&self, args: FuncArgs) -> Result<Value, String>;
+}
+
+impl<F> Function for F
+where
+    F: Fn(FuncArgs) -> Result<Value, String> + Send + Sync,
+{
+    fn call(&self, args: FuncArgs) -> Result<Value, String> {
+        self(args)
+    }
+}
+
+/// A trait for functions whose arguments are not evaluated before the function is called.
+///
+/// Lazy functions receive the unevaluated argument expressions together with the [`Context`] of
+/// the function call. This allows them to decide which arguments to evaluate and to recover from
+/// evaluation errors, which is needed for functions like `try` and `can`.
+///
+/// It is implemented for all closures (and plain functions) with the signature
+/// `Fn(LazyFuncArgs, &Context) -> Result<Value, String>` that are `Send + Sync`. Use
+/// [`FuncDefBuilder::build_lazy`] to create a [`FuncDef`] from a lazy function.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hcl::eval::{Context, Evaluate, FuncDef, LazyFuncArgs, ParamType};
+/// use hcl::expr::{FuncCall, Variable};
+/// use hcl::Value;
+///
+/// fn or_null(args: LazyFuncArgs, ctx: &Context) -> Result<Value, String> {
+///     Ok(args[0].evaluate(ctx).unwrap_or(Value::Null))
+/// }
+///
+/// let mut ctx = Context::new();
+/// ctx.declare_func(
+///     "or_null",
+///     FuncDef::builder().param(ParamType::Any).build_lazy(or_null),
+/// );
+///
+/// let expr = FuncCall::builder("or_null")
+///     .arg(Variable::new("undefined_var")?)
+///     .build();
+///
+/// assert_eq!(expr.evaluate(&ctx)?, Value::Null);
+/// #   Ok(())
+/// # }
+/// ```
+pub trait LazyFunction: Send + Sync {
+    /// Calls the function with the unevaluated arguments and the context of the function call.
+    ///
+    /// The number of arguments is already validated against the parameters of the [`FuncDef`]
+    /// that holds the function. Since the arguments are not evaluated yet, their types are not
+    /// validated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if the function call failed.
+    fn call(&self, args: LazyFuncArgs, ctx: &Context) -> Result<Value, String>;
+}
+
+impl<F> LazyFunction for F
+where
+    F: Fn(LazyFuncArgs, &Context) -> Result<Value, String> + Send + Sync,
+{
+    fn call(&self, args: LazyFuncArgs, ctx: &Context) -> Result<Value, String> {
+        self(args, ctx)
+    }
+}
+
+// The kinds of functions a `FuncDef` can hold.
+#[derive(Clone)]
+enum FuncKind {
+    Eager(Arc<dyn Function>),
+    Lazy(Arc<dyn LazyFunction>),
+}
+
 /// A type hint for a function parameter.
 ///
 /// The parameter type is used to validate the arguments of a function call expression before
@@ -112,13 +239,55 @@ impl fmt::Display for ParamType {
     }
 }
 
+// A function parameter which either validates arguments against a `ParamType` or converts them
+// to a `Type`.
+#[derive(Debug, Clone)]
+enum Param {
+    Validate(ParamType),
+    Convert(Type),
+}
+
+impl Param {
+    // Validates or converts the argument at position `pos`.
+    fn apply(&self, arg: &mut Value, pos: usize, variadic: &str) -> Result<(), String> {
+        match self {
+            Param::Validate(param) => {
+                if param.is_satisfied_by(arg) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "expected {variadic}argument at position {pos} to be of type {param}, got `{arg}`",
+                    ))
+                }
+            }
+            Param::Convert(_) if arg.is_null() => Err(format!(
+                "{variadic}argument at position {pos} must not be null"
+            )),
+            Param::Convert(ty) => {
+                *arg = types::convert(arg.take(), ty).map_err(|err| {
+                    format!("invalid {variadic}argument at position {pos}: {err}")
+                })?;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl From<ParamType> for Param {
+    fn from(param: ParamType) -> Self {
+        Param::Validate(param)
+    }
+}
+
 /// The definition of a function that can be called in HCL expressions.
 ///
 /// It defines the function to call, and number and types of parameters that the function accepts.
 /// The parameter information is used to validate function arguments prior to calling it.
 ///
-/// The signature of a function is defined by the [`Func`][Func] type alias. For available
-/// parameter types see the documentation of [`ParamType`][ParamType].
+/// The function can be a plain function matching the [`Func`][Func] type alias, a closure that
+/// captures state or any other type implementing the [`Function`] trait. Function definitions are
+/// cheap to clone since the function is shared via [`Arc`]. For available parameter types see the
+/// documentation of [`ParamType`][ParamType].
 ///
 /// # Function call evaluation
 ///
@@ -131,6 +300,15 @@ impl fmt::Display for ParamType {
 /// Because all arguments are validated before calling the function, unnecessary length and
 /// type checks on the function arguments can be avoided in the function body.
 ///
+/// Parameters added via [`FuncDefBuilder::typed_param`] and
+/// [`FuncDefBuilder::typed_variadic_param`] convert the arguments to a [`Type`] instead of just
+/// validating them, e.g. the string `"5"` is passed as the number `5` to a `number` parameter.
+/// See the [`types`][crate::types] module for the conversion rules.
+///
+/// Functions built via [`FuncDefBuilder::build_lazy`] are called with the unevaluated argument
+/// expressions instead. Only the number of arguments is validated for them, see the documentation
+/// of [`LazyFunction`] for details.
+///
 /// # Examples
 ///
 /// ```
@@ -171,12 +349,26 @@ impl fmt::Display for ParamType {
 ///     .build(add);
 /// ```
 ///
+/// Closures can capture state from their environment:
+///
+/// ```
+/// # use hcl::eval::{FuncArgs, FuncDef, ParamType};
+/// # use hcl::Value;
+/// let root = String::from("/etc/config");
+///
+/// let func_def = FuncDef::builder()
+///     .param(ParamType::String)
+///     .build(move |args: FuncArgs| -> Result<Value, String> {
+///         Ok(Value::from(format!("{}/{}", root, args[0].as_str().unwrap())))
+///     });
+/// ```
+///
 /// See the documentation of the [`FuncDefBuilder`] for all available methods.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct FuncDef {
-    func: Func,
-    params: Vec<ParamType>,
-    variadic_param: Option<ParamType>,
+    func: FuncKind,
+    params: Vec<Param>,
+    variadic_param: Option<Param>,
 }
 
 impl FuncDef {
@@ -189,8 +381,9 @@ impl FuncDef {
     /// See the type-level documentation of [`FuncDef`] for usage examples.
     ///
     /// [`.builder()`]: FuncDef::builder
-    pub fn new<P>(func: Func, params: P) -> FuncDef
+    pub fn new<F, P>(func: F, params: P) -> FuncDef
     where
+        F: Function + 'static,
         P: IntoIterator<Item = ParamType>,
     {
         FuncDef::builder().params(params).build(func)
@@ -206,43 +399,90 @@ impl FuncDef {
         }
     }
 
-    /// Calls the function with the provided arguments.
-    pub(super) fn call(&self, args: Vec<Value>) -> Result<Value, String> {
-        let params_len = self.params.len();
-        let args_len = args.len();
+    /// Returns `true` if the function expects unevaluated arguments.
+    pub(super) fn is_lazy(&self) -> bool {
+        matches!(self.func, FuncKind::Lazy(_))
+    }
 
-        if args_len < params_len || (self.variadic_param.is_none() && args_len > params_len) {
-            return Err(format!(
-                "expected {params_len} positional arguments, got {args_len}"
-            ));
+    /// Calls the function with the provided evaluated arguments.
+    ///
+    /// Lazy functions receive the arguments as literal expressions together with `ctx`.
+    pub(super) fn call(&self, args: Vec<Value>, ctx: &Context) -> Result<Value, String> {
+        match &self.func {
+            FuncKind::Eager(func) => self.call_eager(func.as_ref(), args),
+            // Already evaluated arguments are valid expressions as well.
+            FuncKind::Lazy(func) => {
+                let args = args.into_iter().map(Expression::from).collect();
+                self.call_lazy_func(func.as_ref(), args, ctx)
+            }
         }
+    }
 
-        let (pos_args, var_args) = args.split_at(params_len);
-
-        for (pos, (arg, param)) in pos_args.iter().zip(self.params.iter()).enumerate() {
-            if !param.is_satisfied_by(arg) {
-                return Err(format!(
-                    "expected argument at position {pos} to be of type {param}, got `{arg}`",
-                ));
+    /// Calls the function with the provided unevaluated arguments.
+    ///
+    /// Functions that are not lazy are called with the evaluated arguments.
+    pub(super) fn call_lazy(&self, args: Vec<Expression>, ctx: &Context) -> Result<Value, String> {
+        match &self.func {
+            FuncKind::Lazy(func) => self.call_lazy_func(func.as_ref(), args, ctx),
+            FuncKind::Eager(func) => {
+                let values = args
+                    .iter()
+                    .map(|arg| arg.evaluate(ctx))
+                    .collect::<Result<_, _>>()
+                    .map_err(|err| err.to_string())?;
+                self.call_eager(func.as_ref(), values)
             }
         }
+    }
+
+    fn call_eager(&self, func: &dyn Function, mut args: Vec<Value>) -> Result<Value, String> {
+        let params_len = self.params.len();
+        self.validate_args_len(args.len())?;
+
+        let (pos_args, var_args) = args.split_at_mut(params_len);
+
+        for (pos, (arg, param)) in pos_args.iter_mut().zip(self.params.iter()).enumerate() {
+            param.apply(arg, pos, "")?;
+        }
 
         if let Some(var_param) = &self.variadic_param {
-            for (pos, arg) in var_args.iter().enumerate() {
-                if !var_param.is_satisfied_by(arg) {
-                    return Err(format!(
-                        "expected variadic argument at position {} to be of type {}, got `{}`",
-                        params_len + pos,
-                        var_param,
-                        arg
-                    ));
-                }
+            for (pos, arg) in var_args.iter_mut().enumerate() {
+                var_param.apply(arg, params_len + pos, "variadic ")?;
             }
         }
 
-        let func_args = FuncArgs::new(args, params_len);
+        func.call(FuncArgs::new(args, params_len))
+    }
+
+    fn call_lazy_func(
+        &self,
+        func: &dyn LazyFunction,
+        args: Vec<Expression>,
+        ctx: &Context,
+    ) -> Result<Value, String> {
+        self.validate_args_len(args.len())?;
+        func.call(LazyFuncArgs::new(args, self.params.len()), ctx)
+    }
+
+    fn validate_args_len(&self, args_len: usize) -> Result<(), String> {
+        let params_len = self.params.len();
+
+        if args_len < params_len || (self.variadic_param.is_none() && args_len > params_len) {
+            Err(format!(
+                "expected {params_len} positional arguments, got {args_len}"
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
 
-        (self.func)(func_args)
+impl fmt::Debug for FuncDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FuncDef")
+            .field("params", &self.params)
+            .field("variadic_param", &self.variadic_param)
+            .finish_non_exhaustive()
     }
 }
 
@@ -255,8 +495,8 @@ impl FuncDef {
 /// [`.builder()`]: FuncDef::builder
 #[derive(Debug)]
 pub struct FuncDefBuilder {
-    params: Vec<ParamType>,
-    variadic_param: Option<ParamType>,
+    params: Vec<Param>,
+    variadic_param: Option<Param>,
 }
 
 impl FuncDefBuilder {
@@ -280,7 +520,47 @@ impl FuncDefBuilder {
     ///     .build(strlen);
     /// ```
     pub fn param(mut self, param: ParamType) -> FuncDefBuilder {
-        self.params.push(param);
+        self.params.push(param.into());
+        self
+    }
+
+    /// Adds a function parameter which converts its argument to the given [`Type`].
+    ///
+    /// Unlike parameters added via [`.param()`], which only validate the argument, the argument
+    /// is converted before it is passed to the function. Arguments that cannot be converted and
+    /// `null` arguments are rejected. See the [`types`][crate::types] module for the conversion
+    /// rules.
+    ///
+    /// [`.param()`]: FuncDefBuilder::param
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use hcl::eval::{Context, Evaluate, FuncArgs, FuncDef};
+    /// use hcl::expr::FuncCall;
+    /// use hcl::types::Type;
+    /// use hcl::Value;
+    ///
+    /// fn double(args: FuncArgs) -> Result<Value, String> {
+    ///     let n = args[0].as_number().unwrap();
+    ///     Ok(Value::Number(*n + *n))
+    /// }
+    ///
+    /// let mut ctx = Context::new();
+    /// ctx.declare_func(
+    ///     "double",
+    ///     FuncDef::builder().typed_param(Type::Number).build(double),
+    /// );
+    ///
+    /// let expr = FuncCall::builder("double").arg("21").build();
+    ///
+    /// assert_eq!(expr.evaluate(&ctx)?, Value::from(42));
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub fn typed_param(mut self, ty: Type) -> FuncDefBuilder {
+        self.params.push(Param::Convert(ty));
         self
     }
 
@@ -311,7 +591,7 @@ impl FuncDefBuilder {
     where
         I: IntoIterator<Item = ParamType>,
     {
-        self.params.extend(params.into_iter());
+        self.params.extend(params.into_iter().map(Param::from));
         self
     }
 
@@ -334,15 +614,60 @@ impl FuncDefBuilder {
     ///     .build(printf);
     /// ```
     pub fn variadic_param(mut self, param: ParamType) -> FuncDefBuilder {
-        self.variadic_param = Some(param);
+        self.variadic_param = Some(param.into());
+        self
+    }
+
+    /// Adds a variadic parameter which converts its arguments to the given [`Type`].
+    ///
+    /// Only one variadic parameter can be added. Subsequent invocation of this method or
+    /// [`.variadic_param()`] will overwrite a previously set variadic parameter. See
+    /// [`.typed_param()`] for details about the argument conversion.
+    ///
+    /// [`.variadic_param()`]: FuncDefBuilder::variadic_param
+    /// [`.typed_param()`]: FuncDefBuilder::typed_param
+    pub fn typed_variadic_param(mut self, ty: Type) -> FuncDefBuilder {
+        self.variadic_param = Some(Param::Convert(ty));
         self
     }
 
     /// Takes ownership of the builder and builds the `FuncDef` for the provided function and the
     /// contents of the builder.
-    pub fn build(self, func: Func) -> FuncDef {
+    ///
+    /// The function can be a plain function, a closure or any other type implementing
+    /// [`Function`].
+    pub fn build<F>(self, func: F) -> FuncDef
+    where
+        F: Function + 'static,
+    {
+        self.build_shared(Arc::new(func))
+    }
+
+    /// Takes ownership of the builder and builds the `FuncDef` for the provided shared function
+    /// and the contents of the builder.
+    ///
+    /// This is useful to declare the same function instance under multiple names or in multiple
+    /// contexts without cloning its state.
+    pub fn build_shared(self, func: Arc<dyn Function>) -> FuncDef {
+        FuncDef {
+            func: FuncKind::Eager(func),
+            params: self.params,
+            variadic_param: self.variadic_param,
+        }
+    }
+
+    /// Takes ownership of the builder and builds the `FuncDef` for the provided lazy function and
+    /// the contents of the builder.
+    ///
+    /// The parameter types are not used to validate the arguments of lazy functions since they
+    /// are not evaluated before the function is called. See the documentation of
+    /// [`LazyFunction`] for details.
+    pub fn build_lazy<F>(self, func: F) -> FuncDef
+    where
+        F: LazyFunction + 'static,
+    {
         FuncDef {
-            func,
+            func: FuncKind::Lazy(Arc::new(func)),
             params: self.params,
             variadic_param: self.variadic_param,
         }
@@ -398,6 +723,52 @@ impl ops::Deref for FuncArgs {
     }
 }
 
+/// Wrapper type for unevaluated function arguments.
+///
+/// It is passed to [`LazyFunction`]s with the argument expressions of the function call
+/// expression. If the final argument is expanded via `...`, it is evaluated and its elements are
+/// passed as individual arguments.
+///
+/// `LazyFuncArgs` behaves exactly like a `Vec<Expression>` due to its `Deref` implementation, but
+/// exposes additional methods to access positional and variadic arguments.
+#[derive(Debug, Clone)]
+pub struct LazyFuncArgs {
+    exprs: Vec<Expression>,
+    pos_args_len: usize,
+}
+
+impl LazyFuncArgs {
+    pub(super) fn new(exprs: Vec<Expression>, pos_args_len: usize) -> LazyFuncArgs {
+        LazyFuncArgs {
+            exprs,
+            pos_args_len,
+        }
+    }
+
+    /// Takes ownership of the function argument expressions.
+    pub fn into_exprs(self) -> Vec<Expression> {
+        self.exprs
+    }
+
+    /// Returns the positional arguments.
+    pub fn positional_args(&self) -> &[Expression] {
+        &self.exprs[..self.pos_args_len]
+    }
+
+    /// Returns the variadic arguments.
+    pub fn variadic_args(&self) -> &[Expression] {
+        &self.exprs[self.pos_args_len..]
+    }
+}
+
+impl ops::Deref for LazyFuncArgs {
+    type Target = Vec<Expression>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.exprs
+    }
+}
+
 /// An iterator over positional function arguments.
 ///
 /// This `struct` is created by the [`positional_args`] method on [`FuncArgs`]. See its
@@ -12,6 +12,20 @@ impl Evaluate for Body {
             .map(|structure| structure.evaluate(ctx))
             .collect()
     }
+
+    fn evaluate_in_place(&mut self, ctx: &Context) -> EvalResult<(), Errors> {
+        let errors: Errors = self
+            .iter_mut()
+            .filter_map(|structure| structure.evaluate_in_place(ctx).err())
+            .flatten()
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
 }
 
 impl private::Sealed for Structure {}
@@ -25,6 +39,13 @@ impl Evaluate for Structure {
             Structure::Block(block) => block.evaluate(ctx).map(Structure::Block),
         }
     }
+
+    fn evaluate_in_place(&mut self, ctx: &Context) -> EvalResult<(), Errors> {
+        match self {
+            Structure::Attribute(attr) => attr.evaluate_in_place(ctx),
+            Structure::Block(block) => block.evaluate_in_place(ctx),
+        }
+    }
 }
 
 impl private::Sealed for Attribute {}
@@ -35,7 +56,24 @@ impl Evaluate for Attribute {
     fn evaluate(&self, ctx: &Context) -> EvalResult<Self::Output> {
         Ok(Attribute {
             key: self.key.clone(),
-            expr: self.expr.evaluate(ctx).map(Into::into)?,
+            expr: self
+                .expr
+                .evaluate(ctx)
+                .map(Into::into)
+                .map_err(|err| err.with_span(self.span()))?,
+            span: self.span.clone(),
+            comments: self.comments.clone(),
+        })
+    }
+
+    fn evaluate_in_place(&mut self, ctx: &Context) -> EvalResult<(), Errors> {
+        self.expr.evaluate_in_place(ctx).map_err(|errors| {
+            let segment = PathSegment::Attribute(self.key.clone());
+
+            errors
+                .into_iter()
+                .map(|err| err.with_span(self.span()).prepend_path(segment.clone()))
+                .collect()
         })
     }
 }
@@ -50,6 +88,19 @@ impl Evaluate for Block {
             identifier: self.identifier.clone(),
             labels: self.labels.clone(),
             body: self.body.evaluate(ctx)?,
+            span: self.span.clone(),
+            comments: self.comments.clone(),
+        })
+    }
+
+    fn evaluate_in_place(&mut self, ctx: &Context) -> EvalResult<(), Errors> {
+        self.body.evaluate_in_place(ctx).map_err(|errors| {
+            let segment = PathSegment::Block(self.identifier.clone(), self.labels.clone());
+
+            errors
+                .into_iter()
+                .map(|err| err.prepend_path(segment.clone()))
+                .collect()
         })
     }
 }
@@ -65,7 +116,7 @@ impl Evaluate for Expression {
             Expression::Array(array) => array.evaluate(ctx).map(Value::Array),
             Expression::Object(object) => object.evaluate(ctx).map(Value::Object),
             Expression::TemplateExpr(expr) => expr.evaluate(ctx),
-            Expression::Variable(ident) => ctx.lookup_var(ident).cloned(),
+            Expression::Variable(ident) => ctx.lookup_var(ident, None),
             Expression::Traversal(traversal) => traversal.evaluate(ctx),
             Expression::FuncCall(func_call) => func_call.evaluate(ctx),
             Expression::Parenthesis(expr) => expr.evaluate(ctx),
@@ -76,6 +127,42 @@ impl Evaluate for Expression {
             other => Ok(Value::from(other.clone())),
         }
     }
+
+    fn evaluate_in_place(&mut self, ctx: &Context) -> EvalResult<(), Errors> {
+        // Arrays and objects are evaluated element-wise to collect the errors of all failing
+        // elements while still replacing the ones that succeed.
+        let errors: Errors = match self {
+            Expression::Array(array) => array
+                .iter_mut()
+                .filter_map(|expr| expr.evaluate_in_place(ctx).err())
+                .flatten()
+                .collect(),
+            Expression::Object(object) => {
+                let mut errors = Vec::new();
+
+                *object = std::mem::take(object)
+                    .into_iter()
+                    .map(|(mut key, mut expr)| {
+                        errors.extend(key.evaluate_in_place(ctx).err());
+                        errors.extend(expr.evaluate_in_place(ctx).err());
+                        (key, expr)
+                    })
+                    .collect();
+
+                errors.into_iter().flatten().collect()
+            }
+            expr => {
+                *expr = expr.evaluate(ctx)?.into();
+                return Ok(());
+            }
+        };
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
 }
 
 impl<T> private::Sealed for Vec<T> where T: Evaluate {}
@@ -124,6 +211,15 @@ impl Evaluate for ObjectKey {
             ident => Ok(ident.to_string()),
         }
     }
+
+    fn evaluate_in_place(&mut self, ctx: &Context) -> EvalResult<(), Errors> {
+        // Identifier keys are already fully evaluated, leave them as is.
+        if let ObjectKey::Expression(expr) = self {
+            *expr = expr::evaluate_object_key(expr, ctx)?.into();
+        }
+
+        Ok(())
+    }
 }
 
 impl private::Sealed for TemplateExpr {}
@@ -167,7 +263,13 @@ impl Evaluate for Traversal {
     type Output = Value;
 
     fn evaluate(&self, ctx: &Context) -> EvalResult<Self::Output> {
-        let value = self.expr.evaluate(ctx)?;
+        let value = match &self.expr {
+            // Pass the traversal on to variable resolvers.
+            Expression::Variable(ident) => ctx
+                .child_with_expr(&self.expr)
+                .lookup_var(ident, Some(self))?,
+            expr => expr.evaluate(ctx)?,
+        };
         let deque = self.operators.iter().collect();
         expr::evaluate_traversal(value, deque, ctx)
     }
@@ -182,18 +284,33 @@ impl Evaluate for FuncCall {
         let name = &self.name;
         let func = ctx.lookup_func(name)?;
         let len = self.args.len();
-        let mut args = Vec::with_capacity(len);
 
-        for (index, arg) in self.args.iter().enumerate() {
-            if self.expand_final && index == len - 1 {
-                args.extend(expr::evaluate_array(arg, ctx)?);
-            } else {
-                args.push(arg.evaluate(ctx)?);
+        let result = if func.is_lazy() {
+            let mut args = self.args.clone();
+
+            if self.expand_final {
+                if let Some(last) = args.pop() {
+                    let values = expr::evaluate_array(&last, ctx)?;
+                    args.extend(values.into_iter().map(Expression::from));
+                }
             }
-        }
 
-        func.call(args)
-            .map_err(|err| ctx.error(ErrorKind::FuncCall(name.clone(), err)))
+            func.call_lazy(args, ctx)
+        } else {
+            let mut args = Vec::with_capacity(len);
+
+            for (index, arg) in self.args.iter().enumerate() {
+                if self.expand_final && index == len - 1 {
+                    args.extend(expr::evaluate_array(arg, ctx)?);
+                } else {
+                    args.push(arg.evaluate(ctx)?);
+                }
+            }
+
+            func.call(args, ctx)
+        };
+
+        result.map_err(|err| ctx.error(ErrorKind::FuncCall(name.clone(), err)))
     }
 }
 
@@ -142,6 +142,80 @@
 //! # }
 //! ```
 //!
+//! ## Partial evaluation
+//!
+//! When not all variables are known yet, the [`PartialEvaluate`] trait can be used instead of
+//! [`Evaluate`]. It treats undeclared variables as unknown values and returns a partially
+//! evaluated [`Body`][crate::Body] or [`Expression`][crate::Expression] in which everything that
+//! only depends on known values is replaced by its evaluated value:
+//!
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use hcl::eval::{Context, PartialEvaluate};
+//!
+//! let input = r#"
+//! size  = count * 2
+//! label = "${name}-${count}"
+//! "#;
+//!
+//! let mut ctx = Context::new();
+//! ctx.declare_var("count", 3);
+//!
+//! let body = hcl::parse(input)?.partial_evaluate(&ctx)?;
+//!
+//! let expected = r#"size = 6
+//! label = "${name}-3"
+//! "#;
+//!
+//! assert_eq!(hcl::to_string(&body)?, expected);
+//! #   Ok(())
+//! # }
+//! ```
+//!
+//! ## Collecting all errors
+//!
+//! [`Evaluate::evaluate`] stops at the first error it encounters. To report all problems in a
+//! body at once, use [`evaluate_in_place`][Evaluate::evaluate_in_place] instead. It evaluates
+//! everything it can, leaves failing expressions untouched and returns every error together with
+//! the path of the attribute it occurred in:
+//!
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use hcl::eval::{Context, Evaluate};
+//!
+//! let input = r#"
+//! service "api" {
+//!   port = 8000 + offset
+//!   name = "api"
+//!   host = upper(hostname)
+//! }
+//! "#;
+//!
+//! let mut body = hcl::parse(input)?;
+//! let errors = body.evaluate_in_place(&Context::new()).unwrap_err();
+//!
+//! assert_eq!(errors.len(), 2);
+//!
+//! let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+//!
+//! assert_eq!(
+//!     messages,
+//!     [
+//!         r#"service "api" > port: undefined variable `offset` in expression `8000 + offset`"#,
+//!         r#"service "api" > host: undefined function `upper` in expression `upper(hostname)`"#,
+//!     ]
+//! );
+//! #   Ok(())
+//! # }
+//! ```
+//!
+//! ## Self-referencing bodies
+//!
+//! Attributes which reference other attributes of the same body, like the ones in Terraform's
+//! `locals` blocks, cannot be evaluated against a fixed [`Context`]. The [`OrderedEvaluator`]
+//! evaluates them in the order of their dependencies instead, declares the results in the context
+//! and reports reference cycles. See its documentation for a usage example.
+//!
 //! ## Expression evaluation during (de-)serialization
 //!
 //! It's possible to evaluate expressions directly when deserializing HCL into a Rust value, or
@@ -203,23 +277,34 @@ mod error;
 mod expr;
 mod func;
 mod impls;
+mod ordered;
+mod partial;
+mod resolver;
+#[cfg(feature = "stdlib")]
+mod stdlib;
 mod template;
 
-pub use self::error::{Error, ErrorKind, EvalResult};
+pub use self::error::{Error, ErrorKind, Errors, EvalResult, PathSegment};
 pub use self::func::{
-    Func, FuncArgs, FuncDef, FuncDefBuilder, ParamType, PositionalArgs, VariadicArgs,
+    Func, FuncArgs, FuncDef, FuncDefBuilder, Function, LazyFuncArgs, LazyFunction, ParamType,
+    PositionalArgs, VariadicArgs,
 };
+pub use self::ordered::OrderedEvaluator;
+pub use self::partial::PartialEvaluate;
+use self::resolver::Resolver;
+pub use self::resolver::VariableResolver;
 use crate::expr::{
     BinaryOp, BinaryOperator, Conditional, Expression, ForExpr, FuncCall, Object, ObjectKey,
     Operation, TemplateExpr, Traversal, TraversalOperator, UnaryOp, UnaryOperator,
 };
 use crate::parser;
-use crate::structure::{Attribute, Block, Body, Structure};
+use crate::structure::{Attribute, Block, BlockLabel, Body, Structure};
 use crate::template::{
     Directive, Element, ForDirective, IfDirective, Interpolation, Strip, Template,
 };
-use crate::{Identifier, Map, Result, Value};
+use crate::{Identifier, Map, Result, Span, Value};
 use serde::{de, ser};
+use std::sync::Arc;
 
 mod private {
     pub trait Sealed {}
@@ -250,16 +335,82 @@ pub trait Evaluate: private::Sealed {
     /// - an undefined variable or function is encountered.
     /// - a defined function is called with unexpected arguments.
     fn evaluate(&self, ctx: &Context) -> EvalResult<Self::Output>;
+
+    /// Recursively tries to evaluate all nested expressions in place.
+    ///
+    /// Unlike [`evaluate`][Evaluate::evaluate], this does not stop at the first error. Every
+    /// expression that can be evaluated successfully is replaced with its value while expressions
+    /// that fail to evaluate are left untouched. Errors of all failing expressions are collected
+    /// and carry the [path][Error::path] of the attribute they occurred in.
+    ///
+    /// See the [module-level documentation][crate::eval] for a usage example.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with a collection of all errors that occurred during evaluation. See
+    /// [`evaluate`][Evaluate::evaluate] for the conditions that lead to an error.
+    fn evaluate_in_place(&mut self, ctx: &Context) -> EvalResult<(), Errors>
+    where
+        Self: Sized,
+        Self::Output: Into<Self>,
+    {
+        *self = self.evaluate(ctx)?.into();
+        Ok(())
+    }
 }
 
 /// A type holding the evaluation context.
 ///
 /// The `Context` is used to declare variables and functions that are evaluated when evaluating a
 /// template or expression.
+///
+/// The lifetime parameter is only used for the scopes that are created internally during
+/// evaluation. Contexts created by users are `Context<'static>`, which is `Send + Sync` and can
+/// be stored in long-lived structs or shared between threads.
+///
+/// To declare variables and functions once and reuse them for many evaluations, put the context
+/// into an [`Arc`] and create cheap scopes on top of it via [`Context::with_base`].
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hcl::eval::{Context, Evaluate};
+/// use hcl::expr::TemplateExpr;
+/// use hcl::Value;
+/// use std::sync::Arc;
+///
+/// let mut base = Context::new();
+/// base.declare_var("greeting", "Hello");
+/// let base = Arc::new(base);
+///
+/// let handles: Vec<_> = ["alice", "bob"]
+///     .into_iter()
+///     .map(|name| {
+///         let mut ctx = Context::with_base(base.clone());
+///         ctx.declare_var("name", name);
+///
+///         std::thread::spawn(move || {
+///             TemplateExpr::from("${greeting}, ${name}!").evaluate(&ctx)
+///         })
+///     })
+///     .collect();
+///
+/// let results = handles
+///     .into_iter()
+///     .map(|handle| handle.join().unwrap())
+///     .collect::<Result<Vec<_>, _>>()?;
+///
+/// assert_eq!(results, [Value::from("Hello, alice!"), Value::from("Hello, bob!")]);
+/// #   Ok(())
+/// # }
+/// ```
 #[derive(Debug, Clone)]
 pub struct Context<'a> {
     vars: Map<Identifier, Value>,
     funcs: Map<Identifier, FuncDef>,
+    resolver: Option<Resolver>,
+    base: Option<Arc<Context<'static>>>,
     parent: Option<&'a Context<'a>>,
     expr: Option<&'a Expression>,
 }
@@ -269,6 +420,8 @@ impl Default for Context<'_> {
         Context {
             vars: Map::new(),
             funcs: Map::new(),
+            resolver: None,
+            base: None,
             parent: None,
             expr: None,
         }
@@ -281,6 +434,73 @@ impl<'a> Context<'a> {
         Context::default()
     }
 
+    /// Creates a `Context` with the standard library of HCL functions already declared.
+    ///
+    /// The functions follow the semantics of the ones provided by the Go
+    /// [`cty/function/stdlib`](https://pkg.go.dev/github.com/zclconf/go-cty/cty/function/stdlib)
+    /// package, which are also the basis for many of the Terraform built-in functions. Their
+    /// arguments are validated using [`ParamType`] before the function is called. The error
+    /// handling functions `can` and `try` follow HCL's `tryfunc` extension and are
+    /// [lazy functions][LazyFunction].
+    ///
+    /// Functions declared later via [`declare_func`][Context::declare_func] replace standard
+    /// library functions of the same name.
+    ///
+    /// The following functions are available:
+    ///
+    /// | Category | Functions |
+    /// |----------|-----------|
+    /// | Numeric | `abs`, `ceil`, `floor`, `max`, `min`, `signum` |
+    /// | String | `chomp`, `format`, `join`, `lower`, `replace`, `split`, `strrev`, `substr`, `title`, `trim`, `trimprefix`, `trimspace`, `trimsuffix`, `upper` |
+    /// | Collection | `coalesce`, `coalescelist`, `compact`, `concat`, `contains`, `distinct`, `element`, `flatten`, `index`, `keys`, `length`, `lookup`, `merge`, `range`, `reverse`, `slice`, `sort`, `values`, `zipmap` |
+    /// | Encoding | `jsondecode`, `jsonencode` |
+    /// | Type conversion | `tobool`, `tonumber`, `tostring` |
+    /// | Error handling | `can`, `try` |
+    ///
+    /// A few deviations from the Go implementation exist: `length` and `substr` count unicode
+    /// scalar values instead of grapheme clusters, `replace` does not support regular
+    /// expressions and `format` supports the `%v`, `%s`, `%q`, `%t`, `%d`, `%f` and `%e` verbs
+    /// with the `-`, `+` and `0` flags, width and precision.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use hcl::Value;
+    /// use hcl::eval::{Context, Evaluate};
+    /// use hcl::expr::TemplateExpr;
+    ///
+    /// let mut ctx = Context::with_stdlib();
+    /// ctx.declare_var("names", vec!["alice", "bob"]);
+    ///
+    /// let expr = TemplateExpr::from(r#"${upper(join(", ", names))}"#);
+    ///
+    /// assert_eq!(expr.evaluate(&ctx)?, Value::from("ALICE, BOB"));
+    /// #   Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "stdlib")]
+    pub fn with_stdlib() -> Self {
+        let mut ctx = Context::new();
+        stdlib::declare(&mut ctx);
+        ctx
+    }
+
+    /// Creates an empty `Context` which layers over a shared base context.
+    ///
+    /// Variables and functions that are not declared in the new context are looked up in the
+    /// base context. Declarations in the new context shadow the ones of the base context, which
+    /// itself is never modified. Creating a context with a base is cheap since the base is not
+    /// cloned.
+    ///
+    /// See the type-level documentation of [`Context`] for a usage example.
+    pub fn with_base(base: Arc<Context<'static>>) -> Self {
+        Context {
+            base: Some(base),
+            ..Context::default()
+        }
+    }
+
     // Create a new child `Context` which has the current one as parent.
     fn child(&self) -> Context<'_> {
         let mut ctx = Context::new();
@@ -346,13 +566,84 @@ impl<'a> Context<'a> {
         self.funcs.insert(name.into(), func);
     }
 
+    /// Sets the resolver that is consulted for variables which are not declared in the context.
+    ///
+    /// Variables declared via [`declare_var`][Context::declare_var] take precedence over the
+    /// ones provided by the resolver. See the documentation of [`VariableResolver`] for more.
+    ///
+    /// Resolved values are cached for as long as the context lives, across all evaluations that
+    /// use it. This keeps values consistent within and between evaluations, but also means that
+    /// changes of the underlying data are not picked up. Use
+    /// [`clear_resolver_cache`][Context::clear_resolver_cache] to resolve variables anew.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hcl::eval::Context;
+    /// use hcl::expr::Traversal;
+    /// use hcl::{Identifier, Value};
+    ///
+    /// let mut ctx = Context::new();
+    /// ctx.set_var_resolver(
+    ///     |name: &Identifier, _: Option<&Traversal>| -> Result<Option<Value>, String> {
+    ///         Ok(std::env::var(name.as_str()).ok().map(Value::from))
+    ///     },
+    /// );
+    /// ```
+    pub fn set_var_resolver<R>(&mut self, resolver: R)
+    where
+        R: VariableResolver + 'static,
+    {
+        self.resolver = Some(Resolver::new(Arc::new(resolver)));
+    }
+
+    /// Clears the values cached by the variable resolver, if any.
+    ///
+    /// Afterwards, the resolver is called again for each variable the next time it is referenced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use hcl::eval::{Context, Evaluate};
+    /// use hcl::expr::{Expression, Traversal, Variable};
+    /// use hcl::{Identifier, Value};
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    ///
+    /// static COUNTER: AtomicU64 = AtomicU64::new(0);
+    ///
+    /// let mut ctx = Context::new();
+    /// ctx.set_var_resolver(
+    ///     |_: &Identifier, _: Option<&Traversal>| -> Result<Option<Value>, String> {
+    ///         Ok(Some(Value::from(COUNTER.fetch_add(1, Ordering::SeqCst))))
+    ///     },
+    /// );
+    ///
+    /// let expr = Expression::from(Variable::new("counter")?);
+    /// assert_eq!(expr.evaluate(&ctx)?, Value::from(0));
+    /// assert_eq!(expr.evaluate(&ctx)?, Value::from(0));
+    ///
+    /// ctx.clear_resolver_cache();
+    /// assert_eq!(expr.evaluate(&ctx)?, Value::from(1));
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub fn clear_resolver_cache(&mut self) {
+        if let Some(resolver) = &self.resolver {
+            resolver.clear();
+        }
+    }
+
     /// Lookup a variable's value.
     ///
     /// When the variable is declared in multiple parent scopes, the innermost variable's value is
-    /// returned.
-    fn lookup_var(&self, name: &Identifier) -> EvalResult<&Value> {
-        self.var(name)
-            .ok_or_else(|| self.error(ErrorKind::UndefinedVar(name.clone())))
+    /// returned. The traversal is passed to variable resolvers if the variable is its root.
+    fn lookup_var(&self, name: &Identifier, traversal: Option<&Traversal>) -> EvalResult<Value> {
+        match self.var(name, traversal) {
+            Ok(Some(value)) => Ok(value),
+            Ok(None) => Err(self.error(ErrorKind::UndefinedVar(name.clone()))),
+            Err(msg) => Err(self.error(ErrorKind::ResolveVar(name.clone(), msg))),
+        }
     }
 
     /// Lookup a function definition.
@@ -377,16 +668,36 @@ impl<'a> Context<'a> {
         }
     }
 
-    fn var(&self, name: &Identifier) -> Option<&Value> {
-        self.vars
-            .get(name)
-            .or_else(|| self.parent.and_then(|parent| parent.var(name)))
+    fn var(
+        &self,
+        name: &Identifier,
+        traversal: Option<&Traversal>,
+    ) -> Result<Option<Value>, String> {
+        if let Some(value) = self.vars.get(name) {
+            return Ok(Some(value.clone()));
+        }
+
+        if let Some(resolver) = &self.resolver {
+            if let Some(value) = resolver.resolve(name, traversal)? {
+                return Ok(Some(value));
+            }
+        }
+
+        match (self.parent, &self.base) {
+            (Some(parent), _) => parent.var(name, traversal),
+            (None, Some(base)) => base.var(name, traversal),
+            (None, None) => Ok(None),
+        }
     }
 
     fn func(&self, name: &Identifier) -> Option<&FuncDef> {
         self.funcs
             .get(name)
-            .or_else(|| self.parent.and_then(|parent| parent.func(name)))
+            .or_else(|| match (self.parent, &self.base) {
+                (Some(parent), _) => parent.func(name),
+                (None, Some(base)) => base.func(name),
+                (None, None) => None,
+            })
     }
 
     fn expr(&self) -> Option<&Expression> {
@@ -416,7 +727,7 @@ pub fn from_str<T>(s: &str, ctx: &Context) -> Result<T>
 where
     T: de::DeserializeOwned,
 {
-    let body = parser::parse(s)?;
+    let body = parser::parse_with_spans(s)?;
     let evaluated = body.evaluate(ctx)?;
     super::from_body(evaluated)
 }
@@ -0,0 +1,388 @@
+use super::*;
+
+/// Evaluates the attributes of a [`Body`] in the order of their dependencies.
+///
+/// [`Evaluate::evaluate`] evaluates attributes in the order in which they appear in the body
+/// against a fixed [`Context`]. This does not work for bodies whose attributes reference each
+/// other, like Terraform's `locals` blocks. The `OrderedEvaluator` extracts the references of each
+/// attribute, evaluates the attributes they depend on first and declares each result in the
+/// context before moving on. References can either be plain variables or attribute accesses on a
+/// namespace variable, see [`OrderedEvaluator::with_namespace`].
+///
+/// Only the attributes of the body take part in the ordering. Blocks are evaluated after all
+/// attributes and can thus reference any of them.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hcl::eval::{Context, Evaluate, OrderedEvaluator};
+/// use hcl::expr::TemplateExpr;
+/// use hcl::Value;
+///
+/// let input = r#"
+/// url  = "${local.host}:${local.port}"
+/// port = base_port + 1
+/// host = "localhost"
+/// "#;
+///
+/// let mut ctx = Context::new();
+/// ctx.declare_var("base_port", 8000);
+///
+/// let body = hcl::parse(input)?;
+/// let locals = OrderedEvaluator::with_namespace("local").evaluate(&body, &mut ctx)?;
+///
+/// let expected = r#"url = "localhost:8001"
+/// port = 8001
+/// host = "localhost"
+/// "#;
+///
+/// assert_eq!(hcl::to_string(&locals)?, expected);
+///
+/// // The results are available in the context afterwards.
+/// let expr = TemplateExpr::from("${local.url}/api");
+/// assert_eq!(expr.evaluate(&ctx)?, Value::from("localhost:8001/api"));
+/// #   Ok(())
+/// # }
+/// ```
+///
+/// Reference cycles are reported as [`ErrorKind::ReferenceCycle`]:
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hcl::eval::{Context, ErrorKind, OrderedEvaluator};
+///
+/// let body = hcl::parse("a = b + 1\nb = c + 1\nc = a + 1")?;
+/// let err = OrderedEvaluator::new()
+///     .evaluate(&body, &mut Context::new())
+///     .unwrap_err();
+///
+/// assert!(matches!(err.kind(), ErrorKind::ReferenceCycle(_)));
+/// assert_eq!(err.to_string(), "reference cycle: `a` -> `b` -> `c` -> `a`");
+/// #   Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OrderedEvaluator {
+    namespace: Option<Identifier>,
+}
+
+impl OrderedEvaluator {
+    /// Creates an `OrderedEvaluator` which treats variables as references to attributes and
+    /// declares the evaluated attributes as variables of the same name.
+    pub fn new() -> OrderedEvaluator {
+        OrderedEvaluator::default()
+    }
+
+    /// Creates an `OrderedEvaluator` which treats attribute accesses on the `namespace` variable
+    /// as references to attributes, e.g. `local.a` for the namespace `local`.
+    ///
+    /// Attributes which index the namespace dynamically, e.g. `local[var.key]`, are evaluated
+    /// after all attributes with static references. A dynamic lookup can therefore not refer to
+    /// another attribute with a dynamic lookup.
+    ///
+    /// The evaluated attributes are declared as an object variable named `namespace`. If the
+    /// context already declares an object variable with this name, the attributes are merged into
+    /// it, which allows evaluating multiple bodies into the same namespace.
+    pub fn with_namespace<I>(namespace: I) -> OrderedEvaluator
+    where
+        I: Into<Identifier>,
+    {
+        OrderedEvaluator {
+            namespace: Some(namespace.into()),
+        }
+    }
+
+    /// Evaluates all attributes and blocks of `body` and declares the attribute values in `ctx`.
+    ///
+    /// Returns the evaluated body. The order of its structures is preserved.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with an [`ErrorKind::ReferenceCycle`] error if attributes depend on
+    /// each other, or with any error described in the documentation of
+    /// [`Evaluate::evaluate`].
+    pub fn evaluate(&self, body: &Body, ctx: &mut Context) -> EvalResult<Body> {
+        let attrs: Vec<&Attribute> = body.attributes().collect();
+        let index: Map<&str, usize> = attrs
+            .iter()
+            .enumerate()
+            .map(|(i, attr)| (attr.key(), i))
+            .collect();
+
+        let refs: Vec<References> = attrs
+            .iter()
+            .map(|attr| {
+                let mut refs = References::new(self.namespace.as_ref());
+                refs.expr(&attr.expr);
+                refs
+            })
+            .collect();
+
+        let deps = refs
+            .iter()
+            .map(|attr_refs| {
+                if attr_refs.all {
+                    // A dynamic lookup depends on all attributes with static references. Other
+                    // dynamic lookups are excluded, since two of them would otherwise form a
+                    // cycle, and so is the attribute itself, which is not declared yet.
+                    (0..attrs.len()).filter(|&j| !refs[j].all).collect()
+                } else {
+                    attr_refs
+                        .names
+                        .iter()
+                        .filter_map(|name| index.get(name.as_str()).copied())
+                        .collect()
+                }
+            })
+            .collect();
+
+        let mut graph = Graph {
+            state: vec![State::Pending; attrs.len()],
+            values: vec![None; attrs.len()],
+            stack: Vec::new(),
+            attrs,
+            deps,
+        };
+
+        for i in 0..graph.attrs.len() {
+            graph.visit(i, self, ctx)?;
+        }
+
+        let mut values = graph.values.into_iter().flatten();
+
+        body.iter()
+            .map(|structure| match structure {
+                Structure::Attribute(attr) => Ok(Structure::Attribute(Attribute {
+                    key: attr.key.clone(),
+                    expr: values.next().expect("attribute was evaluated").into(),
+                    span: attr.span.clone(),
+                    comments: attr.comments.clone(),
+                })),
+                Structure::Block(block) => block.evaluate(ctx).map(Structure::Block),
+            })
+            .collect()
+    }
+
+    // Declares the value of an evaluated attribute in the context.
+    fn declare(&self, key: &Identifier, value: Value, ctx: &mut Context) {
+        match &self.namespace {
+            Some(namespace) => match ctx.vars.get_mut(namespace) {
+                Some(Value::Object(object)) => {
+                    object.insert(key.to_string(), value);
+                }
+                _ => ctx.declare_var(
+                    namespace.clone(),
+                    Value::from_iter([(key.to_string(), value)]),
+                ),
+            },
+            None => ctx.declare_var(key.clone(), value),
+        }
+    }
+}
+
+// The dependency graph of the attributes of a body.
+struct Graph<'a> {
+    attrs: Vec<&'a Attribute>,
+    // The indices of the attributes that each attribute references.
+    deps: Vec<Vec<usize>>,
+    state: Vec<State>,
+    // The attributes that are currently visited, used to report the full reference cycle.
+    stack: Vec<usize>,
+    values: Vec<Option<Value>>,
+}
+
+impl Graph<'_> {
+    // Evaluates the attribute at index `i` after evaluating all of its dependencies.
+    fn visit(
+        &mut self,
+        i: usize,
+        evaluator: &OrderedEvaluator,
+        ctx: &mut Context,
+    ) -> EvalResult<()> {
+        match self.state[i] {
+            State::Done => return Ok(()),
+            State::Visiting => {
+                let start = self.stack.iter().position(|&j| j == i).unwrap_or_default();
+                let cycle = self.stack[start..]
+                    .iter()
+                    .map(|&j| self.attrs[j].key.clone())
+                    .collect();
+
+                return Err(
+                    Error::new(ErrorKind::ReferenceCycle(cycle)).with_span(self.attrs[i].span())
+                );
+            }
+            State::Pending => {}
+        }
+
+        self.state[i] = State::Visiting;
+        self.stack.push(i);
+
+        for n in 0..self.deps[i].len() {
+            self.visit(self.deps[i][n], evaluator, ctx)?;
+        }
+
+        let attr = self.attrs[i];
+        let value = attr
+            .expr
+            .evaluate(ctx)
+            .map_err(|err| err.with_span(attr.span()))?;
+
+        evaluator.declare(&attr.key, value.clone(), ctx);
+        self.values[i] = Some(value);
+        self.stack.pop();
+        self.state[i] = State::Done;
+        Ok(())
+    }
+}
+
+// The evaluation state of an attribute.
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Pending,
+    Visiting,
+    Done,
+}
+
+// Collects the names of the attributes that an expression references.
+struct References<'a> {
+    namespace: Option<&'a Identifier>,
+    // Variables declared by enclosing `for` expressions and directives.
+    shadowed: Vec<Identifier>,
+    names: Vec<Identifier>,
+    // Set if the namespace is referenced as a whole, e.g. `local[var.key]`.
+    all: bool,
+}
+
+impl<'a> References<'a> {
+    fn new(namespace: Option<&'a Identifier>) -> References<'a> {
+        References {
+            namespace,
+            shadowed: Vec::new(),
+            names: Vec::new(),
+            all: false,
+        }
+    }
+
+    fn add(&mut self, name: Identifier) {
+        if !self.names.contains(&name) {
+            self.names.push(name);
+        }
+    }
+
+    fn variable(&mut self, var: &Identifier, operator: Option<&TraversalOperator>) {
+        if self.shadowed.contains(var) {
+            return;
+        }
+
+        match self.namespace {
+            None => self.add(var.clone()),
+            Some(namespace) if namespace == var => match operator {
+                Some(TraversalOperator::GetAttr(name)) => self.add(name.clone()),
+                Some(TraversalOperator::Index(Expression::String(name))) => {
+                    self.add(Identifier::unchecked(name));
+                }
+                _ => self.all = true,
+            },
+            Some(_) => {}
+        }
+    }
+
+    fn with_shadowed<F>(&mut self, key_var: Option<&Identifier>, value_var: &Identifier, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let len = self.shadowed.len();
+        self.shadowed.extend(key_var.cloned());
+        self.shadowed.push(value_var.clone());
+        f(self);
+        self.shadowed.truncate(len);
+    }
+
+    fn expr(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Array(array) => array.iter().for_each(|expr| self.expr(expr)),
+            Expression::Object(object) => {
+                for (key, value) in object {
+                    if let ObjectKey::Expression(key) = key {
+                        self.expr(key);
+                    }
+                    self.expr(value);
+                }
+            }
+            Expression::TemplateExpr(expr) => {
+                // Templates that fail to parse are reported during evaluation.
+                if let Ok(template) = Template::from_expr(expr) {
+                    self.template(&template);
+                }
+            }
+            Expression::Variable(var) => self.variable(var, None),
+            Expression::Traversal(traversal) => {
+                match &traversal.expr {
+                    Expression::Variable(var) => self.variable(var, traversal.operators.first()),
+                    expr => self.expr(expr),
+                }
+
+                for operator in &traversal.operators {
+                    if let TraversalOperator::Index(expr) = operator {
+                        self.expr(expr);
+                    }
+                }
+            }
+            Expression::FuncCall(func_call) => func_call.args.iter().for_each(|arg| self.expr(arg)),
+            Expression::Parenthesis(expr) => self.expr(expr),
+            Expression::Conditional(cond) => {
+                self.expr(&cond.cond_expr);
+                self.expr(&cond.true_expr);
+                self.expr(&cond.false_expr);
+            }
+            Expression::Operation(op) => match &**op {
+                Operation::Unary(op) => self.expr(&op.expr),
+                Operation::Binary(op) => {
+                    self.expr(&op.lhs_expr);
+                    self.expr(&op.rhs_expr);
+                }
+            },
+            Expression::ForExpr(for_expr) => {
+                self.expr(&for_expr.collection_expr);
+                self.with_shadowed(for_expr.key_var.as_ref(), &for_expr.value_var, |refs| {
+                    if let Some(key_expr) = &for_expr.key_expr {
+                        refs.expr(key_expr);
+                    }
+                    refs.expr(&for_expr.value_expr);
+                    if let Some(cond_expr) = &for_expr.cond_expr {
+                        refs.expr(cond_expr);
+                    }
+                });
+            }
+            Expression::Null
+            | Expression::Bool(_)
+            | Expression::Number(_)
+            | Expression::String(_)
+            | Expression::Raw(_) => {}
+        }
+    }
+
+    fn template(&mut self, template: &Template) {
+        for element in template.elements() {
+            match element {
+                Element::Literal(_) => {}
+                Element::Interpolation(interp) => self.expr(&interp.expr),
+                Element::Directive(Directive::If(dir)) => {
+                    self.expr(&dir.cond_expr);
+                    self.template(&dir.true_template);
+                    if let Some(false_template) = &dir.false_template {
+                        self.template(false_template);
+                    }
+                }
+                Element::Directive(Directive::For(dir)) => {
+                    self.expr(&dir.collection_expr);
+                    self.with_shadowed(dir.key_var.as_ref(), &dir.value_var, |refs| {
+                        refs.template(&dir.template);
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,722 @@
+use super::*;
+use crate::util::is_templated;
+use indexmap::map::Entry;
+
+/// A trait for partially evaluating the HCL template and expression sub-languages.
+///
+/// In contrast to [`Evaluate`], variables which are not declared in the [`Context`] do not cause
+/// an [`ErrorKind::UndefinedVar`] error. Instead, they are treated as *unknown* values. Unknown
+/// values propagate through operations, conditionals, `for` expressions, function calls and
+/// templates, similar to how unknown values work in
+/// [`cty`](https://github.com/zclconf/go-cty/blob/main/docs/concepts.md#unknown-values-and-the-dynamic-pseudo-type).
+///
+/// The result is a partially evaluated value: all parts that only depend on known values are
+/// replaced by their evaluated literal values, while all parts that depend on unknown values are
+/// kept as symbolic expressions. The partially evaluated result can be evaluated again later once
+/// the missing variables are known.
+///
+/// A few rules apply to unknown values:
+///
+/// - operations, function calls and traversals are only evaluated if all of their operands or
+///   arguments are known.
+/// - a conditional with an unknown condition keeps both (partially evaluated) branches.
+/// - a `for` expression with an unknown collection, or an unknown condition, key or value
+///   expression for any element, is kept as a whole while its collection expression is still
+///   evaluated.
+/// - known interpolations and directives of a template are replaced by their evaluated strings,
+///   while unknown ones are kept. An `if` directive with a known condition is replaced by the
+///   partially evaluated branch it selects. A `for` directive is kept as a whole if any of its
+///   iterations depends on unknown values.
+///
+/// This trait is sealed to prevent implementation outside of this crate.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hcl::Expression;
+/// use hcl::eval::{Context, PartialEvaluate};
+/// use hcl::expr::{BinaryOp, BinaryOperator, Conditional, Variable};
+///
+/// // cond ? a * 2 : b + 1
+/// let expr = Expression::from(Conditional::new(
+///     Variable::unchecked("cond"),
+///     BinaryOp::new(Variable::unchecked("a"), BinaryOperator::Mul, 2),
+///     BinaryOp::new(Variable::unchecked("b"), BinaryOperator::Plus, 1),
+/// ));
+///
+/// let mut ctx = Context::new();
+/// ctx.declare_var("a", 21);
+///
+/// // `cond` and `b` are unknown.
+/// let partial = expr.partial_evaluate(&ctx)?;
+/// assert_eq!(partial.to_string(), "cond ? 42 : b + 1");
+///
+/// // Once `cond` is known, only the selected branch is evaluated.
+/// ctx.declare_var("cond", true);
+/// assert_eq!(partial.partial_evaluate(&ctx)?, Expression::from(42));
+/// #   Ok(())
+/// # }
+/// ```
+pub trait PartialEvaluate: private::Sealed {
+    /// The type that is returned by [`partial_evaluate`][PartialEvaluate::partial_evaluate] on
+    /// success.
+    type Output;
+
+    /// Recursively evaluates all HCL templates and expressions in the implementing type using the
+    /// variables and functions declared in the `Context`, treating undeclared variables as
+    /// unknown values.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with an error under the same conditions as
+    /// [`Evaluate::evaluate`][Evaluate::evaluate], except for undefined variables.
+    fn partial_evaluate(&self, ctx: &Context) -> EvalResult<Self::Output>;
+}
+
+impl PartialEvaluate for Body {
+    type Output = Self;
+
+    fn partial_evaluate(&self, ctx: &Context) -> EvalResult<Self::Output> {
+        self.iter()
+            .map(|structure| structure.partial_evaluate(ctx))
+            .collect()
+    }
+}
+
+impl PartialEvaluate for Structure {
+    type Output = Self;
+
+    fn partial_evaluate(&self, ctx: &Context) -> EvalResult<Self::Output> {
+        match self {
+            Structure::Attribute(attr) => attr.partial_evaluate(ctx).map(Structure::Attribute),
+            Structure::Block(block) => block.partial_evaluate(ctx).map(Structure::Block),
+        }
+    }
+}
+
+impl PartialEvaluate for Attribute {
+    type Output = Self;
+
+    fn partial_evaluate(&self, ctx: &Context) -> EvalResult<Self::Output> {
+        Ok(Attribute {
+            key: self.key.clone(),
+            expr: self
+                .expr
+                .partial_evaluate(ctx)
+                .map_err(|err| err.with_span(self.span()))?,
+            span: self.span.clone(),
+            comments: self.comments.clone(),
+        })
+    }
+}
+
+impl PartialEvaluate for Block {
+    type Output = Self;
+
+    fn partial_evaluate(&self, ctx: &Context) -> EvalResult<Self::Output> {
+        Ok(Block {
+            identifier: self.identifier.clone(),
+            labels: self.labels.clone(),
+            body: self.body.partial_evaluate(ctx)?,
+            span: self.span.clone(),
+            comments: self.comments.clone(),
+        })
+    }
+}
+
+impl PartialEvaluate for Expression {
+    type Output = Self;
+
+    fn partial_evaluate(&self, ctx: &Context) -> EvalResult<Self::Output> {
+        partial_evaluate(self, ctx).map(Partial::into_expr)
+    }
+}
+
+// The result of partially evaluating an expression.
+enum Partial {
+    // The expression evaluated to a known value.
+    Known(Value),
+    // The expression depends on unknown values. Holds the partially evaluated expression.
+    Unknown(Expression),
+}
+
+impl Partial {
+    fn is_known(&self) -> bool {
+        matches!(self, Partial::Known(_))
+    }
+
+    // Must only be called on known partials.
+    fn into_value(self) -> Value {
+        match self {
+            Partial::Known(value) => value,
+            Partial::Unknown(_) => unreachable!("unknown value"),
+        }
+    }
+
+    fn into_expr(self) -> Expression {
+        match self {
+            Partial::Known(value) => Expression::from(value),
+            Partial::Unknown(expr) => expr,
+        }
+    }
+}
+
+// Returns the values of all partials if all of them are known, or their expressions otherwise.
+fn collect_known(partials: Vec<Partial>) -> Result<Vec<Value>, Vec<Expression>> {
+    if partials.iter().all(Partial::is_known) {
+        Ok(partials.into_iter().map(Partial::into_value).collect())
+    } else {
+        Err(partials.into_iter().map(Partial::into_expr).collect())
+    }
+}
+
+// Wraps operation and conditional expressions in parenthesis so that they are not subject to
+// operator precedence rules once they become the operand of another operation.
+fn parenthesize(expr: Expression) -> Expression {
+    match expr {
+        Expression::Operation(_) | Expression::Conditional(_) => {
+            Expression::Parenthesis(Box::new(expr))
+        }
+        expr => expr,
+    }
+}
+
+fn partial_evaluate(expr: &Expression, ctx: &Context) -> EvalResult<Partial> {
+    let ctx = &ctx.child_with_expr(expr);
+    match expr {
+        Expression::Array(array) => partial_evaluate_array(array, ctx),
+        Expression::Object(object) => partial_evaluate_object(object, ctx),
+        Expression::TemplateExpr(template_expr) => {
+            partial_evaluate_template_expr(template_expr, ctx)
+        }
+        Expression::Variable(ident) => partial_evaluate_var(ident, None, expr, ctx),
+        Expression::Traversal(traversal) => partial_evaluate_traversal(traversal, ctx),
+        Expression::FuncCall(func_call) => partial_evaluate_func_call(func_call, ctx),
+        Expression::Parenthesis(inner) => Ok(match partial_evaluate(inner, ctx)? {
+            Partial::Unknown(expr) => Partial::Unknown(Expression::Parenthesis(Box::new(expr))),
+            known => known,
+        }),
+        Expression::Conditional(cond) => partial_evaluate_conditional(cond, ctx),
+        Expression::Operation(op) => partial_evaluate_operation(op, ctx),
+        Expression::ForExpr(for_expr) => partial_evaluate_for_expr(for_expr, ctx),
+        other => other.evaluate(ctx).map(Partial::Known),
+    }
+}
+
+fn partial_evaluate_array(array: &[Expression], ctx: &Context) -> EvalResult<Partial> {
+    let partials = array
+        .iter()
+        .map(|expr| partial_evaluate(expr, ctx))
+        .collect::<EvalResult<_>>()?;
+
+    Ok(match collect_known(partials) {
+        Ok(values) => Partial::Known(Value::Array(values)),
+        Err(exprs) => Partial::Unknown(Expression::Array(exprs)),
+    })
+}
+
+fn partial_evaluate_object(
+    object: &Object<ObjectKey, Expression>,
+    ctx: &Context,
+) -> EvalResult<Partial> {
+    let mut entries = Vec::with_capacity(object.len());
+    let mut known = true;
+
+    for (key, value) in object {
+        let key = match key {
+            ObjectKey::Identifier(ident) => Ok(ident.to_string()),
+            ObjectKey::Expression(expr) => match partial_evaluate(expr, ctx)? {
+                Partial::Known(value) => Ok(expr::expect_object_key(value, ctx)?),
+                Partial::Unknown(expr) => Err(expr),
+            },
+        };
+
+        let value = partial_evaluate(value, ctx)?;
+        known &= key.is_ok() && value.is_known();
+        entries.push((key, value));
+    }
+
+    if known {
+        return Ok(Partial::Known(Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.unwrap(), value.into_value()))
+                .collect(),
+        )));
+    }
+
+    let object = object
+        .keys()
+        .zip(entries)
+        .map(|(orig_key, (key, value))| {
+            let key = match (orig_key, key) {
+                (ObjectKey::Identifier(ident), _) => ObjectKey::Identifier(ident.clone()),
+                (_, Ok(key)) => ObjectKey::from(key),
+                (_, Err(expr)) => ObjectKey::Expression(expr),
+            };
+
+            (key, value.into_expr())
+        })
+        .collect();
+
+    Ok(Partial::Unknown(Expression::Object(object)))
+}
+
+fn partial_evaluate_template_expr(
+    template_expr: &TemplateExpr,
+    ctx: &Context,
+) -> EvalResult<Partial> {
+    let template = Template::from_expr(template_expr)?;
+    let elements = template.elements();
+
+    match elements.first() {
+        // Interpolation unwrapping, see the `Evaluate` implementation for `TemplateExpr`.
+        Some(Element::Interpolation(interp)) if elements.len() == 1 => {
+            partial_evaluate(&interp.expr, ctx)
+        }
+        _ => {
+            let mut partial = Vec::with_capacity(elements.len());
+            partial_evaluate_template(&mut partial, &template, ctx, Strip::None, Strip::None)?;
+
+            Ok(match partial.as_slice() {
+                [] => Partial::Known(Value::String(String::new())),
+                [Element::Literal(literal)] => Partial::Known(Value::String(literal.clone())),
+                // A single remaining interpolation would be subject to interpolation unwrapping
+                // once the template is evaluated, which changes the result if it is not a
+                // string. Keep the template as is.
+                [Element::Interpolation(_)] => {
+                    Partial::Unknown(Expression::from(template_expr.clone()))
+                }
+                _ => {
+                    let template = Template::from_iter(partial);
+                    Partial::Unknown(Expression::from(TemplateExpr::from(template.to_string())))
+                }
+            })
+        }
+    }
+}
+
+// Partially evaluates the elements of `template` and appends them to `elements`, mirroring
+// `template::evaluate_template`. Known interpolations and directives become literals, while
+// unknown ones are kept. The whitespace stripping of kept elements is applied to their
+// surrounding literals right away.
+fn partial_evaluate_template(
+    elements: &mut Vec<Element>,
+    template: &Template,
+    ctx: &Context,
+    prev_strip: Strip,
+    next_strip: Strip,
+) -> EvalResult<()> {
+    let elems = template.elements();
+
+    for (index, elem) in elems.iter().enumerate() {
+        match elem {
+            Element::Literal(literal) => {
+                let prev = index
+                    .checked_sub(1)
+                    .map_or(prev_strip, |i| elems[i].strip());
+                let next = elems.get(index + 1).map_or(next_strip, Element::strip);
+                push_literal(elements, template::strip_literal(literal, prev, next));
+            }
+            Element::Interpolation(interp) => match partial_evaluate(&interp.expr, ctx)? {
+                Partial::Known(value) => {
+                    let string = template::interpolated_string(value);
+
+                    // Template sequences in the value would be interpreted once the template is
+                    // evaluated again, so the interpolation is kept in this case.
+                    if is_templated(&string) {
+                        elements.push(Element::from(Interpolation::new(interp.expr.clone())));
+                    } else {
+                        push_literal(elements, &string);
+                    }
+                }
+                Partial::Unknown(expr) => elements.push(Element::from(Interpolation::new(expr))),
+            },
+            Element::Directive(Directive::If(dir)) => {
+                partial_evaluate_if_directive(elements, dir, ctx)?;
+            }
+            Element::Directive(Directive::For(dir)) => {
+                partial_evaluate_for_directive(elements, dir, ctx)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn partial_evaluate_if_directive(
+    elements: &mut Vec<Element>,
+    dir: &IfDirective,
+    ctx: &Context,
+) -> EvalResult<()> {
+    match partial_evaluate(&dir.cond_expr, ctx)? {
+        Partial::Known(cond) => {
+            if expr::expect_bool(cond, ctx)? {
+                let next_strip = if dir.false_template.is_some() {
+                    dir.else_strip
+                } else {
+                    dir.endif_strip
+                };
+                partial_evaluate_template(
+                    elements,
+                    &dir.true_template,
+                    ctx,
+                    dir.if_strip,
+                    next_strip,
+                )
+            } else if let Some(false_template) = &dir.false_template {
+                partial_evaluate_template(
+                    elements,
+                    false_template,
+                    ctx,
+                    dir.else_strip,
+                    dir.endif_strip,
+                )
+            } else {
+                Ok(())
+            }
+        }
+        Partial::Unknown(cond_expr) => {
+            push_directive(
+                elements,
+                Directive::If(IfDirective {
+                    cond_expr,
+                    ..dir.clone()
+                }),
+            );
+            Ok(())
+        }
+    }
+}
+
+fn partial_evaluate_for_directive(
+    elements: &mut Vec<Element>,
+    dir: &ForDirective,
+    ctx: &Context,
+) -> EvalResult<()> {
+    let collection = match partial_evaluate(&dir.collection_expr, ctx)? {
+        Partial::Known(value) => value,
+        Partial::Unknown(collection_expr) => {
+            push_directive(
+                elements,
+                Directive::For(ForDirective {
+                    collection_expr,
+                    ..dir.clone()
+                }),
+            );
+            return Ok(());
+        }
+    };
+
+    let mut partial = Vec::new();
+
+    for (key, value) in expr::expect_collection(collection, ctx)? {
+        let mut ctx = ctx.child();
+        if let Some(key_var) = &dir.key_var {
+            ctx.declare_var(key_var.clone(), key);
+        }
+        ctx.declare_var(dir.value_var.clone(), value);
+
+        partial_evaluate_template(
+            &mut partial,
+            &dir.template,
+            &ctx,
+            dir.for_strip,
+            dir.endfor_strip,
+        )?;
+    }
+
+    // Unknown elements may reference the directive's variables which are out of scope in the
+    // result, so the directive is kept as is in this case.
+    if partial
+        .iter()
+        .all(|elem| matches!(elem, Element::Literal(_)))
+    {
+        for elem in partial {
+            if let Element::Literal(literal) = elem {
+                push_literal(elements, &literal);
+            }
+        }
+    } else {
+        push_directive(elements, Directive::For(dir.clone()));
+    }
+
+    Ok(())
+}
+
+// Appends a literal to `elements`, merging it with a preceding literal.
+fn push_literal(elements: &mut Vec<Element>, literal: &str) {
+    if literal.is_empty() {
+        return;
+    }
+
+    match elements.last_mut() {
+        Some(Element::Literal(last)) => last.push_str(literal),
+        _ => elements.push(Element::from(literal)),
+    }
+}
+
+// Appends a directive that is kept in the result. The surrounding literals were already stripped,
+// so the directive must not strip them again once the template is evaluated.
+fn push_directive(elements: &mut Vec<Element>, mut dir: Directive) {
+    let (start, end) = match &mut dir {
+        Directive::If(dir) => (&mut dir.if_strip, &mut dir.endif_strip),
+        Directive::For(dir) => (&mut dir.for_strip, &mut dir.endfor_strip),
+    };
+
+    *start = Strip::from((false, start.strip_end()));
+    *end = Strip::from((end.strip_start(), false));
+    elements.push(Element::from(dir));
+}
+
+fn partial_evaluate_var(
+    ident: &Identifier,
+    traversal: Option<&Traversal>,
+    expr: &Expression,
+    ctx: &Context,
+) -> EvalResult<Partial> {
+    match ctx.var(ident, traversal) {
+        Ok(Some(value)) => Ok(Partial::Known(value)),
+        Ok(None) => Ok(Partial::Unknown(expr.clone())),
+        Err(msg) => Err(ctx.error(ErrorKind::ResolveVar(ident.clone(), msg))),
+    }
+}
+
+fn partial_evaluate_traversal(traversal: &Traversal, ctx: &Context) -> EvalResult<Partial> {
+    let base = match &traversal.expr {
+        // Pass the traversal on to variable resolvers.
+        Expression::Variable(ident) => {
+            let ctx = &ctx.child_with_expr(&traversal.expr);
+            partial_evaluate_var(ident, Some(traversal), &traversal.expr, ctx)?
+        }
+        expr => partial_evaluate(expr, ctx)?,
+    };
+    let mut known = base.is_known();
+    let mut operators = Vec::with_capacity(traversal.operators.len());
+
+    for operator in &traversal.operators {
+        let operator = match operator {
+            TraversalOperator::Index(index_expr) => {
+                let index = partial_evaluate(index_expr, ctx)?;
+                known &= index.is_known();
+                TraversalOperator::Index(index.into_expr())
+            }
+            other => other.clone(),
+        };
+
+        operators.push(operator);
+    }
+
+    match base {
+        Partial::Known(value) if known => {
+            expr::evaluate_traversal(value, operators.iter().collect(), ctx).map(Partial::Known)
+        }
+        base => Ok(Partial::Unknown(Expression::from(Traversal::new(
+            base.into_expr(),
+            operators,
+        )))),
+    }
+}
+
+fn partial_evaluate_func_call(func_call: &FuncCall, ctx: &Context) -> EvalResult<Partial> {
+    // Fail early for undefined functions, even if some of the arguments are unknown.
+    let func = ctx.lookup_func(&func_call.name)?;
+
+    let (args, known) = if func.is_lazy() {
+        let mut known = true;
+
+        let args = func_call
+            .args
+            .iter()
+            .map(|arg| match partial_evaluate(arg, ctx) {
+                Ok(Partial::Known(value)) => Expression::from(value),
+                Ok(Partial::Unknown(expr)) => {
+                    known = false;
+                    expr
+                }
+                // Lazily evaluated functions handle errors of their arguments themselves.
+                Err(_) => arg.clone(),
+            })
+            .collect();
+
+        (args, known)
+    } else {
+        let partials = func_call
+            .args
+            .iter()
+            .map(|arg| partial_evaluate(arg, ctx))
+            .collect::<EvalResult<_>>()?;
+
+        match collect_known(partials) {
+            Ok(values) => (values.into_iter().map(Expression::from).collect(), true),
+            Err(exprs) => (exprs, false),
+        }
+    };
+
+    let func_call = FuncCall {
+        name: func_call.name.clone(),
+        args,
+        expand_final: func_call.expand_final,
+    };
+
+    if known {
+        func_call.evaluate(ctx).map(Partial::Known)
+    } else {
+        Ok(Partial::Unknown(Expression::from(func_call)))
+    }
+}
+
+fn partial_evaluate_conditional(cond: &Conditional, ctx: &Context) -> EvalResult<Partial> {
+    match partial_evaluate(&cond.cond_expr, ctx)? {
+        Partial::Known(value) => {
+            if expr::expect_bool(value, ctx)? {
+                partial_evaluate(&cond.true_expr, ctx)
+            } else {
+                partial_evaluate(&cond.false_expr, ctx)
+            }
+        }
+        Partial::Unknown(cond_expr) => {
+            // Since it is not known yet which branch will be taken, errors in any of the branches
+            // must not fail the evaluation. The affected branch is kept as is in this case.
+            let branch = |expr: &Expression| {
+                partial_evaluate(expr, ctx).map_or_else(|_| expr.clone(), Partial::into_expr)
+            };
+
+            Ok(Partial::Unknown(Expression::from(Conditional::new(
+                parenthesize(cond_expr),
+                branch(&cond.true_expr),
+                branch(&cond.false_expr),
+            ))))
+        }
+    }
+}
+
+fn partial_evaluate_operation(op: &Operation, ctx: &Context) -> EvalResult<Partial> {
+    match op {
+        Operation::Unary(unary) => match partial_evaluate(&unary.expr, ctx)? {
+            Partial::Known(value) => UnaryOp::new(unary.operator, value)
+                .evaluate(ctx)
+                .map(Partial::Known),
+            Partial::Unknown(expr) => Ok(Partial::Unknown(Expression::from(UnaryOp::new(
+                unary.operator,
+                parenthesize(expr),
+            )))),
+        },
+        Operation::Binary(binary) => {
+            let binary = binary.clone().normalize();
+            let lhs = partial_evaluate(&binary.lhs_expr, ctx)?;
+            let rhs = partial_evaluate(&binary.rhs_expr, ctx)?;
+
+            match (lhs, rhs) {
+                (Partial::Known(lhs), Partial::Known(rhs)) => {
+                    BinaryOp::new(lhs, binary.operator, rhs)
+                        .evaluate(ctx)
+                        .map(Partial::Known)
+                }
+                (lhs, rhs) => Ok(Partial::Unknown(Expression::from(BinaryOp::new(
+                    parenthesize(lhs.into_expr()),
+                    binary.operator,
+                    parenthesize(rhs.into_expr()),
+                )))),
+            }
+        }
+    }
+}
+
+fn partial_evaluate_for_expr(for_expr: &ForExpr, ctx: &Context) -> EvalResult<Partial> {
+    let collection = match partial_evaluate(&for_expr.collection_expr, ctx)? {
+        Partial::Known(value) => value,
+        Partial::Unknown(collection_expr) => {
+            return Ok(Partial::Unknown(Expression::from(ForExpr {
+                collection_expr,
+                ..for_expr.clone()
+            })))
+        }
+    };
+
+    // Used if the result cannot be determined because the condition, key or value expression
+    // evaluates to an unknown value for any of the collection's elements. Residual expressions of
+    // single elements cannot be used in this case, since they may reference the `for` expression's
+    // variables which are out of scope in the result.
+    let unknown = |collection: Value| {
+        Partial::Unknown(Expression::from(ForExpr {
+            collection_expr: Expression::from(collection),
+            ..for_expr.clone()
+        }))
+    };
+
+    let mut entries = Vec::new();
+
+    for (key, value) in expr::expect_collection(collection.clone(), ctx)? {
+        let mut ctx = ctx.child();
+        if let Some(key_var) = &for_expr.key_var {
+            ctx.declare_var(key_var.clone(), key);
+        }
+        ctx.declare_var(for_expr.value_var.clone(), value);
+
+        if let Some(cond_expr) = &for_expr.cond_expr {
+            match partial_evaluate(cond_expr, &ctx)? {
+                Partial::Known(cond) => {
+                    if !expr::expect_bool(cond, &ctx)? {
+                        continue;
+                    }
+                }
+                Partial::Unknown(_) => return Ok(unknown(collection)),
+            }
+        }
+
+        let key = match &for_expr.key_expr {
+            Some(key_expr) => match partial_evaluate(key_expr, &ctx)? {
+                Partial::Known(key) => Some(expr::expect_object_key(key, &ctx)?),
+                Partial::Unknown(_) => return Ok(unknown(collection)),
+            },
+            None => None,
+        };
+
+        let value = match partial_evaluate(&for_expr.value_expr, &ctx)? {
+            Partial::Known(value) => value,
+            Partial::Unknown(_) => return Ok(unknown(collection)),
+        };
+
+        entries.push((key, value));
+    }
+
+    if for_expr.key_expr.is_none() {
+        // Result will be an array.
+        let values = entries.into_iter().map(|(_, value)| value).collect();
+        return Ok(Partial::Known(Value::Array(values)));
+    }
+
+    // Result will be an object.
+    let mut result: Map<String, Vec<Value>> = Map::with_capacity(entries.len());
+
+    for (key, value) in entries {
+        let key = key.unwrap();
+
+        match result.entry(key) {
+            Entry::Occupied(mut entry) if for_expr.grouping => entry.get_mut().push(value),
+            Entry::Occupied(entry) => {
+                return Err(ctx.error(ErrorKind::KeyExists(entry.key().clone())))
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(vec![value]);
+            }
+        }
+    }
+
+    Ok(Partial::Known(Value::Object(
+        result
+            .into_iter()
+            .map(|(key, mut values)| {
+                let value = if for_expr.grouping {
+                    Value::Array(values)
+                } else {
+                    values.remove(0)
+                };
+
+                (key, value)
+            })
+            .collect(),
+    )))
+}
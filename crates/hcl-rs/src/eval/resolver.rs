@@ -0,0 +1,133 @@
+use crate::expr::Traversal;
+use crate::{Identifier, Map, Value};
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A trait for resolving variables that are not declared in a [`Context`][super::Context].
+///
+/// The resolver is consulted when an expression references a variable which was not declared
+/// via [`Context::declare_var`][super::Context::declare_var]. This allows to compute the values
+/// of variables lazily, e.g. if there are a lot of potential variables but only a few of them are
+/// referenced by the expressions that are evaluated.
+///
+/// The resolver receives the identifier of the variable and, if the variable is the root of a
+/// [`Traversal`], the traversal expression. The traversal can be used to decide how to compute
+/// the value, but the resolver must always return the value of the whole variable since resolved
+/// values are cached per context: the resolver is called at most once per variable name until
+/// the cache is cleared via [`Context::clear_resolver_cache`][super::Context::clear_resolver_cache].
+///
+/// It is implemented for all closures (and plain functions) with the signature
+/// `Fn(&Identifier, Option<&Traversal>) -> Result<Option<Value>, String>` that are
+/// `Send + Sync`.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hcl::eval::{Context, Evaluate};
+/// use hcl::expr::{Traversal, Variable};
+/// use hcl::{Identifier, Value};
+///
+/// fn resolve(name: &Identifier, _: Option<&Traversal>) -> Result<Option<Value>, String> {
+///     match name.as_str() {
+///         "data" => Ok(Some(hcl::value!({ answer = 42 }))),
+///         _ => Ok(None),
+///     }
+/// }
+///
+/// let mut ctx = Context::new();
+/// ctx.set_var_resolver(resolve);
+///
+/// let expr = Traversal::builder(Variable::new("data")?)
+///     .attr("answer")
+///     .build();
+///
+/// assert_eq!(expr.evaluate(&ctx)?, Value::from(42));
+/// #   Ok(())
+/// # }
+/// ```
+pub trait VariableResolver: Send + Sync {
+    /// Resolves the value of the variable with the given name.
+    ///
+    /// Returns `Ok(None)` if the variable is not known to the resolver, in which case the lookup
+    /// continues in the parent context, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if the value of a known variable cannot be computed.
+    fn resolve(
+        &self,
+        name: &Identifier,
+        traversal: Option<&Traversal>,
+    ) -> Result<Option<Value>, String>;
+}
+
+impl<F> VariableResolver for F
+where
+    F: Fn(&Identifier, Option<&Traversal>) -> Result<Option<Value>, String> + Send + Sync,
+{
+    fn resolve(
+        &self,
+        name: &Identifier,
+        traversal: Option<&Traversal>,
+    ) -> Result<Option<Value>, String> {
+        self(name, traversal)
+    }
+}
+
+// A `VariableResolver` together with the cache of the values it resolved.
+pub(super) struct Resolver {
+    resolver: Arc<dyn VariableResolver>,
+    cache: Mutex<Map<Identifier, Option<Value>>>,
+}
+
+impl Resolver {
+    pub(super) fn new(resolver: Arc<dyn VariableResolver>) -> Resolver {
+        Resolver {
+            resolver,
+            cache: Mutex::new(Map::new()),
+        }
+    }
+
+    pub(super) fn resolve(
+        &self,
+        name: &Identifier,
+        traversal: Option<&Traversal>,
+    ) -> Result<Option<Value>, String> {
+        if let Some(value) = self.cache().get(name) {
+            return Ok(value.clone());
+        }
+
+        // The lock is not held while calling the resolver, since it may evaluate expressions
+        // using the same context itself.
+        let value = self.resolver.resolve(name, traversal)?;
+        self.cache().insert(name.clone(), value.clone());
+        Ok(value)
+    }
+
+    pub(super) fn clear(&self) {
+        self.cache().clear();
+    }
+
+    fn cache(&self) -> std::sync::MutexGuard<'_, Map<Identifier, Option<Value>>> {
+        // The cache is always in a consistent state, even if another thread panicked.
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Clone for Resolver {
+    fn clone(&self) -> Self {
+        Resolver {
+            resolver: self.resolver.clone(),
+            cache: Mutex::new(self.cache().clone()),
+        }
+    }
+}
+
+impl fmt::Debug for Resolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resolver")
+            .field("cache", &*self.cache())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,795 @@
+//! A standard library of HCL functions.
+//!
+//! The functions follow the semantics of the ones provided by the Go
+//! [`cty/function/stdlib`](https://pkg.go.dev/github.com/zclconf/go-cty/cty/function/stdlib)
+//! package as closely as possible. The full list of available functions is documented on
+//! [`Context::with_stdlib`].
+
+use super::{Context, Evaluate, FuncArgs, FuncDef, LazyFuncArgs, ParamType};
+use crate::{Map, Number, Value};
+use std::fmt::Write;
+
+// The maximum number of elements that the `range` function is allowed to produce.
+const MAX_RANGE_LEN: usize = 1024;
+
+type FuncResult = Result<Value, String>;
+
+/// Declares all standard library functions in the given context.
+pub(super) fn declare(ctx: &mut Context) {
+    declare_numeric_funcs(ctx);
+    declare_string_funcs(ctx);
+    declare_collection_funcs(ctx);
+    declare_encoding_funcs(ctx);
+    declare_conversion_funcs(ctx);
+    declare_error_handling_funcs(ctx);
+}
+
+fn list() -> ParamType {
+    ParamType::array_of(ParamType::Any)
+}
+
+fn string_list() -> ParamType {
+    ParamType::array_of(ParamType::String)
+}
+
+fn object_type() -> ParamType {
+    ParamType::object_of(ParamType::Any)
+}
+
+fn declare_numeric_funcs(ctx: &mut Context) {
+    use ParamType::Number;
+
+    ctx.declare_func("abs", FuncDef::new(abs, [Number]));
+    ctx.declare_func("ceil", FuncDef::new(ceil, [Number]));
+    ctx.declare_func("floor", FuncDef::new(floor, [Number]));
+    ctx.declare_func("signum", FuncDef::new(signum, [Number]));
+    ctx.declare_func("max", FuncDef::builder().variadic_param(Number).build(max));
+    ctx.declare_func("min", FuncDef::builder().variadic_param(Number).build(min));
+}
+
+fn declare_string_funcs(ctx: &mut Context) {
+    use ParamType::{Any, Number, String};
+
+    ctx.declare_func("chomp", FuncDef::new(chomp, [String]));
+    ctx.declare_func(
+        "format",
+        FuncDef::builder()
+            .param(String)
+            .variadic_param(Any)
+            .build(format),
+    );
+    ctx.declare_func(
+        "join",
+        FuncDef::builder()
+            .param(String)
+            .variadic_param(string_list())
+            .build(join),
+    );
+    ctx.declare_func("lower", FuncDef::new(lower, [String]));
+    ctx.declare_func("replace", FuncDef::new(replace, [String, String, String]));
+    ctx.declare_func("split", FuncDef::new(split, [String, String]));
+    ctx.declare_func("strrev", FuncDef::new(strrev, [String]));
+    ctx.declare_func("substr", FuncDef::new(substr, [String, Number, Number]));
+    ctx.declare_func("title", FuncDef::new(title, [String]));
+    ctx.declare_func("trim", FuncDef::new(trim, [String, String]));
+    ctx.declare_func("trimprefix", FuncDef::new(trimprefix, [String, String]));
+    ctx.declare_func("trimspace", FuncDef::new(trimspace, [String]));
+    ctx.declare_func("trimsuffix", FuncDef::new(trimsuffix, [String, String]));
+    ctx.declare_func("upper", FuncDef::new(upper, [String]));
+}
+
+fn declare_collection_funcs(ctx: &mut Context) {
+    use ParamType::{Any, Number, String};
+
+    ctx.declare_func(
+        "coalesce",
+        FuncDef::builder().variadic_param(Any).build(coalesce),
+    );
+    ctx.declare_func(
+        "coalescelist",
+        FuncDef::builder()
+            .variadic_param(ParamType::nullable(list()))
+            .build(coalescelist),
+    );
+    ctx.declare_func(
+        "compact",
+        FuncDef::new(compact, [ParamType::array_of(ParamType::nullable(String))]),
+    );
+    ctx.declare_func(
+        "concat",
+        FuncDef::builder().variadic_param(list()).build(concat),
+    );
+    ctx.declare_func("contains", FuncDef::new(contains, [list(), Any]));
+    ctx.declare_func("distinct", FuncDef::new(distinct, [list()]));
+    ctx.declare_func("element", FuncDef::new(element, [list(), Number]));
+    ctx.declare_func("flatten", FuncDef::new(flatten, [list()]));
+    ctx.declare_func("index", FuncDef::new(index, [list(), Any]));
+    ctx.declare_func("keys", FuncDef::new(keys, [object_type()]));
+    ctx.declare_func(
+        "length",
+        FuncDef::new(length, [ParamType::one_of([String, list(), object_type()])]),
+    );
+    ctx.declare_func(
+        "lookup",
+        FuncDef::builder()
+            .params([object_type(), String])
+            .variadic_param(Any)
+            .build(lookup),
+    );
+    ctx.declare_func(
+        "merge",
+        FuncDef::builder()
+            .variadic_param(ParamType::nullable(object_type()))
+            .build(merge),
+    );
+    ctx.declare_func(
+        "range",
+        FuncDef::builder().variadic_param(Number).build(range),
+    );
+    ctx.declare_func("reverse", FuncDef::new(reverse, [list()]));
+    ctx.declare_func("slice", FuncDef::new(slice, [list(), Number, Number]));
+    ctx.declare_func("sort", FuncDef::new(sort, [string_list()]));
+    ctx.declare_func("values", FuncDef::new(values, [object_type()]));
+    ctx.declare_func("zipmap", FuncDef::new(zipmap, [string_list(), list()]));
+}
+
+fn declare_encoding_funcs(ctx: &mut Context) {
+    use ParamType::{Any, String};
+
+    ctx.declare_func("jsondecode", FuncDef::new(jsondecode, [String]));
+    ctx.declare_func("jsonencode", FuncDef::new(jsonencode, [Any]));
+}
+
+fn declare_conversion_funcs(ctx: &mut Context) {
+    use ParamType::{Bool, Number, String};
+
+    ctx.declare_func(
+        "tobool",
+        FuncDef::new(
+            tobool,
+            [ParamType::nullable(ParamType::one_of([Bool, String]))],
+        ),
+    );
+    ctx.declare_func(
+        "tonumber",
+        FuncDef::new(
+            tonumber,
+            [ParamType::nullable(ParamType::one_of([Number, String]))],
+        ),
+    );
+    ctx.declare_func(
+        "tostring",
+        FuncDef::new(
+            tostring,
+            [ParamType::nullable(ParamType::one_of([
+                Bool, Number, String,
+            ]))],
+        ),
+    );
+}
+
+fn declare_error_handling_funcs(ctx: &mut Context) {
+    use ParamType::Any;
+
+    ctx.declare_func("can", FuncDef::builder().param(Any).build_lazy(can));
+    ctx.declare_func(
+        "try",
+        FuncDef::builder().variadic_param(Any).build_lazy(r#try),
+    );
+}
+
+// Helpers for accessing arguments which were already validated by the `FuncDef`.
+
+fn num(value: &Value) -> Number {
+    *value.as_number().unwrap()
+}
+
+fn str(value: &Value) -> &str {
+    value.as_str().unwrap()
+}
+
+fn array(value: &Value) -> &Vec<Value> {
+    value.as_array().unwrap()
+}
+
+fn object(value: &Value) -> &Map<String, Value> {
+    value.as_object().unwrap()
+}
+
+fn integer(value: &Value, what: &str) -> Result<i64, String> {
+    num(value)
+        .as_i64()
+        .ok_or_else(|| format!("{what} must be a whole number, got `{value}`"))
+}
+
+fn index_arg(value: &Value, what: &str) -> Result<usize, String> {
+    let n = integer(value, what)?;
+    usize::try_from(n).map_err(|_| format!("{what} must not be negative, got `{n}`"))
+}
+
+fn from_f64(f: f64) -> FuncResult {
+    Number::from_f64(f)
+        .map(Value::Number)
+        .ok_or_else(|| format!("`{f}` is not a valid number"))
+}
+
+// Numeric functions.
+
+fn abs(args: FuncArgs) -> FuncResult {
+    let n = num(&args[0]);
+
+    if n < Number::from(0) {
+        Ok(Value::Number(-n))
+    } else {
+        Ok(Value::Number(n))
+    }
+}
+
+fn ceil(args: FuncArgs) -> FuncResult {
+    from_f64(num(&args[0]).as_f64().unwrap().ceil())
+}
+
+fn floor(args: FuncArgs) -> FuncResult {
+    from_f64(num(&args[0]).as_f64().unwrap().floor())
+}
+
+fn signum(args: FuncArgs) -> FuncResult {
+    let n = num(&args[0]);
+    let zero = Number::from(0);
+    Ok(Value::from(i8::from(n > zero) - i8::from(n < zero)))
+}
+
+fn max(args: FuncArgs) -> FuncResult {
+    args.iter()
+        .map(num)
+        .reduce(|a, b| if b > a { b } else { a })
+        .map(Value::Number)
+        .ok_or_else(|| String::from("must pass at least one number"))
+}
+
+fn min(args: FuncArgs) -> FuncResult {
+    args.iter()
+        .map(num)
+        .reduce(|a, b| if b < a { b } else { a })
+        .map(Value::Number)
+        .ok_or_else(|| String::from("must pass at least one number"))
+}
+
+// String functions.
+
+fn chomp(args: FuncArgs) -> FuncResult {
+    Ok(Value::from(str(&args[0]).trim_end_matches(['\r', '\n'])))
+}
+
+fn join(args: FuncArgs) -> FuncResult {
+    let sep = str(&args[0]);
+    let mut lists = args.variadic_args().peekable();
+
+    if lists.peek().is_none() {
+        return Err(String::from("at least one list is required"));
+    }
+
+    let joined = lists.flat_map(array).map(str).collect::<Vec<_>>().join(sep);
+
+    Ok(Value::from(joined))
+}
+
+fn lower(args: FuncArgs) -> FuncResult {
+    Ok(Value::from(str(&args[0]).to_lowercase()))
+}
+
+fn replace(args: FuncArgs) -> FuncResult {
+    Ok(Value::from(
+        str(&args[0]).replace(str(&args[1]), str(&args[2])),
+    ))
+}
+
+fn split(args: FuncArgs) -> FuncResult {
+    let sep = str(&args[0]);
+    let s = str(&args[1]);
+
+    if s.is_empty() {
+        return Ok(Value::Array(Vec::new()));
+    }
+
+    Ok(s.split(sep).collect())
+}
+
+fn strrev(args: FuncArgs) -> FuncResult {
+    Ok(Value::from(str(&args[0]).chars().rev().collect::<String>()))
+}
+
+fn substr(args: FuncArgs) -> FuncResult {
+    let chars: Vec<char> = str(&args[0]).chars().collect();
+    let len = chars.len() as i64;
+    let mut offset = integer(&args[1], "offset")?;
+    let length = integer(&args[2], "length")?;
+
+    if offset < 0 {
+        offset += len;
+    }
+
+    let start = offset.clamp(0, len);
+    let end = if length < 0 {
+        len
+    } else {
+        start.saturating_add(length).min(len)
+    };
+
+    Ok(Value::from(
+        chars[start as usize..end as usize]
+            .iter()
+            .collect::<String>(),
+    ))
+}
+
+fn title(args: FuncArgs) -> FuncResult {
+    let mut result = String::new();
+    let mut prev_is_letter = false;
+
+    for ch in str(&args[0]).chars() {
+        if prev_is_letter {
+            result.push(ch);
+        } else {
+            result.extend(ch.to_uppercase());
+        }
+
+        prev_is_letter = ch.is_alphanumeric();
+    }
+
+    Ok(Value::from(result))
+}
+
+fn trim(args: FuncArgs) -> FuncResult {
+    let cutset = str(&args[1]);
+    Ok(Value::from(
+        str(&args[0]).trim_matches(|ch| cutset.contains(ch)),
+    ))
+}
+
+fn trimprefix(args: FuncArgs) -> FuncResult {
+    let s = str(&args[0]);
+    Ok(Value::from(s.strip_prefix(str(&args[1])).unwrap_or(s)))
+}
+
+fn trimspace(args: FuncArgs) -> FuncResult {
+    Ok(Value::from(str(&args[0]).trim()))
+}
+
+fn trimsuffix(args: FuncArgs) -> FuncResult {
+    let s = str(&args[0]);
+    Ok(Value::from(s.strip_suffix(str(&args[1])).unwrap_or(s)))
+}
+
+fn upper(args: FuncArgs) -> FuncResult {
+    Ok(Value::from(str(&args[0]).to_uppercase()))
+}
+
+fn format(args: FuncArgs) -> FuncResult {
+    let mut values = args.variadic_args();
+    let mut result = String::new();
+    let mut chars = str(&args[0]).chars().peekable();
+    let mut used = 0;
+
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            result.push(ch);
+            continue;
+        }
+
+        if chars.next_if_eq(&'%').is_some() {
+            result.push('%');
+            continue;
+        }
+
+        let mut spec = FormatSpec::default();
+
+        while let Some(flag) = chars.next_if(|ch| matches!(ch, '-' | '+' | '0')) {
+            match flag {
+                '-' => spec.left_align = true,
+                '+' => spec.plus = true,
+                _ => spec.zero_pad = true,
+            }
+        }
+
+        spec.width = parse_digits(&mut chars);
+
+        if chars.next_if_eq(&'.').is_some() {
+            spec.precision = Some(parse_digits(&mut chars).unwrap_or(0));
+        }
+
+        let verb = chars
+            .next()
+            .ok_or_else(|| String::from("unterminated format verb at end of string"))?;
+
+        let value = values
+            .next()
+            .ok_or_else(|| format!("not enough arguments for %{verb} at index {used}"))?;
+
+        used += 1;
+
+        spec.write(&mut result, verb, value)?;
+    }
+
+    if values.next().is_some() {
+        return Err(format!(
+            "too many arguments; only {used} used by format string"
+        ));
+    }
+
+    Ok(Value::from(result))
+}
+
+fn parse_digits<I>(chars: &mut std::iter::Peekable<I>) -> Option<usize>
+where
+    I: Iterator<Item = char>,
+{
+    let mut n = None;
+
+    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+        let digit = digit.to_digit(10).unwrap() as usize;
+        n = Some(n.unwrap_or(0) * 10 + digit);
+    }
+
+    n
+}
+
+// The parsed flags, width and precision of a single format verb.
+#[derive(Default)]
+struct FormatSpec {
+    left_align: bool,
+    plus: bool,
+    zero_pad: bool,
+    width: Option<usize>,
+    precision: Option<usize>,
+}
+
+impl FormatSpec {
+    fn write(&self, result: &mut String, verb: char, value: &Value) -> Result<(), String> {
+        let unsupported = || format!("unsupported value for %{verb}: `{value}`");
+
+        let formatted = match (verb, value) {
+            ('v' | 's', Value::String(s)) => s.clone(),
+            ('v', _) | ('s', Value::Number(_) | Value::Bool(_)) | ('q', Value::String(_)) => {
+                value.to_string()
+            }
+            ('t', Value::Bool(b)) => b.to_string(),
+            ('d', Value::Number(n)) => {
+                let n = n.as_i64().ok_or_else(unsupported)?;
+                self.sign(n.to_string(), n >= 0)
+            }
+            ('f', Value::Number(n)) => {
+                let f = n.as_f64().unwrap();
+                let precision = self.precision.unwrap_or(6);
+                self.sign(format!("{f:.precision$}"), f >= 0.0)
+            }
+            ('e', Value::Number(n)) => {
+                let f = n.as_f64().unwrap();
+                let precision = self.precision.unwrap_or(6);
+                self.sign(format!("{f:.precision$e}"), f >= 0.0)
+            }
+            ('s' | 'q' | 't' | 'd' | 'f' | 'e', _) => return Err(unsupported()),
+            (verb, _) => return Err(format!("unsupported format verb %{verb}")),
+        };
+
+        let formatted = match (verb, self.precision) {
+            ('s' | 'v', Some(precision)) => formatted.chars().take(precision).collect(),
+            _ => formatted,
+        };
+
+        let width = self.width.unwrap_or(0);
+        let len = formatted.chars().count();
+
+        if len >= width {
+            result.push_str(&formatted);
+        } else if self.left_align {
+            write!(result, "{formatted:<width$}").unwrap();
+        } else if self.zero_pad && matches!(verb, 'd' | 'f' | 'e') {
+            let (sign, digits) = match formatted.strip_prefix(['-', '+']) {
+                Some(digits) => formatted.split_at(formatted.len() - digits.len()),
+                None => ("", formatted.as_str()),
+            };
+            let width = width - sign.len();
+            write!(result, "{sign}{digits:0>width$}").unwrap();
+        } else {
+            write!(result, "{formatted:>width$}").unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn sign(&self, formatted: String, non_negative: bool) -> String {
+        if self.plus && non_negative {
+            format!("+{formatted}")
+        } else {
+            formatted
+        }
+    }
+}
+
+// Collection functions.
+
+fn coalesce(args: FuncArgs) -> FuncResult {
+    args.iter()
+        .find(|value| !value.is_null())
+        .cloned()
+        .ok_or_else(|| String::from("no non-null arguments"))
+}
+
+fn coalescelist(args: FuncArgs) -> FuncResult {
+    args.iter()
+        .find(|value| value.as_array().is_some_and(|array| !array.is_empty()))
+        .cloned()
+        .ok_or_else(|| String::from("no non-empty list arguments"))
+}
+
+fn compact(args: FuncArgs) -> FuncResult {
+    Ok(array(&args[0])
+        .iter()
+        .filter(|value| value.as_str().is_some_and(|s| !s.is_empty()))
+        .cloned()
+        .collect())
+}
+
+fn concat(args: FuncArgs) -> FuncResult {
+    Ok(args.iter().flat_map(array).cloned().collect())
+}
+
+fn contains(args: FuncArgs) -> FuncResult {
+    Ok(Value::Bool(array(&args[0]).contains(&args[1])))
+}
+
+fn distinct(args: FuncArgs) -> FuncResult {
+    let mut result: Vec<Value> = Vec::new();
+
+    for value in array(&args[0]) {
+        if !result.contains(value) {
+            result.push(value.clone());
+        }
+    }
+
+    Ok(Value::Array(result))
+}
+
+fn element(args: FuncArgs) -> FuncResult {
+    let list = array(&args[0]);
+    let index = index_arg(&args[1], "index")?;
+
+    if list.is_empty() {
+        return Err(String::from(
+            "cannot use element function with an empty list",
+        ));
+    }
+
+    Ok(list[index % list.len()].clone())
+}
+
+fn flatten(args: FuncArgs) -> FuncResult {
+    fn flatten_into(result: &mut Vec<Value>, list: &[Value]) {
+        for value in list {
+            match value {
+                Value::Array(list) => flatten_into(result, list),
+                other => result.push(other.clone()),
+            }
+        }
+    }
+
+    let mut result = Vec::new();
+    flatten_into(&mut result, array(&args[0]));
+    Ok(Value::Array(result))
+}
+
+fn index(args: FuncArgs) -> FuncResult {
+    array(&args[0])
+        .iter()
+        .position(|value| value == &args[1])
+        .map(Value::from)
+        .ok_or_else(|| String::from("item not found"))
+}
+
+fn keys(args: FuncArgs) -> FuncResult {
+    let mut keys: Vec<&String> = object(&args[0]).keys().collect();
+    keys.sort();
+    Ok(keys.into_iter().cloned().collect())
+}
+
+fn length(args: FuncArgs) -> FuncResult {
+    let len = match &args[0] {
+        Value::String(s) => s.chars().count(),
+        Value::Array(array) => array.len(),
+        Value::Object(object) => object.len(),
+        _ => unreachable!(),
+    };
+
+    Ok(Value::from(len))
+}
+
+fn lookup(args: FuncArgs) -> FuncResult {
+    let key = str(&args[1]);
+
+    if args.len() > 3 {
+        return Err(String::from("lookup() takes no more than three arguments"));
+    }
+
+    object(&args[0])
+        .get(key)
+        .or_else(|| args.get(2))
+        .cloned()
+        .ok_or_else(|| format!("lookup failed to find key `{key}`"))
+}
+
+fn merge(args: FuncArgs) -> FuncResult {
+    Ok(args
+        .iter()
+        .filter_map(Value::as_object)
+        .flat_map(|object| object.iter().map(|(k, v)| (k.clone(), v.clone())))
+        .collect())
+}
+
+fn range(args: FuncArgs) -> FuncResult {
+    let nums: Vec<f64> = args.iter().map(|v| num(v).as_f64().unwrap()).collect();
+
+    let (start, limit, step) = match nums[..] {
+        [limit] => (0.0, limit, 1.0),
+        [start, limit] => (start, limit, if limit < start { -1.0 } else { 1.0 }),
+        [start, limit, step] => (start, limit, step),
+        _ => return Err(String::from("must have one, two, or three arguments")),
+    };
+
+    if step == 0.0 {
+        return Err(String::from("step must not be zero"));
+    }
+
+    if start < limit && step < 0.0 {
+        return Err(String::from(
+            "step must be positive when start is less than limit",
+        ));
+    }
+
+    if start > limit && step > 0.0 {
+        return Err(String::from(
+            "step must be negative when start is greater than limit",
+        ));
+    }
+
+    let mut result = Vec::new();
+    let mut n = start;
+
+    while (step > 0.0 && n < limit) || (step < 0.0 && n > limit) {
+        if result.len() >= MAX_RANGE_LEN {
+            return Err(format!(
+                "more than {MAX_RANGE_LEN} values were generated; either decrease the difference \
+                 between start and limit or use a smaller step"
+            ));
+        }
+
+        result.push(from_f64(n)?);
+        n += step;
+    }
+
+    Ok(Value::Array(result))
+}
+
+fn reverse(args: FuncArgs) -> FuncResult {
+    Ok(array(&args[0]).iter().rev().cloned().collect())
+}
+
+fn slice(args: FuncArgs) -> FuncResult {
+    let list = array(&args[0]);
+    let start = index_arg(&args[1], "start index")?;
+    let end = index_arg(&args[2], "end index")?;
+
+    if end > list.len() {
+        return Err(String::from(
+            "end index must not be greater than the length of the list",
+        ));
+    }
+
+    if start > end {
+        return Err(String::from(
+            "start index must not be greater than end index",
+        ));
+    }
+
+    Ok(Value::Array(list[start..end].to_vec()))
+}
+
+fn sort(args: FuncArgs) -> FuncResult {
+    let mut list: Vec<&str> = array(&args[0]).iter().map(str).collect();
+    list.sort_unstable();
+    Ok(list.into_iter().collect())
+}
+
+fn values(args: FuncArgs) -> FuncResult {
+    let mut entries: Vec<(&String, &Value)> = object(&args[0]).iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    Ok(entries.into_iter().map(|(_, v)| v.clone()).collect())
+}
+
+fn zipmap(args: FuncArgs) -> FuncResult {
+    let keys = array(&args[0]);
+    let values = array(&args[1]);
+
+    if keys.len() != values.len() {
+        return Err(format!(
+            "number of keys ({}) does not match number of values ({})",
+            keys.len(),
+            values.len()
+        ));
+    }
+
+    Ok(keys
+        .iter()
+        .map(|key| str(key).to_owned())
+        .zip(values.iter().cloned())
+        .collect())
+}
+
+// Encoding functions.
+
+fn jsondecode(args: FuncArgs) -> FuncResult {
+    serde_json::from_str(str(&args[0])).map_err(|err| err.to_string())
+}
+
+fn jsonencode(args: FuncArgs) -> FuncResult {
+    serde_json::to_string(&args[0])
+        .map(Value::String)
+        .map_err(|err| err.to_string())
+}
+
+// Type conversion functions.
+
+fn tobool(args: FuncArgs) -> FuncResult {
+    match &args[0] {
+        Value::String(s) if s == "true" => Ok(Value::Bool(true)),
+        Value::String(s) if s == "false" => Ok(Value::Bool(false)),
+        Value::String(s) => Err(format!("cannot convert `{s}` to bool")),
+        other => Ok(other.clone()),
+    }
+}
+
+fn tonumber(args: FuncArgs) -> FuncResult {
+    match &args[0] {
+        Value::String(s) => s
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| format!("cannot convert `{s}` to number")),
+        other => Ok(other.clone()),
+    }
+}
+
+fn tostring(args: FuncArgs) -> FuncResult {
+    match &args[0] {
+        Value::Null => Ok(Value::Null),
+        Value::String(s) => Ok(Value::from(s.as_str())),
+        other => Ok(Value::from(other.to_string())),
+    }
+}
+
+// Error handling functions.
+
+fn can(args: LazyFuncArgs, ctx: &Context) -> FuncResult {
+    Ok(Value::Bool(args[0].evaluate(ctx).is_ok()))
+}
+
+fn r#try(args: LazyFuncArgs, ctx: &Context) -> FuncResult {
+    if args.is_empty() {
+        return Err("at least one argument is required".into());
+    }
+
+    let mut errors = Vec::with_capacity(args.len());
+
+    for arg in args.iter() {
+        match arg.evaluate(ctx) {
+            Ok(value) => return Ok(value),
+            Err(err) => errors.push(err.to_string()),
+        }
+    }
+
+    Err(format!(
+        "no expression succeeded:\n- {}",
+        errors.join("\n- ")
+    ))
+}
@@ -46,7 +46,7 @@ fn evaluate_element(
 
 // Depending on the `StripMode`, strips off leading and trailing spaces up until the first line
 // break that is encountered. The line break is stripped as well.
-fn strip_literal(mut literal: &str, prev_strip: Strip, next_strip: Strip) -> &str {
+pub(super) fn strip_literal(mut literal: &str, prev_strip: Strip, next_strip: Strip) -> &str {
     fn is_space(ch: char) -> bool {
         ch.is_whitespace() && ch != '\r' && ch != '\n'
     }
@@ -83,15 +83,19 @@ fn evaluate_interpolation(
     interp: &Interpolation,
     ctx: &Context,
 ) -> EvalResult<()> {
-    let string = match interp.expr.evaluate(ctx)? {
-        Value::String(string) => string,
-        other => other.to_string(),
-    };
-
+    let string = interpolated_string(interp.expr.evaluate(ctx)?);
     result.push_str(&string);
     Ok(())
 }
 
+// Converts the value of an interpolation to the string that is included in the result.
+pub(super) fn interpolated_string(value: Value) -> String {
+    match value {
+        Value::String(string) => string,
+        other => other.to_string(),
+    }
+}
+
 fn evaluate_directive(result: &mut String, dir: &Directive, ctx: &Context) -> EvalResult<()> {
     match dir {
         Directive::If(dir) => evaluate_if_directive(result, dir, ctx),
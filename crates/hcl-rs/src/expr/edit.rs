@@ -0,0 +1,405 @@
+// Conversions between the expression types of this crate and their `hcl-edit` counterparts.
+//
+// Converting from `hcl-edit` is infallible and drops all whitespace and comments. Converting into
+// `hcl-edit` types produces default decor, but may fail because raw expressions and the raw
+// strings of template expressions need to be parsed.
+
+use super::{
+    BinaryOp, Conditional, Expression, ForExpr, FuncCall, Heredoc, HeredocStripMode, ObjectKey,
+    Operation, RawExpression, TemplateExpr, Traversal, TraversalOperator, UnaryOp, Variable,
+};
+use crate::{format, Error, Identifier, Result};
+use hcl_edit::expr as edit;
+use hcl_edit::template::{HeredocTemplate, StringTemplate};
+use hcl_edit::{parser, Decorate, Decorated, Ident};
+
+impl From<edit::Expression> for Expression {
+    fn from(expr: edit::Expression) -> Self {
+        match expr {
+            edit::Expression::Null(_) => Expression::Null,
+            edit::Expression::Bool(b) => Expression::Bool(b.into_value()),
+            edit::Expression::Number(n) => Expression::Number(n.into_value()),
+            edit::Expression::String(s) => Expression::String(s.into_value()),
+            edit::Expression::Array(array) => {
+                Expression::Array(array.into_iter().map(Into::into).collect())
+            }
+            edit::Expression::Object(object) => Expression::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (ObjectKey::from(key), Expression::from(value.into_expr())))
+                    .collect(),
+            ),
+            edit::Expression::Template(template) => TemplateExpr::from(template).into(),
+            edit::Expression::HeredocTemplate(heredoc) => Heredoc::from(*heredoc).into(),
+            edit::Expression::Parenthesis(parens) => {
+                Expression::Parenthesis(Box::new(parens.into_inner().into()))
+            }
+            edit::Expression::Variable(var) => Variable::from(var).into(),
+            edit::Expression::Conditional(cond) => Conditional::from(*cond).into(),
+            edit::Expression::FuncCall(func_call) => FuncCall::from(*func_call).into(),
+            edit::Expression::Traversal(traversal) => Traversal::from(*traversal).into(),
+            edit::Expression::UnaryOp(op) => UnaryOp::from(*op).into(),
+            edit::Expression::BinaryOp(op) => BinaryOp::from(*op).into(),
+            edit::Expression::ForExpr(expr) => ForExpr::from(*expr).into(),
+        }
+    }
+}
+
+impl TryFrom<Expression> for edit::Expression {
+    type Error = Error;
+
+    fn try_from(expr: Expression) -> Result<Self> {
+        let expr = match expr {
+            Expression::Null => edit::Expression::Null(Decorated::new(edit::Null)),
+            Expression::Bool(b) => b.into(),
+            Expression::Number(n) => n.into(),
+            Expression::String(s) => s.into(),
+            Expression::Array(array) => edit::Expression::Array(
+                array
+                    .into_iter()
+                    .map(edit::Expression::try_from)
+                    .collect::<Result<_>>()?,
+            ),
+            Expression::Object(object) => edit::Expression::Object(
+                object
+                    .into_iter()
+                    .map(|(key, expr)| {
+                        let key = edit::ObjectKey::try_from(key)?;
+                        let value = edit::ObjectValue::new(edit::Expression::try_from(expr)?);
+                        Ok((key, value))
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            Expression::TemplateExpr(expr) => edit::Expression::try_from(*expr)?,
+            Expression::Variable(var) => Decorated::<Ident>::from(var).into(),
+            Expression::Traversal(traversal) => edit::Traversal::try_from(*traversal)?.into(),
+            Expression::FuncCall(func_call) => edit::FuncCall::try_from(*func_call)?.into(),
+            Expression::Parenthesis(expr) => {
+                edit::Parenthesis::new(edit::Expression::try_from(*expr)?).into()
+            }
+            Expression::Conditional(cond) => edit::Conditional::try_from(*cond)?.into(),
+            Expression::Operation(op) => edit::Expression::try_from(*op)?,
+            Expression::ForExpr(expr) => edit::ForExpr::try_from(*expr)?.into(),
+            Expression::Raw(raw) => edit::Expression::try_from(raw)?,
+        };
+
+        Ok(expr)
+    }
+}
+
+impl From<edit::ObjectKey> for ObjectKey {
+    fn from(key: edit::ObjectKey) -> Self {
+        match key {
+            edit::ObjectKey::Ident(ident) => ObjectKey::Identifier(ident.into_value().into()),
+            edit::ObjectKey::Expression(expr) => ObjectKey::Expression(expr.into()),
+        }
+    }
+}
+
+impl TryFrom<ObjectKey> for edit::ObjectKey {
+    type Error = Error;
+
+    fn try_from(key: ObjectKey) -> Result<Self> {
+        match key {
+            ObjectKey::Identifier(ident) => Ok(edit::ObjectKey::Ident(ident.into())),
+            ObjectKey::Expression(expr) => edit::Expression::try_from(expr).map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<RawExpression> for edit::Expression {
+    type Error = Error;
+
+    fn try_from(raw: RawExpression) -> Result<Self> {
+        parser::parse_expr(raw.as_str()).map_err(Error::new)
+    }
+}
+
+impl From<StringTemplate> for TemplateExpr {
+    fn from(mut template: StringTemplate) -> Self {
+        template.decor_mut().clear();
+        let quoted = edit::Expression::Template(template).to_string();
+        // Strip the surrounding quotes, the raw template keeps its escape sequences.
+        TemplateExpr::QuotedString(quoted[1..quoted.len() - 1].to_owned())
+    }
+}
+
+impl TryFrom<TemplateExpr> for edit::Expression {
+    type Error = Error;
+
+    fn try_from(expr: TemplateExpr) -> Result<Self> {
+        match expr {
+            TemplateExpr::QuotedString(_) => {
+                let quoted = format::to_string(&expr)?;
+
+                match parser::parse_expr(&quoted).map_err(Error::new)? {
+                    // Template expressions without interpolations or directives are parsed as
+                    // plain strings, but they should stay templates.
+                    edit::Expression::String(s) => {
+                        Ok(StringTemplate::from(vec![s.into_value().into()]).into())
+                    }
+                    expr => Ok(expr),
+                }
+            }
+            TemplateExpr::Heredoc(heredoc) => HeredocTemplate::try_from(heredoc).map(Into::into),
+        }
+    }
+}
+
+impl From<HeredocTemplate> for Heredoc {
+    fn from(heredoc: HeredocTemplate) -> Self {
+        let strip = match heredoc.indent() {
+            Some(_) => HeredocStripMode::Indent,
+            None => HeredocStripMode::None,
+        };
+
+        Heredoc {
+            delimiter: heredoc.delimiter.into(),
+            template: heredoc.template.to_string(),
+            strip,
+        }
+    }
+}
+
+impl TryFrom<Heredoc> for HeredocTemplate {
+    type Error = Error;
+
+    fn try_from(heredoc: Heredoc) -> Result<Self> {
+        let mut template = heredoc.template;
+
+        // The closing delimiter must be placed on its own line.
+        if !template.ends_with('\n') {
+            template.push('\n');
+        }
+
+        let template = parser::parse_template(&template).map_err(Error::new)?;
+        let mut heredoc_template = HeredocTemplate::new(heredoc.delimiter.into(), template);
+
+        // Indented heredoc templates are already stripped of their common leading whitespace.
+        if heredoc.strip == HeredocStripMode::Indent {
+            heredoc_template.set_indent(0);
+        }
+
+        Ok(heredoc_template)
+    }
+}
+
+impl From<Decorated<Ident>> for Variable {
+    fn from(ident: Decorated<Ident>) -> Self {
+        Variable::from(Identifier::from(ident.into_value()))
+    }
+}
+
+impl From<Variable> for Decorated<Ident> {
+    fn from(var: Variable) -> Self {
+        var.into_inner().into()
+    }
+}
+
+impl From<edit::Conditional> for Conditional {
+    fn from(cond: edit::Conditional) -> Self {
+        Conditional {
+            cond_expr: cond.cond_expr.into(),
+            true_expr: cond.true_expr.into(),
+            false_expr: cond.false_expr.into(),
+        }
+    }
+}
+
+impl TryFrom<Conditional> for edit::Conditional {
+    type Error = Error;
+
+    fn try_from(cond: Conditional) -> Result<Self> {
+        Ok(edit::Conditional::new(
+            edit::Expression::try_from(cond.cond_expr)?,
+            edit::Expression::try_from(cond.true_expr)?,
+            edit::Expression::try_from(cond.false_expr)?,
+        ))
+    }
+}
+
+impl From<edit::FuncCall> for FuncCall {
+    fn from(func_call: edit::FuncCall) -> Self {
+        let expand_final = func_call.args.expand_final();
+
+        FuncCall {
+            name: func_call.ident.into_value().into(),
+            args: func_call.args.into_iter().map(Into::into).collect(),
+            expand_final,
+        }
+    }
+}
+
+impl TryFrom<FuncCall> for edit::FuncCall {
+    type Error = Error;
+
+    fn try_from(func_call: FuncCall) -> Result<Self> {
+        let mut args = func_call
+            .args
+            .into_iter()
+            .map(edit::Expression::try_from)
+            .collect::<Result<edit::FuncArgs>>()?;
+        args.set_expand_final(func_call.expand_final);
+
+        Ok(edit::FuncCall::new(func_call.name, args))
+    }
+}
+
+impl From<edit::Traversal> for Traversal {
+    fn from(traversal: edit::Traversal) -> Self {
+        Traversal {
+            expr: traversal.expr.into(),
+            operators: traversal
+                .operators
+                .into_iter()
+                .map(|operator| operator.into_value().into())
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<Traversal> for edit::Traversal {
+    type Error = Error;
+
+    fn try_from(traversal: Traversal) -> Result<Self> {
+        let operators = traversal
+            .operators
+            .into_iter()
+            .map(|operator| edit::TraversalOperator::try_from(operator).map(Decorated::new))
+            .collect::<Result<_>>()?;
+
+        Ok(edit::Traversal::new(
+            edit::Expression::try_from(traversal.expr)?,
+            operators,
+        ))
+    }
+}
+
+impl From<edit::TraversalOperator> for TraversalOperator {
+    fn from(operator: edit::TraversalOperator) -> Self {
+        match operator {
+            edit::TraversalOperator::AttrSplat(_) => TraversalOperator::AttrSplat,
+            edit::TraversalOperator::FullSplat(_) => TraversalOperator::FullSplat,
+            edit::TraversalOperator::GetAttr(ident) => {
+                TraversalOperator::GetAttr(ident.into_value().into())
+            }
+            edit::TraversalOperator::Index(expr) => TraversalOperator::Index(expr.into()),
+            edit::TraversalOperator::LegacyIndex(index) => {
+                TraversalOperator::LegacyIndex(index.into_value())
+            }
+        }
+    }
+}
+
+impl TryFrom<TraversalOperator> for edit::TraversalOperator {
+    type Error = Error;
+
+    fn try_from(operator: TraversalOperator) -> Result<Self> {
+        let operator = match operator {
+            TraversalOperator::AttrSplat => {
+                edit::TraversalOperator::AttrSplat(Decorated::new(edit::Splat))
+            }
+            TraversalOperator::FullSplat => {
+                edit::TraversalOperator::FullSplat(Decorated::new(edit::Splat))
+            }
+            TraversalOperator::GetAttr(ident) => edit::TraversalOperator::GetAttr(ident.into()),
+            TraversalOperator::Index(expr) => {
+                edit::TraversalOperator::Index(edit::Expression::try_from(expr)?)
+            }
+            TraversalOperator::LegacyIndex(index) => {
+                edit::TraversalOperator::LegacyIndex(Decorated::new(index))
+            }
+        };
+
+        Ok(operator)
+    }
+}
+
+impl From<edit::UnaryOp> for UnaryOp {
+    fn from(op: edit::UnaryOp) -> Self {
+        UnaryOp {
+            operator: op.operator.into_value(),
+            expr: op.expr.into(),
+        }
+    }
+}
+
+impl TryFrom<UnaryOp> for edit::UnaryOp {
+    type Error = Error;
+
+    fn try_from(op: UnaryOp) -> Result<Self> {
+        Ok(edit::UnaryOp::new(
+            op.operator,
+            edit::Expression::try_from(op.expr)?,
+        ))
+    }
+}
+
+impl From<edit::BinaryOp> for BinaryOp {
+    fn from(op: edit::BinaryOp) -> Self {
+        BinaryOp {
+            lhs_expr: op.lhs_expr.into(),
+            operator: op.operator.into_value(),
+            rhs_expr: op.rhs_expr.into(),
+        }
+    }
+}
+
+impl TryFrom<BinaryOp> for edit::BinaryOp {
+    type Error = Error;
+
+    fn try_from(op: BinaryOp) -> Result<Self> {
+        Ok(edit::BinaryOp::new(
+            edit::Expression::try_from(op.lhs_expr)?,
+            op.operator,
+            edit::Expression::try_from(op.rhs_expr)?,
+        ))
+    }
+}
+
+impl TryFrom<Operation> for edit::Expression {
+    type Error = Error;
+
+    fn try_from(op: Operation) -> Result<Self> {
+        match op {
+            Operation::Unary(op) => edit::UnaryOp::try_from(op).map(Into::into),
+            Operation::Binary(op) => edit::BinaryOp::try_from(op).map(Into::into),
+        }
+    }
+}
+
+impl From<edit::ForExpr> for ForExpr {
+    fn from(expr: edit::ForExpr) -> Self {
+        ForExpr {
+            key_var: expr.intro.key_var.map(|var| var.into_value().into()),
+            value_var: expr.intro.value_var.into_value().into(),
+            collection_expr: expr.intro.collection_expr.into(),
+            key_expr: expr.key_expr.map(Into::into),
+            value_expr: expr.value_expr.into(),
+            grouping: expr.grouping,
+            cond_expr: expr.cond.map(|cond| cond.expr.into()),
+        }
+    }
+}
+
+impl TryFrom<ForExpr> for edit::ForExpr {
+    type Error = Error;
+
+    fn try_from(expr: ForExpr) -> Result<Self> {
+        let mut intro = edit::ForIntro::new(
+            expr.value_var,
+            edit::Expression::try_from(expr.collection_expr)?,
+        );
+        intro.key_var = expr.key_var.map(Into::into);
+
+        let mut for_expr = edit::ForExpr::new(intro, edit::Expression::try_from(expr.value_expr)?);
+        for_expr.key_expr = expr.key_expr.map(edit::Expression::try_from).transpose()?;
+        for_expr.grouping = expr.grouping;
+        for_expr.cond = expr
+            .cond_expr
+            .map(|cond| edit::Expression::try_from(cond).map(edit::ForCond::new))
+            .transpose()?;
+
+        Ok(for_expr)
+    }
+}
@@ -12,7 +12,14 @@ use crate::{Identifier, Number, Result, Value};
 use hcl_primitives::ident::is_ident;
 use std::io;
 
-impl<T> private::Sealed for &T where T: Format {}
+impl<T> private::Sealed for &T
+where
+    T: Format,
+{
+    fn as_string_value(&self) -> Option<&str> {
+        (**self).as_string_value()
+    }
+}
 
 impl<T> Format for &T
 where
@@ -33,8 +40,28 @@ impl Format for Body {
     where
         W: io::Write,
     {
-        for structure in self.iter() {
-            structure.format(fmt)?;
+        let structures = fmt.config.body_order(fmt.body_depth).apply(&self.0);
+
+        // Leading comments are written on lines of their own which end a run of aligned
+        // attributes, just like blocks do.
+        let mut paddings = Vec::new();
+
+        for run in structures.chunk_by(|_, next| {
+            !matches!(next, Structure::Attribute(attr) if !attr.leading_comments().is_empty())
+        }) {
+            paddings.extend(fmt.key_paddings(
+                run.iter()
+                    .map(|structure| structure.as_attribute().map(|attr| (&attr.key, &attr.expr))),
+            )?);
+        }
+
+        for (i, structure) in structures.into_iter().enumerate() {
+            let key_padding = paddings.get(i).copied().unwrap_or_default();
+
+            match structure {
+                Structure::Attribute(attr) => format_attribute(fmt, attr, key_padding)?,
+                Structure::Block(block) => block.format(fmt)?,
+            }
         }
 
         Ok(())
@@ -62,14 +89,21 @@ impl Format for Attribute {
     where
         W: io::Write,
     {
-        fmt.begin_attribute()?;
-        self.key.format(fmt)?;
-        fmt.begin_attribute_value()?;
-        self.expr.format(fmt)?;
-        fmt.end_attribute()
+        format_attribute(fmt, self, 0)
     }
 }
 
+fn format_attribute<W>(fmt: &mut Formatter<W>, attr: &Attribute, key_padding: usize) -> Result<()>
+where
+    W: io::Write,
+{
+    fmt.begin_attribute(attr.leading_comments())?;
+    attr.key.format(fmt)?;
+    fmt.begin_attribute_value(key_padding)?;
+    format_value(fmt, &attr.expr)?;
+    fmt.end_attribute(attr.trailing_comment())
+}
+
 impl private::Sealed for Block {}
 
 impl Format for Block {
@@ -77,7 +111,7 @@ impl Format for Block {
     where
         W: io::Write,
     {
-        fmt.begin_block()?;
+        fmt.begin_block(self.leading_comments())?;
         self.identifier.format(fmt)?;
 
         for label in &self.labels {
@@ -86,8 +120,11 @@ impl Format for Block {
         }
 
         fmt.begin_block_body()?;
-        self.body.format(fmt)?;
-        fmt.end_block()
+        fmt.body_depth += 1;
+        let result = self.body.format(fmt);
+        fmt.body_depth -= 1;
+        result?;
+        fmt.end_block(self.trailing_comment())
     }
 }
 
@@ -105,7 +142,14 @@ impl Format for BlockLabel {
     }
 }
 
-impl private::Sealed for Expression {}
+impl private::Sealed for Expression {
+    fn as_string_value(&self) -> Option<&str> {
+        match self {
+            Expression::String(string) => Some(string),
+            _ => None,
+        }
+    }
+}
 
 impl Format for Expression {
     fn format<W>(&self, fmt: &mut Formatter<W>) -> Result<()>
@@ -117,8 +161,12 @@ impl Format for Expression {
             Expression::Bool(b) => Ok(fmt.write_bool(*b)?),
             Expression::Number(num) => num.format(fmt),
             Expression::String(string) => string.format(fmt),
-            Expression::Array(array) => format_array(fmt, array.iter()),
-            Expression::Object(object) => format_object(fmt, object.iter()),
+            Expression::Array(array) => {
+                format_group(fmt, self, |fmt| format_array(fmt, array.iter()))
+            }
+            Expression::Object(object) => {
+                format_group(fmt, self, |fmt| format_object(fmt, object.iter()))
+            }
             Expression::Raw(raw) => raw.format(fmt),
             Expression::TemplateExpr(expr) => expr.format(fmt),
             Expression::Variable(var) => var.format(fmt),
@@ -136,7 +184,11 @@ impl Format for Expression {
     }
 }
 
-impl private::Sealed for Value {}
+impl private::Sealed for Value {
+    fn as_string_value(&self) -> Option<&str> {
+        self.as_str()
+    }
+}
 
 impl Format for Value {
     fn format<W>(&self, fmt: &mut Formatter<W>) -> Result<()>
@@ -148,8 +200,10 @@ impl Format for Value {
             Value::Bool(b) => Ok(fmt.write_bool(*b)?),
             Value::Number(num) => num.format(fmt),
             Value::String(string) => string.format(fmt),
-            Value::Array(array) => format_array(fmt, array.iter()),
-            Value::Object(object) => format_object(fmt, object.iter().map(|(k, v)| (StrKey(k), v))),
+            Value::Array(array) => format_group(fmt, self, |fmt| format_array(fmt, array.iter())),
+            Value::Object(object) => format_group(fmt, self, |fmt| {
+                format_object(fmt, object.iter().map(|(k, v)| (StrKey(k), v)))
+            }),
         }
     }
 }
@@ -316,9 +370,36 @@ impl Format for FuncCall {
     where
         W: io::Write,
     {
+        let expanded = !self.args.is_empty() && fmt.fits_on_line(self)? == Some(false);
+
         self.name.format(fmt)?;
         fmt.write_bytes(b"(")?;
 
+        if expanded {
+            // Place each argument on its own line.
+            fmt.current_indent += 1;
+
+            for (i, arg) in self.args.iter().enumerate() {
+                fmt.write_bytes(b"\n")?;
+                fmt.write_indent(fmt.current_indent)?;
+                arg.format(fmt)?;
+
+                if i + 1 < self.args.len() || !self.expand_final {
+                    fmt.write_bytes(b",")?;
+                }
+            }
+
+            fmt.current_indent -= 1;
+
+            if self.expand_final {
+                fmt.write_bytes(b"...")?;
+            }
+
+            fmt.write_bytes(b"\n")?;
+            fmt.write_indent(fmt.current_indent)?;
+            return fmt.write_bytes(b")");
+        }
+
         fmt.with_compact_mode(|fmt| {
             for (i, arg) in self.args.iter().enumerate() {
                 if i > 0 {
@@ -346,13 +427,21 @@ impl Format for Conditional {
     where
         W: io::Write,
     {
-        fmt.with_compact_mode(|fmt| {
+        let format_cond = |fmt: &mut Formatter<W>| {
             self.cond_expr.format(fmt)?;
             fmt.write_bytes(b" ? ")?;
             self.true_expr.format(fmt)?;
             fmt.write_bytes(b" : ")?;
             self.false_expr.format(fmt)
-        })
+        };
+
+        // HCL does not allow line breaks around the `?` and `:`, but the operands may be broken
+        // up if the conditional does not fit on a single line.
+        if fmt.fits_on_line(self)? == Some(false) {
+            format_cond(fmt)
+        } else {
+            fmt.with_compact_mode(format_cond)
+        }
     }
 }
 
@@ -556,6 +645,34 @@ impl Format for String {
     }
 }
 
+// Formats the value of an attribute or object item. Multi-line strings are formatted as heredocs
+// if preferred, see `Formatter::heredoc_string`.
+fn format_value<W, T>(fmt: &mut Formatter<W>, value: &T) -> Result<()>
+where
+    W: io::Write,
+    T: Format,
+{
+    match value.as_string_value().and_then(|s| fmt.heredoc_string(s)) {
+        Some(heredoc) => heredoc.format(fmt),
+        None => value.format(fmt),
+    }
+}
+
+// Formats an array or object in compact mode if it fits on the current line, see
+// `Formatter::fits_on_line`.
+fn format_group<W, T, F>(fmt: &mut Formatter<W>, value: &T, f: F) -> Result<()>
+where
+    W: io::Write,
+    T: Format,
+    F: FnOnce(&mut Formatter<W>) -> Result<()>,
+{
+    if fmt.fits_on_line(value)? == Some(true) {
+        fmt.with_compact_mode(f)
+    } else {
+        f(fmt)
+    }
+}
+
 fn format_array<W, T>(fmt: &mut Formatter<W>, array: impl Iterator<Item = T>) -> Result<()>
 where
     W: io::Write,
@@ -581,13 +698,22 @@ where
     K: Format,
     V: Format,
 {
+    let items: Vec<_> = object.collect();
+
     fmt.begin_object()?;
 
-    for (key, value) in object {
+    // Items of compact objects are all on the same line and are not aligned.
+    let paddings = if fmt.compact_objects() {
+        Vec::new()
+    } else {
+        fmt.key_paddings(items.iter().map(|(key, value)| Some((key, value))))?
+    };
+
+    for (i, (key, value)) in items.iter().enumerate() {
         fmt.begin_object_key()?;
         key.format(fmt)?;
-        fmt.begin_object_value()?;
-        value.format(fmt)?;
+        fmt.begin_object_value(paddings.get(i).copied().unwrap_or_default())?;
+        format_value(fmt, value)?;
         fmt.end_object_value()?;
     }
 
@@ -38,11 +38,19 @@ mod escape;
 mod impls;
 
 use self::escape::{CharEscape, ESCAPE};
-use crate::Result;
+use crate::expr::{Heredoc, HeredocStripMode};
+use crate::structure::{Attribute, BlockLabel, Comment, CommentStyle, Structure};
+use crate::{Identifier, Result};
+use std::borrow::Cow;
 use std::io;
 
 mod private {
-    pub trait Sealed {}
+    pub trait Sealed {
+        // Returns the string if `self` is a string that may be formatted as a heredoc.
+        fn as_string_value(&self) -> Option<&str> {
+            None
+        }
+    }
 }
 
 /// A trait to format data structures as HCL.
@@ -70,6 +78,7 @@ pub trait Format: private::Sealed {
         self.format(fmt)?;
         // "Drain" the buffer by splitting off all bytes, leaving the formatter's buffer empty
         // ready for reuse.
+        fmt.column = 0;
         Ok(fmt.writer.as_mut().split_off(0))
     }
 
@@ -98,12 +107,18 @@ enum FormatState {
     BlockBodyStart,
 }
 
+#[derive(Clone)]
 struct FormatConfig<'a> {
     indent: &'a [u8],
     dense: bool,
     compact_arrays: bool,
     compact_objects: bool,
     prefer_ident_keys: bool,
+    align_attributes: bool,
+    max_width: Option<usize>,
+    prefer_heredocs: bool,
+    body_order: BodyOrder,
+    body_order_overrides: Vec<(usize, BodyOrder)>,
 }
 
 impl<'a> Default for FormatConfig<'a> {
@@ -114,7 +129,111 @@ impl<'a> Default for FormatConfig<'a> {
             compact_arrays: false,
             compact_objects: false,
             prefer_ident_keys: false,
+            align_attributes: false,
+            max_width: None,
+            prefer_heredocs: false,
+            body_order: BodyOrder::default(),
+            body_order_overrides: Vec::new(),
+        }
+    }
+}
+
+impl FormatConfig<'_> {
+    fn body_order(&self, depth: usize) -> BodyOrder {
+        self.body_order_overrides
+            .iter()
+            .rev()
+            .find(|(d, _)| *d == depth)
+            .map_or(self.body_order, |(_, order)| *order)
+    }
+}
+
+/// Controls the order in which the formatter writes the attributes and blocks of a body.
+///
+/// The default `BodyOrder` preserves the order of the body. Use [`BodyOrder::canonical`] to sort
+/// attributes and blocks and to group attributes before blocks, or configure each of these
+/// individually.
+///
+/// See [`FormatterBuilder::body_order`] for a usage example.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BodyOrder {
+    sort_attributes: bool,
+    sort_blocks: bool,
+    attributes_first: bool,
+}
+
+impl BodyOrder {
+    /// Creates a `BodyOrder` which preserves the order of the body.
+    pub fn new() -> BodyOrder {
+        BodyOrder::default()
+    }
+
+    /// Creates a `BodyOrder` which writes all attributes before all blocks, and sorts the
+    /// attributes by key and the blocks by identifier and labels.
+    pub fn canonical() -> BodyOrder {
+        BodyOrder {
+            sort_attributes: true,
+            sort_blocks: true,
+            attributes_first: true,
+        }
+    }
+
+    /// Controls whether attributes are sorted alphabetically by key.
+    ///
+    /// Unless attributes are grouped before blocks, only attributes which are not separated by a
+    /// block are sorted relative to each other.
+    pub fn sort_attributes(mut self, yes: bool) -> BodyOrder {
+        self.sort_attributes = yes;
+        self
+    }
+
+    /// Controls whether blocks are sorted alphabetically by identifier, and blocks with the same
+    /// identifier by their labels.
+    ///
+    /// Unless attributes are grouped before blocks, only blocks which are not separated by an
+    /// attribute are sorted relative to each other.
+    pub fn sort_blocks(mut self, yes: bool) -> BodyOrder {
+        self.sort_blocks = yes;
+        self
+    }
+
+    /// Controls whether all attributes are written before all blocks.
+    ///
+    /// The relative order of the attributes and of the blocks is preserved, unless they are
+    /// sorted as well.
+    pub fn attributes_first(mut self, yes: bool) -> BodyOrder {
+        self.attributes_first = yes;
+        self
+    }
+
+    // Returns the structures in the order they are written in.
+    fn apply(self, structures: &[Structure]) -> Vec<&Structure> {
+        let mut structures: Vec<&Structure> = structures.iter().collect();
+
+        if self.attributes_first {
+            // The sort is stable, so the relative order of attributes and blocks is preserved.
+            structures.sort_by_key(|structure| structure.is_block());
+        }
+
+        for run in structures.chunk_by_mut(|prev, next| prev.is_block() == next.is_block()) {
+            match run[0] {
+                Structure::Attribute(_) if self.sort_attributes => {
+                    run.sort_by_key(|structure| structure.as_attribute().map(Attribute::key));
+                }
+                Structure::Block(_) if self.sort_blocks => {
+                    run.sort_by_key(|structure| {
+                        structure.as_block().map(|block| {
+                            let labels: Vec<&str> =
+                                block.labels().iter().map(BlockLabel::as_str).collect();
+                            (block.identifier(), labels)
+                        })
+                    });
+                }
+                _ => {}
+            }
         }
+
+        structures
     }
 }
 
@@ -179,6 +298,8 @@ pub struct Formatter<'a, W> {
     current_indent: usize,
     has_value: bool,
     compact_mode_level: u64,
+    column: usize,
+    body_depth: usize,
 }
 
 /// A builder to create a `Formatter`.
@@ -304,6 +425,180 @@ impl<'a> FormatterBuilder<'a> {
         self
     }
 
+    /// Controls the alignment of attributes and object items.
+    ///
+    /// By default, the `=` follows the attribute key or object key after a single space:
+    ///
+    /// ```hcl
+    /// name = "web"
+    /// instance_type = "t2.micro"
+    /// tags = {
+    ///   env = "prod"
+    ///   owner = "ops"
+    /// }
+    /// ```
+    ///
+    /// When attribute alignment is enabled, keys are padded so that the `=` of each run of
+    /// consecutive attributes or object items lines up, like `terraform fmt` does:
+    ///
+    /// ```hcl
+    /// name          = "web"
+    /// instance_type = "t2.micro"
+    /// tags = {
+    ///   env   = "prod"
+    ///   owner = "ops"
+    /// }
+    /// ```
+    ///
+    /// Items whose value spans multiple lines (except for heredocs) and blocks end a run.
+    pub fn align_attributes(mut self, yes: bool) -> Self {
+        self.config.align_attributes = yes;
+        self
+    }
+
+    /// Sets the maximum line width that the formatter aims for.
+    ///
+    /// By default, arrays and objects are always expanded (unless
+    /// [`compact_arrays`][FormatterBuilder::compact_arrays] or
+    /// [`compact_objects`][FormatterBuilder::compact_objects] is set), while function calls and
+    /// conditionals are always kept on a single line.
+    ///
+    /// With a maximum width, arrays, objects, function calls and conditionals are kept on a
+    /// single line if they fit into the remainder of the current line, and are broken up
+    /// otherwise. Nested collections and function calls are laid out the same way, so that short
+    /// ones stay on one line:
+    ///
+    /// ```hcl
+    /// tags = { env = "prod", owner = "ops" }
+    /// subnets = [
+    ///   cidrsubnet(var.vpc_cidr, 8, 1),
+    ///   cidrsubnet(var.vpc_cidr, 8, 2)
+    /// ]
+    /// policy = jsonencode(
+    ///   {
+    ///     Version = "2012-10-17"
+    ///     Statement = [
+    ///       { Action = "s3:GetObject", Effect = "Allow" }
+    ///     ]
+    ///   },
+    /// )
+    /// ```
+    ///
+    /// A line may still exceed the maximum width if it contains an element that can not be broken
+    /// up, like a long string.
+    pub fn max_width(mut self, width: usize) -> Self {
+        self.config.max_width = Some(width);
+        self
+    }
+
+    /// Controls the formatting of multi-line strings.
+    ///
+    /// By default, strings are always quoted and newlines are escaped:
+    ///
+    /// ```hcl
+    /// user_data = "#!/bin/bash\necho \"hello\"\n"
+    /// ```
+    ///
+    /// When heredocs are preferred, multi-line strings that are the value of an attribute or
+    /// object item are formatted as indented heredocs instead:
+    ///
+    /// ```hcl
+    /// user_data = <<-EOT
+    ///   #!/bin/bash
+    ///   echo "hello"
+    /// EOT
+    /// ```
+    ///
+    /// The heredoc delimiter is chosen so that it does not collide with any line of the string.
+    /// Strings whose lines all start with whitespace are formatted as heredocs without
+    /// indentation (`<<EOT`), since the common leading whitespace of an indented heredoc is
+    /// stripped.
+    ///
+    /// Only strings which parse back to the same string are formatted as heredocs. This excludes
+    /// strings that do not end with a newline, strings containing control characters other than
+    /// newlines and tabs, and strings containing the template sequences `${` or `%{` (escaped or
+    /// not). These, as well as strings in compact mode, are still quoted. Template expressions
+    /// are never formatted as heredocs.
+    pub fn prefer_heredocs(mut self, yes: bool) -> Self {
+        self.config.prefer_heredocs = yes;
+        self
+    }
+
+    /// Controls the order in which the attributes and blocks of bodies are written.
+    ///
+    /// By default, the order of a body is preserved. When serializing types whose fields are
+    /// unordered, like a `HashMap`, this means that the order of the output changes between
+    /// runs. A sorting [`BodyOrder`] makes the output deterministic.
+    ///
+    /// The order applies to bodies at all depths unless it is overridden for a specific depth
+    /// via [`body_order_at_depth`][FormatterBuilder::body_order_at_depth].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use hcl::format::{BodyOrder, Format, Formatter};
+    ///
+    /// let body = hcl::body!({
+    ///     resource "aws_s3_bucket" "logs" {
+    ///         region = "us-east-1"
+    ///         bucket = "logs"
+    ///     }
+    ///     variable "region" {}
+    ///     resource "aws_instance" "web" {}
+    ///     provider = "aws"
+    /// });
+    ///
+    /// let mut formatter = Formatter::builder()
+    ///     .body_order(BodyOrder::canonical())
+    ///     .build_vec();
+    ///
+    /// let expected = r#"
+    /// provider = "aws"
+    ///
+    /// resource "aws_instance" "web" {}
+    ///
+    /// resource "aws_s3_bucket" "logs" {
+    ///   bucket = "logs"
+    ///   region = "us-east-1"
+    /// }
+    ///
+    /// variable "region" {}
+    /// "#.trim_start();
+    ///
+    /// assert_eq!(body.format_string(&mut formatter)?, expected);
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub fn body_order(mut self, order: BodyOrder) -> Self {
+        self.config.body_order = order;
+        self
+    }
+
+    /// Overrides the order in which the attributes and blocks of bodies at the given depth are
+    /// written.
+    ///
+    /// The body that is formatted is at depth `0`, the bodies of its blocks are at depth `1`, and
+    /// so on. Bodies at other depths use the order set via
+    /// [`body_order`][FormatterBuilder::body_order].
+    ///
+    /// For example, to only sort the top-level blocks of a file and to keep the contents of the
+    /// blocks as they are:
+    ///
+    /// ```
+    /// use hcl::format::{BodyOrder, Formatter};
+    ///
+    /// let formatter = Formatter::builder()
+    ///     .body_order_at_depth(0, BodyOrder::new().sort_blocks(true))
+    ///     .build_vec();
+    /// ```
+    pub fn body_order_at_depth(mut self, depth: usize, order: BodyOrder) -> Self {
+        self.config.body_order_overrides.push((depth, order));
+        self
+    }
+
     /// Consumes the `FormatterBuilder` and turns it into a `Formatter` which writes HCL to the
     /// provided writer.
     pub fn build<W>(self, writer: W) -> Formatter<'a, W>
@@ -318,6 +613,8 @@ impl<'a> FormatterBuilder<'a> {
             current_indent: 0,
             has_value: false,
             compact_mode_level: 0,
+            column: 0,
+            body_depth: 0,
         }
     }
 
@@ -460,7 +757,11 @@ where
             }
 
             let char_escape = CharEscape::from_escape_table(escape, byte);
-            char_escape.write_escaped(&mut self.writer)?;
+            let mut buf = [0; 6];
+            let mut escaped = &mut buf[..];
+            char_escape.write_escaped(&mut escaped)?;
+            let len = 6 - escaped.len();
+            self.write_bytes(&buf[..len])?;
 
             start = i + 1;
         }
@@ -550,8 +851,10 @@ where
         Ok(())
     }
 
-    /// Signals the start of an object value to the formatter.
-    fn begin_object_value(&mut self) -> Result<()> {
+    /// Signals the start of an object value to the formatter. The `key_padding` is the number of
+    /// spaces needed to align the `=` with the one of adjacent object items.
+    fn begin_object_value(&mut self, key_padding: usize) -> Result<()> {
+        self.write_padding(key_padding)?;
         self.write_bytes(b" = ")
     }
 
@@ -578,26 +881,34 @@ where
         self.write_bytes(b"}")
     }
 
-    /// Signals the start of an attribute to the formatter.
-    fn begin_attribute(&mut self) -> Result<()> {
+    /// Signals the start of an attribute to the formatter. The `comments` are written on their own
+    /// lines before the attribute.
+    fn begin_attribute(&mut self, comments: &[Comment]) -> Result<()> {
         self.maybe_write_newline(FormatState::AttributeStart)?;
+        self.write_leading_comments(comments)?;
         self.write_indent(self.current_indent)
     }
 
-    /// Signals the start of an attribute value to the formatter.
-    fn begin_attribute_value(&mut self) -> Result<()> {
+    /// Signals the start of an attribute value to the formatter. The `key_padding` is the number
+    /// of spaces needed to align the `=` with the one of adjacent attributes.
+    fn begin_attribute_value(&mut self, key_padding: usize) -> Result<()> {
+        self.write_padding(key_padding)?;
         self.write_bytes(b" = ")
     }
 
-    /// Signals the end of an attribute to the formatter.
-    fn end_attribute(&mut self) -> Result<()> {
+    /// Signals the end of an attribute to the formatter. The `comment` is written after the
+    /// attribute value on the same line.
+    fn end_attribute(&mut self, comment: Option<&Comment>) -> Result<()> {
         self.state = FormatState::AttributeEnd;
+        self.write_trailing_comment(comment)?;
         self.write_bytes(b"\n")
     }
 
-    /// Signals the start of a block to the formatter.
-    fn begin_block(&mut self) -> Result<()> {
+    /// Signals the start of a block to the formatter. The `comments` are written on their own
+    /// lines before the block.
+    fn begin_block(&mut self, comments: &[Comment]) -> Result<()> {
         self.maybe_write_newline(FormatState::BlockStart)?;
+        self.write_leading_comments(comments)?;
         self.write_indent(self.current_indent)
     }
 
@@ -608,12 +919,74 @@ where
         self.write_bytes(b" {")
     }
 
-    /// Signals the end of a block to the formatter.
-    fn end_block(&mut self) -> Result<()> {
+    /// Signals the end of a block to the formatter. The `comment` is written after the closing
+    /// brace on the same line.
+    fn end_block(&mut self, comment: Option<&Comment>) -> Result<()> {
         self.state = FormatState::BlockEnd;
         self.current_indent -= 1;
         self.write_indent(self.current_indent)?;
-        self.write_bytes(b"}\n")
+        self.write_bytes(b"}")?;
+        self.write_trailing_comment(comment)?;
+        self.write_bytes(b"\n")
+    }
+
+    fn write_leading_comments(&mut self, comments: &[Comment]) -> Result<()> {
+        for comment in comments {
+            self.write_indent(self.current_indent)?;
+            self.write_comment(comment)?;
+            self.write_bytes(b"\n")?;
+        }
+
+        Ok(())
+    }
+
+    fn write_trailing_comment(&mut self, comment: Option<&Comment>) -> Result<()> {
+        if let Some(comment) = comment {
+            self.write_bytes(b" ")?;
+            self.write_comment(comment)?;
+        }
+
+        Ok(())
+    }
+
+    // Writes a comment starting at the current column. Each line of the comment text after the
+    // first one is put on a new line at the current indentation level. Line comments repeat the
+    // comment marker on every line.
+    fn write_comment(&mut self, comment: &Comment) -> Result<()> {
+        let (first, rest, text) = match comment.style() {
+            CommentStyle::Hash => ("# ", "# ", Cow::Borrowed(comment.text())),
+            CommentStyle::DoubleSlash => ("// ", "// ", Cow::Borrowed(comment.text())),
+            // The comment text must not terminate the comment early.
+            CommentStyle::SlashStar => (
+                "/* ",
+                "   ",
+                Cow::Owned(comment.text().replace("*/", "* /")),
+            ),
+        };
+
+        let mut lines: Vec<&str> = text.lines().collect();
+
+        if lines.is_empty() {
+            lines.push("");
+        }
+
+        for (i, line) in lines.into_iter().enumerate() {
+            let prefix = if i == 0 {
+                first
+            } else {
+                self.write_bytes(b"\n")?;
+                self.write_indent(self.current_indent)?;
+                rest
+            };
+
+            self.write_string_fragment(format!("{prefix}{line}").trim_end())?;
+        }
+
+        if comment.style() == CommentStyle::SlashStar {
+            self.write_bytes(b" */")?;
+        }
+
+        Ok(())
     }
 
     // Conditionally writes a newline character depending on the formatter configuration and the
@@ -648,6 +1021,14 @@ where
         Ok(())
     }
 
+    fn write_padding(&mut self, n: usize) -> Result<()> {
+        for _ in 0..n {
+            self.write_bytes(b" ")?;
+        }
+
+        Ok(())
+    }
+
     fn write_indented(&mut self, n: usize, s: &str) -> Result<()> {
         for (i, line) in s.lines().enumerate() {
             if i > 0 {
@@ -667,6 +1048,15 @@ where
 
     fn write_bytes(&mut self, buf: &[u8]) -> Result<()> {
         self.writer.write_all(buf)?;
+
+        // Count characters instead of bytes by skipping UTF-8 continuation bytes.
+        let chars = |bytes: &[u8]| bytes.iter().filter(|&&b| b & 0xC0 != 0x80).count();
+
+        self.column = match buf.iter().rposition(|&b| b == b'\n') {
+            Some(pos) => chars(&buf[pos + 1..]),
+            None => self.column + chars(buf),
+        };
+
         Ok(())
     }
 
@@ -684,6 +1074,174 @@ where
         result
     }
 
+    /// Computes the key padding for each of the given key-value pairs that is needed to align the
+    /// `=` across runs of consecutive pairs. A `None` item ends a run.
+    ///
+    /// Returns an empty `Vec` if attribute alignment is disabled.
+    fn key_paddings<K, V>(&self, items: impl Iterator<Item = Option<(K, V)>>) -> Result<Vec<usize>>
+    where
+        K: Format,
+        V: Format,
+    {
+        if !self.config.align_attributes {
+            return Ok(Vec::new());
+        }
+
+        // The width of each key that is part of a run, or `None` if the item ends a run.
+        let mut widths = Vec::new();
+
+        for item in items {
+            let width = match item {
+                Some((key, value)) => {
+                    let key = self.format_detached(&key, 0)?;
+                    let column =
+                        self.current_indent * self.config.indent.len() + key.chars().count() + 3;
+                    let value = self.format_detached(&value, column)?;
+
+                    // Heredocs span multiple lines but their first line is a complete expression.
+                    let single_line = !value.contains('\n') || value.starts_with("<<");
+
+                    (single_line && !key.contains('\n')).then(|| key.chars().count())
+                }
+                None => None,
+            };
+
+            widths.push(width);
+        }
+
+        let mut paddings = vec![0; widths.len()];
+        let mut start = 0;
+
+        while start < widths.len() {
+            let len = widths[start..]
+                .iter()
+                .position(Option::is_none)
+                .unwrap_or(widths.len() - start);
+
+            let run = &widths[start..start + len];
+            let max = run.iter().flatten().max().copied().unwrap_or_default();
+
+            for (padding, width) in paddings[start..].iter_mut().zip(run.iter().flatten()) {
+                *padding = max - width;
+            }
+
+            start += len + 1;
+        }
+
+        Ok(paddings)
+    }
+
+    /// Decides whether a group (an array, object, function call or conditional) is laid out on a
+    /// single line.
+    ///
+    /// Returns `None` if no maximum width is configured or compact mode is already enabled.
+    /// Otherwise, returns `Some(true)` if the value fits into the remainder of the current line
+    /// when formatted in compact mode.
+    fn fits_on_line<T>(&self, value: &T) -> Result<Option<bool>>
+    where
+        T: Format,
+    {
+        let Some(max_width) = self.config.max_width else {
+            return Ok(None);
+        };
+
+        if self.in_compact_mode() {
+            return Ok(None);
+        }
+
+        let flat = self.with_detached(self.column, |fmt| {
+            fmt.compact_mode_level += 1;
+            value.format_string(fmt)
+        })?;
+
+        Ok(Some(
+            !flat.contains('\n') && self.column + flat.chars().count() <= max_width,
+        ))
+    }
+
+    /// Formats the value into a new buffer as if it started at `column`, using the current
+    /// indentation and compact mode, but without attribute alignment.
+    fn format_detached<T>(&self, value: &T, column: usize) -> Result<String>
+    where
+        T: Format,
+    {
+        self.with_detached(column, |fmt| value.format_string(fmt))
+    }
+
+    fn with_detached<F>(&self, column: usize, f: F) -> Result<String>
+    where
+        F: FnOnce(&mut Formatter<'a, Vec<u8>>) -> Result<String>,
+    {
+        let mut config = self.config.clone();
+        config.align_attributes = false;
+
+        let mut fmt = Formatter {
+            writer: Vec::new(),
+            config,
+            state: FormatState::Initial,
+            first_element: false,
+            current_indent: self.current_indent,
+            has_value: false,
+            compact_mode_level: self.compact_mode_level,
+            column,
+            body_depth: self.body_depth,
+        };
+
+        f(&mut fmt)
+    }
+
+    /// Returns the heredoc that a multi-line string is formatted as, or `None` if the string must
+    /// be quoted.
+    fn heredoc_string(&self, s: &str) -> Option<Heredoc> {
+        if !self.config.prefer_heredocs || self.in_compact_mode() {
+            return None;
+        }
+
+        // The newline before the closing delimiter is part of the heredoc template.
+        if !s.ends_with('\n') || s.lines().count() < 2 {
+            return None;
+        }
+
+        // Heredocs do not support escape sequences.
+        if s.chars()
+            .any(|ch| ch.is_control() && ch != '\n' && ch != '\t')
+        {
+            return None;
+        }
+
+        // Template sequences are kept verbatim in string values, so they cannot be escaped in a
+        // way that parses back to the same string. Keep these strings quoted.
+        if s.contains("${") || s.contains("%{") {
+            return None;
+        }
+
+        let delimiter = heredoc_delimiter(s);
+
+        // An indented heredoc strips the whitespace that all non-empty lines have in common,
+        // which must not include whitespace that is part of the string.
+        let indent = s
+            .lines()
+            .any(|line| !line.is_empty() && !line.starts_with(char::is_whitespace));
+
+        if !indent {
+            return Some(Heredoc::new(delimiter, s.to_owned()));
+        }
+
+        let prefix = String::from_utf8_lossy(self.config.indent).repeat(self.current_indent + 1);
+        let mut template = String::with_capacity(s.len());
+
+        for line in s.lines() {
+            if !line.is_empty() {
+                template.push_str(&prefix);
+                template.push_str(line);
+            }
+
+            template.push('\n');
+        }
+
+        Some(Heredoc::new(delimiter, template).with_strip_mode(HeredocStripMode::Indent))
+    }
+
     fn compact_arrays(&self) -> bool {
         self.config.compact_arrays || self.in_compact_mode()
     }
@@ -697,6 +1255,31 @@ where
     }
 }
 
+// Returns the first of `EOT`, `EOT1`, `EOT2`, ... which does not start any line of the string.
+// A line starting with the delimiter would end the heredoc.
+fn heredoc_delimiter(s: &str) -> Identifier {
+    let collides = |delimiter: &str| {
+        s.lines()
+            .any(|line| line.trim_start().starts_with(delimiter))
+    };
+
+    // Each prefix of a line matches at most one delimiter, so a string of `n` bytes collides with
+    // at most `n` of them.
+    for n in 0..=s.len() {
+        let delimiter = if n == 0 {
+            String::from("EOT")
+        } else {
+            format!("EOT{n}")
+        };
+
+        if !collides(&delimiter) {
+            return Identifier::unchecked(delimiter);
+        }
+    }
+
+    unreachable!("a string of n bytes collides with at most n delimiters")
+}
+
 /// Format the given value as an HCL byte vector.
 ///
 /// If you need to serialize custom data structures implementing [`serde::Serialize`] use
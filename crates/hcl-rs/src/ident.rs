@@ -1,5 +1,6 @@
 use crate::expr::Variable;
 use crate::{Error, InternalString, Result};
+use hcl_edit::Decorated;
 use hcl_primitives::Ident;
 use serde::{Deserialize, Serialize};
 use std::borrow::{Borrow, Cow};
@@ -107,6 +108,18 @@ impl From<Ident> for Identifier {
     }
 }
 
+impl From<Identifier> for Ident {
+    fn from(ident: Identifier) -> Self {
+        ident.0
+    }
+}
+
+impl From<Identifier> for Decorated<Ident> {
+    fn from(ident: Identifier) -> Self {
+        Decorated::new(ident.0)
+    }
+}
+
 impl From<String> for Identifier {
     fn from(s: String) -> Self {
         Identifier::sanitized(s)
@@ -0,0 +1,291 @@
+//! Parse and serialize HCL in its [JSON representation][json-spec].
+//!
+//! HCL configuration can also be written in JSON (usually in files with a `.tf.json` or
+//! `.hcl.json` extension). This module converts such JSON input into a [`Body`], so that the same
+//! processing pipeline can be used for native HCL and HCL JSON alike. The functions
+//! [`to_string`] and [`to_writer`] provide the reverse direction.
+//!
+//! Other than the native syntax, JSON cannot distinguish between attributes and blocks. Whether a
+//! JSON object property represents an attribute or a block, and how many levels of nested JSON
+//! objects represent block labels, is defined by a [`Schema`]. All properties that are not
+//! declared as blocks in the schema are interpreted as attributes.
+//!
+//! Strings in attribute values are interpreted as templates, so that `"${var.name}"` becomes an
+//! interpolation expression that can be evaluated using the [`eval`][crate::eval] module. Object
+//! properties named `//` are treated as comments and are ignored.
+//!
+//! This module is only available if the `json` feature is enabled.
+//!
+//! [json-spec]: https://github.com/hashicorp/hcl/blob/main/json/spec.md
+//!
+//! # Example
+//!
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use hcl::json::Schema;
+//!
+//! let input = r#"{
+//!   "variable": {
+//!     "name": {
+//!       "default": "bucket"
+//!     }
+//!   },
+//!   "resource": {
+//!     "aws_s3_bucket": {
+//!       "bucket": {
+//!         "name": "${var.name}",
+//!         "tags": {"env": "prod"}
+//!       }
+//!     }
+//!   }
+//! }"#;
+//!
+//! let schema = Schema::new()
+//!     .add_block("variable", 1)
+//!     .add_block("resource", 2);
+//!
+//! let body = hcl::json::parse(input, &schema)?;
+//!
+//! let expected = r#"variable "name" {
+//!   default = "bucket"
+//! }
+//!
+//! resource "aws_s3_bucket" "bucket" {
+//!   name = "${var.name}"
+//!   tags = {
+//!     env = "prod"
+//!   }
+//! }
+//! "#;
+//!
+//! assert_eq!(hcl::to_string(&body)?, expected);
+//! #   Ok(())
+//! # }
+//! ```
+
+mod parser;
+mod ser;
+
+use crate::{Body, Error, Map, Result};
+use serde::de;
+use std::io;
+
+/// Describes which properties of a JSON object represent HCL blocks.
+///
+/// JSON object properties that are not declared as blocks are interpreted as attributes. An empty
+/// schema thus interprets all properties as attributes.
+///
+/// # Example
+///
+/// ```
+/// use hcl::json::Schema;
+///
+/// let schema = Schema::new()
+///     .add_block("variable", 1)
+///     .add_nested_block(
+///         "resource",
+///         2,
+///         Schema::new().add_block("lifecycle", 0),
+///     );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    blocks: Map<String, BlockSchema>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BlockSchema {
+    labels: usize,
+    body: Schema,
+}
+
+impl Schema {
+    /// Creates a new empty `Schema`.
+    pub fn new() -> Schema {
+        Schema::default()
+    }
+
+    /// Declares a block type with the given number of labels.
+    ///
+    /// All properties of the block's body are interpreted as attributes. Use
+    /// [`add_nested_block`][Schema::add_nested_block] if the block body contains blocks itself.
+    ///
+    /// Consumes `self` and returns the updated `Schema`.
+    pub fn add_block<I>(self, identifier: I, labels: usize) -> Schema
+    where
+        I: Into<String>,
+    {
+        self.add_nested_block(identifier, labels, Schema::new())
+    }
+
+    /// Declares a block type with the given number of labels whose body is interpreted using
+    /// `body`.
+    ///
+    /// Consumes `self` and returns the updated `Schema`.
+    pub fn add_nested_block<I>(mut self, identifier: I, labels: usize, body: Schema) -> Schema
+    where
+        I: Into<String>,
+    {
+        self.blocks
+            .insert(identifier.into(), BlockSchema { labels, body });
+        self
+    }
+
+    fn block(&self, identifier: &str) -> Option<&BlockSchema> {
+        self.blocks.get(identifier)
+    }
+}
+
+/// Parse a `hcl::Body` from a `&str` containing HCL JSON.
+///
+/// See the [module-level documentation](self) for details about the interpretation of the JSON
+/// input.
+///
+/// # Errors
+///
+/// This function fails with an error if the `input` is not valid JSON, if it does not match the
+/// structure expected by the `schema` or if a string contains an invalid template.
+pub fn parse(input: &str, schema: &Schema) -> Result<Body> {
+    parser::parse(input, schema)
+}
+
+/// Deserialize an instance of type `T` from a string of HCL JSON.
+///
+/// The input is parsed into a [`Body`] using [`parse`] first, which is then deserialized like
+/// [`hcl::from_body`][crate::from_body] does.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hcl::json::Schema;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Config {
+///     service: Service,
+/// }
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Service {
+///     port: u16,
+/// }
+///
+/// let input = r#"{"service": {"port": 8080}}"#;
+/// let schema = Schema::new().add_block("service", 0);
+///
+/// let config: Config = hcl::json::from_str(input, &schema)?;
+///
+/// assert_eq!(config, Config { service: Service { port: 8080 } });
+/// #   Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function fails with an error if the `input` cannot be parsed or if the data does not
+/// match the structure of `T`.
+pub fn from_str<T>(input: &str, schema: &Schema) -> Result<T>
+where
+    T: de::DeserializeOwned,
+{
+    let body = parse(input, schema)?;
+    crate::from_body(body)
+}
+
+/// Serialize a `hcl::Body` into a string of HCL JSON.
+///
+/// Blocks are represented as nested JSON objects with one level per block label. Blocks with the
+/// same type and labels are merged into a JSON array. Expressions that are not literal values,
+/// like variables, function calls or operations, as well as templates are encoded as template
+/// strings, e.g. `"${var.name}"`. Since HCL JSON strings are parsed as templates, the `${` and
+/// `%{` sequences in literal strings and object keys are escaped as `$${` and `%%{`.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let input = r#"
+/// resource "aws_s3_bucket" "bucket" {
+///   name  = "${var.prefix}-bucket"
+///   count = var.count + 1
+/// }
+/// "#;
+///
+/// let body = hcl::parse(input)?;
+///
+/// let expected = r#"{"resource":{"aws_s3_bucket":{"bucket":{"name":"${var.prefix}-bucket","count":"${var.count + 1}"}}}}"#;
+///
+/// assert_eq!(hcl::json::to_string(&body)?, expected);
+/// #   Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function fails with an error if the body cannot be serialized to JSON.
+pub fn to_string(body: &Body) -> Result<String> {
+    serde_json::to_string(&ser::to_value(body)).map_err(Error::new)
+}
+
+/// Serialize a `hcl::Body` into a pretty-printed string of HCL JSON.
+///
+/// See the documentation of [`to_string`] for more information.
+///
+/// # Errors
+///
+/// This function fails with an error if the body cannot be serialized to JSON.
+pub fn to_string_pretty(body: &Body) -> Result<String> {
+    serde_json::to_string_pretty(&ser::to_value(body)).map_err(Error::new)
+}
+
+/// Serialize a `hcl::Body` as HCL JSON into the IO stream.
+///
+/// See the documentation of [`to_string`] for more information.
+///
+/// # Errors
+///
+/// This function fails with an error if writing to the IO stream fails or if the body cannot be
+/// serialized to JSON.
+pub fn to_writer<W>(writer: W, body: &Body) -> Result<()>
+where
+    W: io::Write,
+{
+    serde_json::to_writer(writer, &ser::to_value(body)).map_err(Error::new)
+}
+
+// Returns the length of the interpolation or directive sequence at the start of `s`, including
+// the closing brace. Braces within quoted strings of the template expression are ignored.
+fn template_sequence_len(s: &str) -> usize {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, byte) in s.bytes().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return index + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    s.len()
+}
@@ -0,0 +1,287 @@
+use super::{template_sequence_len, BlockSchema, Schema};
+use crate::error::Location;
+use crate::expr::{Expression, Object, ObjectKey, TemplateExpr};
+use crate::structure::{Attribute, Block, BlockLabel, Body, Comments};
+use crate::template::Template;
+use crate::{Error, Identifier, Number, Result};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+pub fn parse(input: &str, schema: &Schema) -> Result<Body> {
+    let mut de = serde_json::Deserializer::from_str(input);
+
+    let value = JsonValue::deserialize(&mut de)
+        .and_then(|value| de.end().map(|()| value))
+        .map_err(|err| Error::Message {
+            msg: err.to_string(),
+            location: Some(Location {
+                line: err.line(),
+                col: err.column(),
+            }),
+        })?;
+
+    match value {
+        JsonValue::Object(props) => body(props, schema),
+        _ => Err(Error::new("expected JSON object as top-level value")),
+    }
+}
+
+// A JSON value which, unlike `serde_json::Value`, preserves the order of object properties as
+// well as duplicate properties. The latter are used to represent repeated blocks.
+enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl<'de> Deserialize<'de> for JsonValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct JsonValueVisitor;
+
+        impl<'de> Visitor<'de> for JsonValueVisitor {
+            type Value = JsonValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a JSON value")
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(JsonValue::Null)
+            }
+
+            fn visit_bool<E>(self, b: bool) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(JsonValue::Bool(b))
+            }
+
+            fn visit_i64<E>(self, n: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(JsonValue::Number(n.into()))
+            }
+
+            fn visit_u64<E>(self, n: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(JsonValue::Number(n.into()))
+            }
+
+            fn visit_f64<E>(self, n: f64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Number::from_f64(n).map_or(JsonValue::Null, JsonValue::Number))
+            }
+
+            fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(JsonValue::String(s.to_owned()))
+            }
+
+            fn visit_string<E>(self, s: String) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(JsonValue::String(s))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut vec = Vec::new();
+
+                while let Some(value) = seq.next_element()? {
+                    vec.push(value);
+                }
+
+                Ok(JsonValue::Array(vec))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut props = Vec::new();
+
+                while let Some(prop) = map.next_entry()? {
+                    props.push(prop);
+                }
+
+                Ok(JsonValue::Object(props))
+            }
+        }
+
+        deserializer.deserialize_any(JsonValueVisitor)
+    }
+}
+
+impl JsonValue {
+    fn kind(&self) -> &'static str {
+        match self {
+            JsonValue::Null => "null",
+            JsonValue::Bool(_) => "boolean",
+            JsonValue::Number(_) => "number",
+            JsonValue::String(_) => "string",
+            JsonValue::Array(_) => "array",
+            JsonValue::Object(_) => "object",
+        }
+    }
+}
+
+fn body(props: Vec<(String, JsonValue)>, schema: &Schema) -> Result<Body> {
+    let mut body = Body::builder();
+
+    for (name, value) in props {
+        if let Some(block_schema) = schema.block(&name) {
+            let ident = identifier(&name)?;
+            let mut blocks = Vec::new();
+            block(&ident, Vec::new(), value, block_schema, &mut blocks)?;
+            body = body.add_blocks(blocks);
+        } else if name != "//" {
+            body = body.add_attribute(Attribute::new(identifier(&name)?, expression(value)?));
+        }
+    }
+
+    Ok(body.build())
+}
+
+// Recursively consumes label levels of a block until the block body is reached. As repeated block
+// types and labels are represented as JSON arrays, a single JSON value may describe many blocks.
+fn block(
+    ident: &Identifier,
+    labels: Vec<BlockLabel>,
+    value: JsonValue,
+    schema: &BlockSchema,
+    blocks: &mut Vec<Block>,
+) -> Result<()> {
+    match value {
+        JsonValue::Array(values) => {
+            for value in values {
+                block(ident, labels.clone(), value, schema, blocks)?;
+            }
+        }
+        JsonValue::Object(props) if labels.len() < schema.labels => {
+            for (label, value) in props {
+                let mut labels = labels.clone();
+                labels.push(BlockLabel::String(label));
+                block(ident, labels, value, schema, blocks)?;
+            }
+        }
+        JsonValue::Object(props) => {
+            blocks.push(Block {
+                identifier: ident.clone(),
+                labels,
+                body: body(props, &schema.body)?,
+                span: None,
+                comments: Comments::default(),
+            });
+        }
+        other => {
+            return Err(Error::new(format!(
+                "expected object or array for block `{ident}`, got {}",
+                other.kind()
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+fn identifier(name: &str) -> Result<Identifier> {
+    Identifier::new(name)
+        .map_err(|_| Error::new(format!("`{name}` is not a valid attribute or block name")))
+}
+
+fn expression(value: JsonValue) -> Result<Expression> {
+    let expr = match value {
+        JsonValue::Null => Expression::Null,
+        JsonValue::Bool(b) => Expression::Bool(b),
+        JsonValue::Number(n) => Expression::Number(n),
+        JsonValue::String(s) => string(s)?,
+        JsonValue::Array(values) => {
+            Expression::Array(values.into_iter().map(expression).collect::<Result<_>>()?)
+        }
+        JsonValue::Object(props) => Expression::Object(
+            props
+                .into_iter()
+                .map(|(key, value)| Ok((object_key(key)?, expression(value)?)))
+                .collect::<Result<Object<_, _>>>()?,
+        ),
+    };
+
+    Ok(expr)
+}
+
+fn object_key(key: String) -> Result<ObjectKey> {
+    match Identifier::new(&key) {
+        Ok(ident) => Ok(ObjectKey::Identifier(ident)),
+        Err(_) => string(key).map(ObjectKey::Expression),
+    }
+}
+
+// Strings are interpreted as templates. Plain strings without template sequences stay strings.
+fn string(s: String) -> Result<Expression> {
+    if !s.contains("${") && !s.contains("%{") {
+        return Ok(Expression::String(s));
+    }
+
+    // Validate the template syntax early to surface errors during parsing.
+    Template::from_str(&s)?;
+
+    Ok(Expression::from(TemplateExpr::QuotedString(
+        quote_template(&s),
+    )))
+}
+
+// Quoted string templates hold the raw template as it would appear in between the quotes in native
+// HCL syntax. Literal parts of the template thus need to be escaped, while interpolation and
+// directive sequences are copied verbatim.
+fn quote_template(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(ch) = rest.chars().next() {
+        let len = if rest.starts_with("$${") || rest.starts_with("%%{") {
+            3
+        } else if rest.starts_with("${") || rest.starts_with("%{") {
+            template_sequence_len(rest)
+        } else {
+            match ch {
+                '"' => quoted.push_str("\\\""),
+                '\\' => quoted.push_str("\\\\"),
+                '\n' => quoted.push_str("\\n"),
+                '\r' => quoted.push_str("\\r"),
+                '\t' => quoted.push_str("\\t"),
+                ch if ch.is_control() => {
+                    let _ = write!(quoted, "\\u{:04x}", ch as u32);
+                }
+                ch => quoted.push(ch),
+            }
+
+            rest = &rest[ch.len_utf8()..];
+            continue;
+        };
+
+        quoted.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+
+    quoted
+}
@@ -0,0 +1,102 @@
+use super::template_sequence_len;
+use crate::expr::{Expression, HeredocStripMode, ObjectKey, RawExpression, TemplateExpr};
+use crate::structure::{IntoJsonSpec, JsonNode};
+use crate::util::{dedent, escape_template, try_unescape};
+use crate::{Body, Value};
+
+pub fn to_value(body: &Body) -> Value {
+    json_node(body.clone().into_json_node())
+}
+
+// Converts a node of the JSON representation of a body into a `Value`. Object keys of maps are
+// block types, block labels or attribute keys and are used as is.
+fn json_node(node: JsonNode) -> Value {
+    match node {
+        JsonNode::Map(map) => map
+            .into_iter()
+            .map(|(key, node)| (key, json_node(node)))
+            .collect(),
+        JsonNode::Body(mut bodies) => {
+            if bodies.len() == 1 {
+                json_node(bodies.remove(0).0.into_json_node())
+            } else {
+                bodies
+                    .into_iter()
+                    .map(|(body, _)| json_node(body.into_json_node()))
+                    .collect()
+            }
+        }
+        JsonNode::Expr(expr, _) => expression(expr),
+    }
+}
+
+fn expression(expr: Expression) -> Value {
+    match expr {
+        Expression::Null => Value::Null,
+        Expression::Bool(b) => Value::Bool(b),
+        Expression::Number(n) => Value::Number(n),
+        // Strings in HCL JSON are parsed as templates, so literal strings need to be escaped.
+        Expression::String(s) => Value::String(escape_template(&s)),
+        Expression::Array(array) => array.into_iter().map(expression).collect(),
+        Expression::Object(object) => object
+            .into_iter()
+            .map(|(key, expr)| (object_key(key), expression(expr)))
+            .collect(),
+        Expression::TemplateExpr(expr) => Value::String(template(*expr)),
+        Expression::Raw(raw) => Value::String(raw.into()),
+        other => Value::String(RawExpression::new(other.to_string()).into()),
+    }
+}
+
+fn object_key(key: ObjectKey) -> String {
+    let expr = match key {
+        ObjectKey::Identifier(ident) => return ident.into_inner(),
+        // Parenthesis around object key expressions are not needed in JSON.
+        ObjectKey::Expression(Expression::Parenthesis(expr)) => *expr,
+        ObjectKey::Expression(expr) => expr,
+    };
+
+    match expression(expr) {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+fn template(expr: TemplateExpr) -> String {
+    match expr {
+        TemplateExpr::QuotedString(s) => unquote_template(&s),
+        TemplateExpr::Heredoc(heredoc) => match heredoc.strip {
+            HeredocStripMode::None => heredoc.template,
+            HeredocStripMode::Indent => dedent(&heredoc.template).into_owned(),
+        },
+    }
+}
+
+// Quoted string templates may contain escape sequences in their literal parts which need to be
+// unescaped. Interpolation and directive sequences are copied verbatim.
+fn unquote_template(s: &str) -> String {
+    let mut unquoted = String::with_capacity(s.len());
+    let mut literal_start = 0;
+    let mut index = 0;
+
+    while index < s.len() {
+        let rest = &s[index..];
+
+        let len = if rest.starts_with("$${") || rest.starts_with("%%{") {
+            3
+        } else if rest.starts_with("${") || rest.starts_with("%{") {
+            template_sequence_len(rest)
+        } else {
+            index += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+
+        unquoted.push_str(&try_unescape(&s[literal_start..index]));
+        unquoted.push_str(&rest[..len]);
+        index += len;
+        literal_start = index;
+    }
+
+    unquoted.push_str(&try_unescape(&s[literal_start..]));
+    unquoted
+}
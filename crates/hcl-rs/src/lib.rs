@@ -32,12 +32,16 @@ pub mod eval;
 pub mod expr;
 pub mod format;
 mod ident;
+#[cfg(feature = "json")]
+pub mod json;
 mod parser;
 pub mod ser;
+mod span;
 pub mod structure;
 pub mod template;
 #[cfg(test)]
 mod tests;
+pub mod types;
 mod util;
 pub mod value;
 
@@ -63,7 +67,10 @@ pub use expr::{
 };
 
 pub use ident::Identifier;
-pub use parser::parse;
+pub use parser::{parse, parse_with_spans};
+
+#[doc(inline)]
+pub use span::Span;
 
 #[doc(inline)]
 pub use ser::{to_string, to_vec, to_writer};
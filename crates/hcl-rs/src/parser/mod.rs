@@ -1,24 +1,14 @@
-mod expr;
-mod structure;
-mod template;
 #[cfg(test)]
 mod tests;
 
-use self::{expr::expression, structure::body, template::template};
 use crate::{
-    expr::Expression, structure::Body, template::Template, util::unescape, Identifier, Number,
-    Result,
+    error::Location,
+    structure::{Attribute, Block, Body, Structure},
+    template::Template,
+    Result, Span,
 };
-use pest::{
-    iterators::{Pair, Pairs},
-    Parser as _,
-};
-use pest_derive::Parser;
-use std::str::FromStr;
-
-#[derive(Parser)]
-#[grammar = "parser/grammar/hcl.pest"]
-struct HclParser;
+use hcl_edit::{parser, structure as edit, Span as _};
+use std::ops::Range;
 
 /// Parse a `hcl::Body` from a `&str`.
 ///
@@ -62,40 +52,101 @@ struct HclParser;
 ///
 /// This function fails with an error if the `input` cannot be parsed as HCL.
 pub fn parse(input: &str) -> Result<Body> {
-    let pair = HclParser::parse(Rule::Hcl, input)?.next().unwrap();
-    body(pair)
+    let body = parser::parse_body_allow_redefined(input)?;
+    Ok(body.into())
 }
 
-pub fn parse_template(input: &str) -> Result<Template> {
-    let pair = HclParser::parse(Rule::HclTemplate, input)?.next().unwrap();
-    template(inner(pair))
+/// Parse a `hcl::Body` from a `&str` while keeping track of the source location of each
+/// [`Attribute`][crate::Attribute] and [`Block`][crate::Block].
+///
+/// The tracked [`Span`]s are available via [`Attribute::span`][crate::Attribute::span] and
+/// [`Block::span`][crate::Block::span]. They are also attached to errors that happen while
+/// evaluating or deserializing the parsed structures, so that these can point at the
+/// offending line and column in the input.
+///
+/// Apart from that, this function behaves exactly like [`hcl::parse`][parse]. Spans are not
+/// considered when comparing structures for equality.
+///
+/// # Example
+///
+/// ```
+/// use hcl::eval::{Context, Evaluate};
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let input = r#"
+/// service "api" {
+///   port = 8000 + offset
+/// }
+/// "#;
+///
+/// let body = hcl::parse_with_spans(input)?;
+/// let err = body.evaluate(&Context::new()).unwrap_err();
+///
+/// assert_eq!(format!("main.hcl:{}", err.span().unwrap()), "main.hcl:3:3");
+/// #   Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// This function fails with an error if the `input` cannot be parsed as HCL.
+pub fn parse_with_spans(input: &str) -> Result<Body> {
+    let body = parser::parse_body_allow_redefined(input)?;
+    Ok(body_with_spans(body, &LineIndex::new(input)))
 }
 
-fn string(pair: Pair<Rule>) -> String {
-    pair.as_str().to_owned()
+pub fn parse_template(input: &str) -> Result<Template> {
+    let template = parser::parse_template(input)?;
+    Ok(template.into())
 }
 
-fn unescape_string(pair: Pair<Rule>) -> Result<String> {
-    unescape(pair.as_str()).map(|c| c.to_string())
+// Converts an `hcl-edit` body and attaches the source spans of its attributes and blocks.
+fn body_with_spans(body: edit::Body, index: &LineIndex) -> Body {
+    body.into_iter()
+        .map(|structure| match structure {
+            edit::Structure::Attribute(attr) => {
+                let span = attr.span().map(|range| index.span(range));
+                let mut attr = Attribute::from(attr);
+                attr.span = span;
+                Structure::Attribute(attr)
+            }
+            edit::Structure::Block(mut block) => {
+                let span = block.span().map(|range| index.span(range));
+                let body = std::mem::take(&mut block.body);
+                let mut block = Block::from(block);
+                block.body = body_with_spans(body, index);
+                block.span = span;
+                Structure::Block(block)
+            }
+        })
+        .collect()
 }
 
-fn ident(pair: Pair<Rule>) -> Identifier {
-    Identifier::unchecked(pair.as_str())
+// Maps byte offsets in the input to line and column numbers.
+struct LineIndex<'a> {
+    input: &'a str,
+    line_starts: Vec<usize>,
 }
 
-fn from_str<T>(pair: Pair<Rule>) -> T
-where
-    T: FromStr,
-    <T as FromStr>::Err: std::fmt::Debug,
-{
-    pair.as_str().parse::<T>().unwrap()
-}
+impl<'a> LineIndex<'a> {
+    fn new(input: &'a str) -> LineIndex<'a> {
+        let line_starts = std::iter::once(0)
+            .chain(input.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
 
-fn inner(pair: Pair<Rule>) -> Pair<Rule> {
-    pair.into_inner().next().unwrap()
-}
+        LineIndex { input, line_starts }
+    }
+
+    fn location(&self, offset: usize) -> Location {
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let line_start = self.line_starts[line - 1];
+        let col = self.input[line_start..offset].chars().count() + 1;
+        Location { line, col }
+    }
 
-#[track_caller]
-fn unexpected_rule(rule: Rule) -> ! {
-    panic!("unexpected rule: {rule:?}")
+    fn span(&self, range: Range<usize>) -> Span {
+        let (start, end) = (self.location(range.start), self.location(range.end));
+        Span::new(range, start, end)
+    }
 }
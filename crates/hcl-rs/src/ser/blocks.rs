@@ -6,6 +6,12 @@ use vecmap::VecMap;
 
 pub(crate) const BLOCK_MARKER: &str = "$hcl::Block";
 
+// Returns `true` if the block markers should be emitted. Besides this crate's serializer,
+// `hcl_edit::ser::to_body` recognizes the same markers.
+fn emit_block_marker() -> bool {
+    in_internal_serialization() || hcl_edit::ser::in_internal_serialization()
+}
+
 /// A transparent wrapper type which hints the [`Serializer`][crate::ser::Serializer] to serialize
 /// `T` as an HCL block.
 ///
@@ -135,7 +141,7 @@ where
     where
         S: serde::Serializer,
     {
-        if in_internal_serialization() {
+        if emit_block_marker() {
             serializer.serialize_newtype_struct(BLOCK_MARKER, &self.0)
         } else {
             self.0.serialize(serializer)
@@ -284,7 +290,7 @@ where
     where
         S: serde::Serializer,
     {
-        if in_internal_serialization() {
+        if emit_block_marker() {
             serializer.serialize_newtype_struct(LABELED_BLOCK_MARKER, &self.0)
         } else {
             self.0.serialize(serializer)
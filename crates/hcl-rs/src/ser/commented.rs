@@ -0,0 +1,236 @@
+use super::in_internal_serialization;
+use crate::structure::{Comment, Comments, Structure};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fmt;
+use std::ops;
+
+pub(crate) const COMMENTED_MARKER: &str = "$hcl::Commented";
+
+thread_local! {
+    // The comments of the `Commented<T>` that is currently being serialized. They are passed
+    // alongside the value because serde has no way to attach extra data to a newtype struct.
+    static PENDING_COMMENTS: RefCell<Comments> = RefCell::new(Comments::default());
+}
+
+/// A transparent wrapper type which hints the [`Serializer`][crate::ser::Serializer] to attach
+/// comments to the HCL attribute or block that `T` is serialized as.
+///
+/// When passed to a serializer other than the one from this crate, a `Commented<T>` serializes
+/// exactly like `T`, if `T` implements `serde::Serialize`.
+///
+/// Like [`Block<T>`][crate::ser::Block], a `Commented<T>` can only be used in the *value
+/// position of a map-like structure* or as the top-level value. If `T` serializes to multiple
+/// structures (e.g. a `Block<Vec<T>>`), the leading comments are attached to the first and the
+/// trailing comment is attached to the last of them. A `Commented<T>` that ends up in an
+/// expression, for example as an array element, serializes like `T` and its comments are
+/// dropped.
+///
+/// The comments are rendered using the syntax of their [`CommentStyle`], see the documentation
+/// of [`Comment`] for details.
+///
+/// [`CommentStyle`]: crate::structure::CommentStyle
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hcl::ser::{Block, Commented};
+/// use hcl::structure::{Comment, CommentStyle};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Config {
+///     service: Commented<Block<Service>>,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Service {
+///     name: String,
+///     port: Commented<u16>,
+/// }
+///
+/// let config = Commented::new(Config {
+///     service: Commented::new(Block::new(Service {
+///         name: "api".into(),
+///         port: Commented::new(8080)
+///             .with_leading_comment("The port to listen on.")
+///             .with_trailing_comment(
+///                 Comment::new("required").with_style(CommentStyle::SlashStar),
+///             ),
+///     }))
+///     .with_leading_comment(
+///         Comment::new("Service configuration").with_style(CommentStyle::DoubleSlash),
+///     ),
+/// })
+/// .with_leading_comment("This file is generated, do not edit.");
+///
+/// let expected = r#"
+/// ## This file is generated, do not edit.
+/// // Service configuration
+/// service {
+///   name = "api"
+///   ## The port to listen on.
+///   port = 8080 /* required */
+/// }
+/// "#.trim_start();
+///
+/// assert_eq!(hcl::to_string(&config)?, expected);
+/// #    Ok(())
+/// # }
+/// ```
+pub struct Commented<T> {
+    value: T,
+    comments: Comments,
+}
+
+impl<T> Commented<T> {
+    /// Create a new `Commented<T>` from a `T` without any comments.
+    pub fn new(value: T) -> Commented<T> {
+        Commented {
+            value,
+            comments: Comments::default(),
+        }
+    }
+
+    /// Adds a comment which is rendered on its own line before the serialized value.
+    ///
+    /// Consumes `self` and returns the `Commented<T>` with the comment added.
+    pub fn with_leading_comment<C>(mut self, comment: C) -> Commented<T>
+    where
+        C: Into<Comment>,
+    {
+        self.comments.leading.push(comment.into());
+        self
+    }
+
+    /// Sets the comment which is rendered at the end of the last line of the serialized value.
+    ///
+    /// Consumes `self` and returns the `Commented<T>` with the comment set.
+    pub fn with_trailing_comment<C>(mut self, comment: C) -> Commented<T>
+    where
+        C: Into<Comment>,
+    {
+        self.comments.trailing = Some(comment.into());
+        self
+    }
+
+    /// Returns the comments which are rendered before the serialized value.
+    pub fn leading_comments(&self) -> &[Comment] {
+        &self.comments.leading
+    }
+
+    /// Returns the comment which is rendered after the serialized value, if any.
+    pub fn trailing_comment(&self) -> Option<&Comment> {
+        self.comments.trailing.as_ref()
+    }
+
+    /// Consume the `Commented` and return the wrapped `T`.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> ops::Deref for Commented<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> ops::DerefMut for Commented<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T> Clone for Commented<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Commented {
+            value: self.value.clone(),
+            comments: self.comments.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Commented<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Commented")
+            .field("value", &self.value)
+            .field("leading_comments", &self.comments.leading)
+            .field("trailing_comment", &self.comments.trailing)
+            .finish()
+    }
+}
+
+impl<T> Serialize for Commented<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if in_internal_serialization() {
+            PENDING_COMMENTS.with(|pending| *pending.borrow_mut() = self.comments.clone());
+            serializer.serialize_newtype_struct(COMMENTED_MARKER, &self.value)
+        } else {
+            self.value.serialize(serializer)
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Commented<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Commented::new)
+    }
+}
+
+/// Takes the comments of the `Commented<T>` whose value is about to be serialized.
+///
+/// Must be called right after receiving a newtype struct with the name `COMMENTED_MARKER` and
+/// before serializing its value, since the value may contain `Commented<T>`s itself.
+pub(crate) fn take_pending_comments() -> Comments {
+    PENDING_COMMENTS.with(RefCell::take)
+}
+
+/// Attaches the leading comments to the first and the trailing comment to the last structure.
+pub(crate) fn attach_comments<I>(structures: I, comments: Comments) -> Vec<Structure>
+where
+    I: IntoIterator<Item = Structure>,
+{
+    let mut structures: Vec<Structure> = structures.into_iter().collect();
+
+    if let Some(first) = structures.first_mut() {
+        let leading = match first {
+            Structure::Attribute(attr) => &mut attr.comments.leading,
+            Structure::Block(block) => &mut block.comments.leading,
+        };
+
+        leading.splice(0..0, comments.leading);
+    }
+
+    if let (Some(last), Some(comment)) = (structures.last_mut(), comments.trailing) {
+        let trailing = match last {
+            Structure::Attribute(attr) => &mut attr.comments.trailing,
+            Structure::Block(block) => &mut block.comments.trailing,
+        };
+
+        *trailing = Some(comment);
+    }
+
+    structures
+}
@@ -9,6 +9,9 @@
 //! HCL block structures from custom types. See the type and function level documentation for
 //! usage examples.
 //!
+//! The [`Commented`] wrapper type can be used to attach comments to the serialized attributes and
+//! blocks.
+//!
 //! If you want to serialize the data structures provided by this crate (e.g.
 //! [`Body`](crate::Body)) consider using the functionality in the [`format`](crate::format) module
 //! instead because it is more efficient.
@@ -264,8 +267,10 @@
 //! ```
 
 pub(crate) mod blocks;
+pub(crate) mod commented;
 
 pub use self::blocks::{block, doubly_labeled_block, labeled_block, Block, LabeledBlock};
+pub use self::commented::Commented;
 use crate::format::{Format, Formatter};
 use crate::structure::Body;
 use crate::{Error, Identifier, Result};
@@ -0,0 +1,76 @@
+//! Source location tracking for parsed HCL structures.
+
+use crate::error::Location;
+use std::fmt;
+use std::ops::Range;
+
+/// The location of a parsed HCL structure in the input it was parsed from.
+///
+/// Spans are only recorded if the input was parsed via [`hcl::parse_with_spans`][parse] or one of
+/// the deserialization functions like [`hcl::from_str`][from_str]. Structures that were parsed via
+/// [`hcl::parse`][crate::parse] or were constructed programmatically do not have a span.
+///
+/// Spans are tracked for attributes and blocks only, expressions do not carry a span. Errors
+/// which occur while evaluating or deserializing an expression point at the attribute containing
+/// it instead.
+///
+/// The `Display` implementation renders the span's start location as `line:col`, which makes it
+/// easy to prefix it with a file name.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let input = r#"
+/// resource "aws_s3_bucket" "bucket" {
+///   name = "the-bucket"
+/// }
+/// "#;
+///
+/// let body = hcl::parse_with_spans(input)?;
+/// let block = body.blocks().next().unwrap();
+/// let attr = block.body().attributes().next().unwrap();
+///
+/// let span = attr.span().unwrap();
+///
+/// assert_eq!(&input[span.range()], r#"name = "the-bucket""#);
+/// assert_eq!(format!("main.hcl:{span}"), "main.hcl:3:3");
+/// #   Ok(())
+/// # }
+/// ```
+///
+/// [parse]: crate::parse_with_spans
+/// [from_str]: crate::from_str
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    range: Range<usize>,
+    start: Location,
+    end: Location,
+}
+
+impl Span {
+    pub(crate) fn new(range: Range<usize>, start: Location, end: Location) -> Span {
+        Span { range, start, end }
+    }
+
+    /// Returns the byte range of the span in the input.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the location of the start of the span.
+    pub fn start(&self) -> &Location {
+        &self.start
+    }
+
+    /// Returns the location of the end of the span.
+    pub fn end(&self) -> &Location {
+        &self.end
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.start.line, self.start.col)
+    }
+}
@@ -1,7 +1,8 @@
 //! Types to represent and build HCL attributes.
 
+use super::comment::{Comment, Comments};
 use crate::expr::Expression;
-use crate::{Identifier, Value};
+use crate::{Identifier, Span, Value};
 use serde::Deserialize;
 use std::iter;
 
@@ -15,12 +16,23 @@ use std::iter;
 ///
 /// Use [`Attribute::new`] to construct an [`Attribute`] from a value that is convertible to this
 /// crate's [`Expression`] type.
-#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+///
+/// **Note**: an `Attribute` is marked as non-exhaustive since it carries private fields which
+/// track its source location and its comments, and may carry more in the future. It can
+/// therefore not be constructed via a struct literal, use [`Attribute::new`] instead. The public
+/// fields can still be read and modified directly.
+#[derive(Deserialize, Debug, Eq, Clone)]
+#[non_exhaustive]
 pub struct Attribute {
     /// The HCL attribute's key.
     pub key: Identifier,
     /// The value expression of the HCL attribute.
     pub expr: Expression,
+
+    #[serde(skip)]
+    pub(crate) span: Option<Span>,
+    #[serde(skip)]
+    pub(crate) comments: Comments,
 }
 
 impl Attribute {
@@ -34,6 +46,8 @@ impl Attribute {
         Attribute {
             key: key.into(),
             expr: expr.into(),
+            span: None,
+            comments: Comments::default(),
         }
     }
 
@@ -46,6 +60,52 @@ impl Attribute {
     pub fn expr(&self) -> &Expression {
         &self.expr
     }
+
+    /// Returns the attribute's location in the parsed input, if it was tracked.
+    ///
+    /// See the documentation of [`Span`] for when spans are available.
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
+    /// Adds a comment which is rendered on its own line before the attribute.
+    ///
+    /// Consumes `self` and returns the `Attribute` with the comment added.
+    pub fn with_leading_comment<C>(mut self, comment: C) -> Attribute
+    where
+        C: Into<Comment>,
+    {
+        self.comments.leading.push(comment.into());
+        self
+    }
+
+    /// Sets the comment which is rendered after the attribute value on the same line.
+    ///
+    /// Consumes `self` and returns the `Attribute` with the comment set.
+    pub fn with_trailing_comment<C>(mut self, comment: C) -> Attribute
+    where
+        C: Into<Comment>,
+    {
+        self.comments.trailing = Some(comment.into());
+        self
+    }
+
+    /// Returns the comments which are rendered before the attribute.
+    pub fn leading_comments(&self) -> &[Comment] {
+        &self.comments.leading
+    }
+
+    /// Returns the comment which is rendered after the attribute value, if any.
+    pub fn trailing_comment(&self) -> Option<&Comment> {
+        self.comments.trailing.as_ref()
+    }
+}
+
+impl PartialEq for Attribute {
+    fn eq(&self, other: &Self) -> bool {
+        // The span and comments are not considered for equality.
+        self.key == other.key && self.expr == other.expr
+    }
 }
 
 impl From<Attribute> for Value {
@@ -1,7 +1,8 @@
 //! Types to represent and build HCL blocks.
 
+use super::comment::{Comment, Comments};
 use super::{Attribute, Body, BodyBuilder, Structure};
-use crate::Identifier;
+use crate::{Identifier, Span};
 use serde::{Deserialize, Serialize};
 
 /// Represents an HCL block which consists of a block identifier, zero or more block labels and a
@@ -14,7 +15,13 @@ use serde::{Deserialize, Serialize};
 ///   body
 /// }
 /// ```
-#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+///
+/// **Note**: a `Block` is marked as non-exhaustive since it carries private fields which track its
+/// source location and its comments, and may carry more in the future. It can therefore not be
+/// constructed via a struct literal, use [`Block::new`] or [`Block::builder`] instead. The public
+/// fields can still be read and modified directly.
+#[derive(Deserialize, Debug, Eq, Clone)]
+#[non_exhaustive]
 pub struct Block {
     /// The block identifier.
     pub identifier: Identifier,
@@ -22,6 +29,11 @@ pub struct Block {
     pub labels: Vec<BlockLabel>,
     /// Represents the `Block`'s body.
     pub body: Body,
+
+    #[serde(skip)]
+    pub(crate) span: Option<Span>,
+    #[serde(skip)]
+    pub(crate) comments: Comments,
 }
 
 impl Block {
@@ -34,6 +46,8 @@ impl Block {
             identifier: ident.into(),
             labels: Vec::new(),
             body: Body::default(),
+            span: None,
+            comments: Comments::default(),
         }
     }
 
@@ -60,6 +74,54 @@ impl Block {
     pub fn body(&self) -> &Body {
         &self.body
     }
+
+    /// Returns the block's location in the parsed input, if it was tracked.
+    ///
+    /// See the documentation of [`Span`] for when spans are available.
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
+    /// Adds a comment which is rendered on its own line before the block.
+    ///
+    /// Consumes `self` and returns the `Block` with the comment added.
+    pub fn with_leading_comment<C>(mut self, comment: C) -> Block
+    where
+        C: Into<Comment>,
+    {
+        self.comments.leading.push(comment.into());
+        self
+    }
+
+    /// Sets the comment which is rendered after the closing brace of the block on the same line.
+    ///
+    /// Consumes `self` and returns the `Block` with the comment set.
+    pub fn with_trailing_comment<C>(mut self, comment: C) -> Block
+    where
+        C: Into<Comment>,
+    {
+        self.comments.trailing = Some(comment.into());
+        self
+    }
+
+    /// Returns the comments which are rendered before the block.
+    pub fn leading_comments(&self) -> &[Comment] {
+        &self.comments.leading
+    }
+
+    /// Returns the comment which is rendered after the closing brace of the block, if any.
+    pub fn trailing_comment(&self) -> Option<&Comment> {
+        self.comments.trailing.as_ref()
+    }
+}
+
+impl PartialEq for Block {
+    fn eq(&self, other: &Self) -> bool {
+        // The span and comments are not considered for equality.
+        self.identifier == other.identifier
+            && self.labels == other.labels
+            && self.body == other.body
+    }
 }
 
 impl<I, B> From<(I, B)> for Block
@@ -73,6 +135,8 @@ where
             identifier: ident.into(),
             labels: Vec::new(),
             body: body.into_iter().collect(),
+            span: None,
+            comments: Comments::default(),
         }
     }
 }
@@ -90,6 +154,8 @@ where
             identifier: ident.into(),
             labels: labels.into_iter().map(Into::into).collect(),
             body: body.into_iter().collect(),
+            span: None,
+            comments: Comments::default(),
         }
     }
 }
@@ -296,6 +362,8 @@ impl BlockBuilder {
             identifier: self.identifier,
             labels: self.labels,
             body: self.body.build(),
+            span: None,
+            comments: Comments::default(),
         }
     }
 }
@@ -0,0 +1,92 @@
+//! Types to represent comments attached to HCL attributes and blocks.
+
+/// The syntax that is used to render a [`Comment`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommentStyle {
+    /// A line comment starting with `#`. This is the default.
+    #[default]
+    Hash,
+    /// A line comment starting with `//`.
+    DoubleSlash,
+    /// An inline comment enclosed in `/*` and `*/`.
+    SlashStar,
+}
+
+/// Represents a comment that is attached to an [`Attribute`][crate::Attribute] or a
+/// [`Block`][crate::Block].
+///
+/// Comments are rendered by the formatter, they are not produced by the parser. A line comment
+/// whose text spans multiple lines is rendered as one comment per line.
+///
+/// # Example
+///
+/// ```
+/// use hcl::structure::{Attribute, Comment, CommentStyle};
+///
+/// let attr = Attribute::new("port", 8080)
+///     .with_leading_comment("The port to listen on.")
+///     .with_trailing_comment(Comment::new("required").with_style(CommentStyle::SlashStar));
+///
+/// let expected = r#"
+/// ## The port to listen on.
+/// port = 8080 /* required */
+/// "#.trim_start();
+///
+/// assert_eq!(hcl::format::to_string(&attr).unwrap(), expected);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    text: String,
+    style: CommentStyle,
+}
+
+impl Comment {
+    /// Creates a new `Comment` with the given text which is rendered using
+    /// [`CommentStyle::Hash`].
+    ///
+    /// The text must not include the comment markers, they are added by the formatter.
+    pub fn new<T>(text: T) -> Comment
+    where
+        T: Into<String>,
+    {
+        Comment {
+            text: text.into(),
+            style: CommentStyle::default(),
+        }
+    }
+
+    /// Sets the syntax that is used to render the comment.
+    pub fn with_style(mut self, style: CommentStyle) -> Comment {
+        self.style = style;
+        self
+    }
+
+    /// Returns a reference to the comment text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the syntax that is used to render the comment.
+    pub fn style(&self) -> CommentStyle {
+        self.style
+    }
+}
+
+impl From<&str> for Comment {
+    fn from(text: &str) -> Self {
+        Comment::new(text)
+    }
+}
+
+impl From<String> for Comment {
+    fn from(text: String) -> Self {
+        Comment::new(text)
+    }
+}
+
+// The comments attached to an attribute or block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Comments {
+    pub(crate) leading: Vec<Comment>,
+    pub(crate) trailing: Option<Comment>,
+}
@@ -0,0 +1,102 @@
+// Conversions between the structure types of this crate and their `hcl-edit` counterparts.
+//
+// Source spans are not carried over in either direction: `hcl-edit` tracks byte ranges only, while
+// `Span` also needs line and column information which requires the original input.
+
+use super::{Attribute, Block, BlockLabel, Body, Comments, Structure};
+use crate::{Error, Expression, Result};
+use hcl_edit::expr::Expression as EditExpression;
+use hcl_edit::structure as edit;
+use hcl_edit::Decorated;
+
+impl From<edit::Body> for Body {
+    fn from(body: edit::Body) -> Self {
+        body.into_iter().map(Structure::from).collect()
+    }
+}
+
+impl TryFrom<Body> for edit::Body {
+    type Error = Error;
+
+    fn try_from(body: Body) -> Result<Self> {
+        body.into_iter().map(edit::Structure::try_from).collect()
+    }
+}
+
+impl From<edit::Structure> for Structure {
+    fn from(structure: edit::Structure) -> Self {
+        match structure {
+            edit::Structure::Attribute(attr) => Structure::Attribute(attr.into()),
+            edit::Structure::Block(block) => Structure::Block(block.into()),
+        }
+    }
+}
+
+impl TryFrom<Structure> for edit::Structure {
+    type Error = Error;
+
+    fn try_from(structure: Structure) -> Result<Self> {
+        match structure {
+            Structure::Attribute(attr) => edit::Attribute::try_from(attr).map(Into::into),
+            Structure::Block(block) => edit::Block::try_from(block).map(Into::into),
+        }
+    }
+}
+
+impl From<edit::Attribute> for Attribute {
+    fn from(attr: edit::Attribute) -> Self {
+        Attribute::new(attr.key.into_value(), Expression::from(attr.value))
+    }
+}
+
+impl TryFrom<Attribute> for edit::Attribute {
+    type Error = Error;
+
+    fn try_from(attr: Attribute) -> Result<Self> {
+        Ok(edit::Attribute::new(
+            attr.key,
+            EditExpression::try_from(attr.expr)?,
+        ))
+    }
+}
+
+impl From<edit::Block> for Block {
+    fn from(block: edit::Block) -> Self {
+        Block {
+            identifier: block.ident.into_value().into(),
+            labels: block.labels.into_iter().map(block_label).collect(),
+            body: block.body.into(),
+            span: None,
+            comments: Comments::default(),
+        }
+    }
+}
+
+impl TryFrom<Block> for edit::Block {
+    type Error = Error;
+
+    fn try_from(block: Block) -> Result<Self> {
+        let mut edit_block = edit::Block::new(block.identifier);
+        edit_block.labels = block.labels.into_iter().map(Into::into).collect();
+        edit_block.body = block.body.try_into()?;
+        Ok(edit_block)
+    }
+}
+
+// This cannot be a `From` impl because it would conflict with `From<T> for BlockLabel where T:
+// Into<String>`.
+fn block_label(label: edit::BlockLabel) -> BlockLabel {
+    match label {
+        edit::BlockLabel::Ident(ident) => BlockLabel::Identifier(ident.into_value().into()),
+        edit::BlockLabel::String(string) => BlockLabel::String(string.into_value()),
+    }
+}
+
+impl From<BlockLabel> for edit::BlockLabel {
+    fn from(label: BlockLabel) -> Self {
+        match label {
+            BlockLabel::Identifier(ident) => edit::BlockLabel::Ident(ident.into()),
+            BlockLabel::String(string) => edit::BlockLabel::String(Decorated::new(string)),
+        }
+    }
+}
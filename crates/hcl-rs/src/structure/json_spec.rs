@@ -1,6 +1,10 @@
-use super::{Block, Body, Structure};
-use crate::{Expression, Identifier, Map, Value};
+use super::{Block, Body, Comments, Structure};
+use crate::de::spanned::{set_pending_span, SPANNED_MARKER};
+use crate::error::PathSegment;
+use crate::{Error, Expression, Identifier, Map, Result, Span, Value};
 use indexmap::map::Entry;
+use serde::de::value::StrDeserializer;
+use serde::de::{self, IntoDeserializer};
 
 /// A trait to convert an HCL structure into its [JSON representation][json-spec].
 ///
@@ -22,6 +26,11 @@ pub(crate) trait IntoJsonSpec: Sized {
     /// labels need to be merged so that the `Expression` resulting from `into_json_spec` conforms
     /// to the HCL JSON specification.
     fn into_json_nodes(self) -> Map<String, JsonNode>;
+
+    /// Converts the value into a single node which can be deserialized directly.
+    fn into_json_node(self) -> JsonNode {
+        JsonNode::Map(self.into_json_nodes())
+    }
 }
 
 impl IntoJsonSpec for Body {
@@ -29,7 +38,7 @@ impl IntoJsonSpec for Body {
         self.into_iter().fold(Map::new(), |mut map, structure| {
             match structure {
                 Structure::Attribute(attr) => {
-                    map.insert(attr.key.into_inner(), JsonNode::Expr(attr.expr));
+                    map.insert(attr.key.into_inner(), JsonNode::Expr(attr.expr, attr.span));
                 }
                 Structure::Block(block) => {
                     for (key, node) in block.into_json_nodes() {
@@ -53,11 +62,13 @@ impl IntoJsonSpec for Block {
                     identifier: Identifier::unchecked(label.into_inner()),
                     labels: labels.collect(),
                     body: self.body,
+                    span: self.span,
+                    comments: Comments::default(),
                 };
 
                 JsonNode::Map(block.into_json_nodes())
             }
-            None => JsonNode::Body(vec![self.body]),
+            None => JsonNode::Body(vec![(self.body, self.span)]),
         };
 
         std::iter::once((self.identifier.into_inner(), node)).collect()
@@ -66,8 +77,8 @@ impl IntoJsonSpec for Block {
 
 pub(crate) enum JsonNode {
     Map(Map<String, JsonNode>),
-    Body(Vec<Body>),
-    Expr(Expression),
+    Body(Vec<(Body, Option<Span>)>),
+    Expr(Expression, Option<Span>),
 }
 
 impl From<JsonNode> for Expression {
@@ -83,12 +94,12 @@ impl From<JsonNode> for Expression {
                 //
                 // [json-spec]: https://github.com/hashicorp/hcl/blob/main/json/spec.md#blocks
                 if vec.len() == 1 {
-                    vec.remove(0).into()
+                    vec.remove(0).0.into()
                 } else {
-                    vec.into()
+                    vec.into_iter().map(|(body, _)| body).collect()
                 }
             }
-            JsonNode::Expr(expr) => expr,
+            JsonNode::Expr(expr, _) => expr,
         }
     }
 }
@@ -135,3 +146,268 @@ impl JsonNode {
         }
     }
 }
+
+// Deserializing a `JsonNode` directly instead of converting it into an `Expression` first keeps
+// the spans of attributes around, which allows attaching locations to deserialization errors.
+impl IntoDeserializer<'_, Error> for JsonNode {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl JsonNode {
+    // Unwraps block bodies which only consist of a single body into a map.
+    fn normalize(self) -> JsonNode {
+        match self {
+            JsonNode::Body(mut vec) if vec.len() == 1 => {
+                JsonNode::Map(vec.remove(0).0.into_json_nodes())
+            }
+            node => node,
+        }
+    }
+
+    // Returns the span of the attribute or block the node was created from. Nodes of merged
+    // blocks and block labels do not have a span.
+    fn span(&self) -> Option<&Span> {
+        match self {
+            JsonNode::Expr(_, span) => span.as_ref(),
+            JsonNode::Body(vec) if vec.len() == 1 => vec[0].1.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+// Deserializer methods that do not need special treatment of maps and bodies. These just delegate
+// to the `Expression` deserializer while attaching the attribute location to errors.
+macro_rules! forward_to_expression {
+    ($($method:ident$(($($arg:ident: $ty:ty),*))?)*) => {
+        $(
+            fn $method<V>(self, $($($arg: $ty,)*)? visitor: V) -> Result<V::Value>
+            where
+                V: de::Visitor<'de>,
+            {
+                match self {
+                    JsonNode::Expr(expr, span) => expr
+                        .$method($($($arg,)*)? visitor)
+                        .map_err(|err| err.with_location(span.as_ref().map(Span::start))),
+                    node => Expression::from(node).$method($($($arg,)*)? visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for JsonNode {
+    type Error = Error;
+
+    forward_to_expression! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_unit
+        deserialize_unit_struct(name: &'static str)
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+        deserialize_identifier
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.normalize() {
+            JsonNode::Map(map) => visitor.visit_map(MapAccess::new(map)),
+            JsonNode::Body(vec) => visitor.visit_seq(SeqAccess::new(vec)),
+            JsonNode::Expr(expr, span) => expr
+                .deserialize_any(visitor)
+                .map_err(|err| err.with_location(span.as_ref().map(Span::start))),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            JsonNode::Expr(expr, span) => expr
+                .deserialize_option(visitor)
+                .map_err(|err| err.with_location(span.as_ref().map(Span::start))),
+            node => visitor.visit_some(node),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if name == SPANNED_MARKER {
+            set_pending_span(self.span().cloned());
+        }
+
+        match self {
+            JsonNode::Expr(expr, span) => expr
+                .deserialize_newtype_struct(name, visitor)
+                .map_err(|err| err.with_location(span.as_ref().map(Span::start))),
+            node => visitor.visit_newtype_struct(node),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.normalize() {
+            node @ JsonNode::Body(_) => node.deserialize_any(visitor),
+            JsonNode::Expr(expr, span) => expr
+                .deserialize_seq(visitor)
+                .map_err(|err| err.with_location(span.as_ref().map(Span::start))),
+            node => Expression::from(node).deserialize_seq(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.normalize() {
+            node @ JsonNode::Map(_) => node.deserialize_any(visitor),
+            JsonNode::Expr(expr, span) => expr
+                .deserialize_map(visitor)
+                .map_err(|err| err.with_location(span.as_ref().map(Span::start))),
+            node => Expression::from(node).deserialize_map(visitor),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.normalize() {
+            JsonNode::Expr(expr, span) => expr
+                .deserialize_struct(name, fields, visitor)
+                .map_err(|err| err.with_location(span.as_ref().map(Span::start))),
+            node => node.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        drop(self);
+        visitor.visit_unit()
+    }
+}
+
+// Records the path to the node that caused an error while deserializing a map of nodes.
+struct MapAccess {
+    iter: indexmap::map::IntoIter<String, JsonNode>,
+    value: Option<(String, JsonNode)>,
+}
+
+impl MapAccess {
+    fn new(map: Map<String, JsonNode>) -> MapAccess {
+        MapAccess {
+            iter: map.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, node)) => {
+                let value = seed.deserialize(StrDeserializer::<Error>::new(&key))?;
+                self.value = Some((key, node));
+                Ok(Some(value))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let (key, node) = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+
+        // The keys of nested maps are block labels.
+        let labeled = matches!(node, JsonNode::Map(_));
+
+        seed.deserialize(node)
+            .map_err(|err| err.with_path_segment(PathSegment::Key(key), labeled))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+// Records the index of the block body that caused an error while deserializing a sequence of
+// block bodies.
+struct SeqAccess {
+    iter: std::iter::Enumerate<std::vec::IntoIter<(Body, Option<Span>)>>,
+}
+
+impl SeqAccess {
+    fn new(vec: Vec<(Body, Option<Span>)>) -> SeqAccess {
+        SeqAccess {
+            iter: vec.into_iter().enumerate(),
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for SeqAccess {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((index, body)) => seed
+                .deserialize(JsonNode::Body(vec![body]))
+                .map(Some)
+                .map_err(|err| err.with_path_segment(PathSegment::Index(index), false)),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
@@ -7,6 +7,7 @@
 //! - [`BlockBuilder`]: provides functionality for building `Block`s
 //! - [`Body`]: represent the body of an HCL configuration or block
 //! - [`BodyBuilder`]: provides functionality for building `Body`s
+//! - [`Comment`]: represent a comment attached to an `Attribute` or `Block`
 //!
 //! ## Examples
 //!
@@ -48,18 +49,24 @@
 mod attribute;
 mod block;
 mod body;
+mod comment;
 pub(crate) mod de;
+mod edit;
 pub mod iter;
 mod json_spec;
 mod ser;
 #[cfg(test)]
 mod tests;
 
+pub(crate) use self::comment::Comments;
 pub(crate) use self::json_spec::IntoJsonSpec;
+#[cfg(feature = "json")]
+pub(crate) use self::json_spec::JsonNode;
 pub use self::{
     attribute::Attribute,
     block::{Block, BlockBuilder, BlockLabel},
     body::{Body, BodyBuilder},
+    comment::{Comment, CommentStyle},
 };
 use crate::Value;
 use serde::Deserialize;
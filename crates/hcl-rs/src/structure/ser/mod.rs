@@ -10,6 +10,7 @@ use crate::expr::ser::{
 };
 use crate::ser::{
     blocks::{BLOCK_MARKER, LABELED_BLOCK_MARKER},
+    commented::{attach_comments, take_pending_comments, COMMENTED_MARKER},
     in_internal_serialization, IdentifierSerializer, InternalHandles,
     SerializeInternalHandleStruct, StringSerializer,
 };
@@ -131,9 +132,22 @@ impl ser::Serializer for BodySerializer {
         bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64
         char str bytes none unit unit_struct unit_variant
     }
-    serialize_self! { some newtype_struct }
+    serialize_self! { some }
     forward_to_serialize_seq! { tuple tuple_struct }
 
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Self::Ok>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        if name == COMMENTED_MARKER {
+            let comments = take_pending_comments();
+            let body = value.serialize(self)?;
+            Ok(Body(attach_comments(body, comments)))
+        } else {
+            value.serialize(self)
+        }
+    }
+
     fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
@@ -517,7 +531,11 @@ impl ser::Serializer for StructureSerializer {
     where
         T: ?Sized + ser::Serialize,
     {
-        if name == BLOCK_MARKER {
+        if name == COMMENTED_MARKER {
+            let comments = take_pending_comments();
+            let structures = value.serialize(self)?;
+            Ok(attach_comments(structures, comments).into())
+        } else if name == BLOCK_MARKER {
             BlockSerializer::new(self.ident).serialize_newtype_struct(name, value)
         } else if name == LABELED_BLOCK_MARKER {
             LabeledBlockSerializer::new(self.ident).serialize_newtype_struct(name, value)
@@ -802,7 +820,11 @@ impl ser::Serializer for BlockSerializer {
     where
         T: ?Sized + ser::Serialize,
     {
-        if name == LABELED_BLOCK_MARKER {
+        if name == COMMENTED_MARKER {
+            let comments = take_pending_comments();
+            let structures = value.serialize(self)?;
+            Ok(attach_comments(structures, comments).into())
+        } else if name == LABELED_BLOCK_MARKER {
             LabeledBlockSerializer::new(self.ident).serialize_newtype_struct(name, value)
         } else {
             value.serialize(self)
@@ -1099,9 +1121,22 @@ impl ser::Serializer for LabeledBlockSerializer {
         bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64
         char str bytes none unit unit_struct unit_variant
     }
-    serialize_self! { some newtype_struct }
+    serialize_self! { some }
     forward_to_serialize_seq! { tuple tuple_struct }
 
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Self::Ok>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        if name == COMMENTED_MARKER {
+            let comments = take_pending_comments();
+            let structures = value.serialize(self)?;
+            Ok(attach_comments(structures, comments).into())
+        } else {
+            value.serialize(self)
+        }
+    }
+
     fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
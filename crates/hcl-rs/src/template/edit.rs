@@ -0,0 +1,165 @@
+// Conversions between the template types of this crate and their `hcl-edit` counterparts.
+//
+// Template literals are copied verbatim in both directions.
+
+use super::{Directive, Element, ForDirective, IfDirective, Interpolation, Strip, Template};
+use crate::{Error, Expression, Result};
+use hcl_edit::expr::Expression as EditExpression;
+use hcl_edit::template as edit;
+
+impl From<edit::Template> for Template {
+    fn from(template: edit::Template) -> Self {
+        template.into_iter().map(Element::from).collect()
+    }
+}
+
+impl TryFrom<Template> for edit::Template {
+    type Error = Error;
+
+    fn try_from(template: Template) -> Result<Self> {
+        template
+            .elements
+            .into_iter()
+            .map(edit::Element::try_from)
+            .collect()
+    }
+}
+
+impl From<edit::Element> for Element {
+    fn from(element: edit::Element) -> Self {
+        match element {
+            edit::Element::Literal(literal) => Element::Literal(literal.into_value()),
+            edit::Element::Interpolation(interp) => Element::Interpolation(interp.into()),
+            edit::Element::Directive(dir) => Element::Directive(dir.into()),
+        }
+    }
+}
+
+impl TryFrom<Element> for edit::Element {
+    type Error = Error;
+
+    fn try_from(element: Element) -> Result<Self> {
+        match element {
+            Element::Literal(literal) => Ok(edit::Element::from(literal)),
+            Element::Interpolation(interp) => edit::Interpolation::try_from(interp).map(Into::into),
+            Element::Directive(dir) => edit::Directive::try_from(dir).map(Into::into),
+        }
+    }
+}
+
+impl From<edit::Interpolation> for Interpolation {
+    fn from(interp: edit::Interpolation) -> Self {
+        Interpolation {
+            expr: interp.expr.into(),
+            strip: interp.strip,
+        }
+    }
+}
+
+impl TryFrom<Interpolation> for edit::Interpolation {
+    type Error = Error;
+
+    fn try_from(interp: Interpolation) -> Result<Self> {
+        let mut edit_interp = edit::Interpolation::new(EditExpression::try_from(interp.expr)?);
+        edit_interp.strip = interp.strip;
+        Ok(edit_interp)
+    }
+}
+
+impl From<edit::Directive> for Directive {
+    fn from(dir: edit::Directive) -> Self {
+        match dir {
+            edit::Directive::If(dir) => Directive::If(dir.into()),
+            edit::Directive::For(dir) => Directive::For(dir.into()),
+        }
+    }
+}
+
+impl TryFrom<Directive> for edit::Directive {
+    type Error = Error;
+
+    fn try_from(dir: Directive) -> Result<Self> {
+        match dir {
+            Directive::If(dir) => edit::IfDirective::try_from(dir).map(Into::into),
+            Directive::For(dir) => edit::ForDirective::try_from(dir).map(Into::into),
+        }
+    }
+}
+
+impl From<edit::IfDirective> for IfDirective {
+    fn from(dir: edit::IfDirective) -> Self {
+        let (false_template, else_strip) = match dir.else_expr {
+            Some(else_expr) => (Some(else_expr.template.into()), else_expr.strip),
+            None => (None, Strip::default()),
+        };
+
+        IfDirective {
+            cond_expr: Expression::from(dir.if_expr.cond_expr),
+            true_template: dir.if_expr.template.into(),
+            false_template,
+            if_strip: dir.if_expr.strip,
+            else_strip,
+            endif_strip: dir.endif_expr.strip,
+        }
+    }
+}
+
+impl TryFrom<IfDirective> for edit::IfDirective {
+    type Error = Error;
+
+    fn try_from(dir: IfDirective) -> Result<Self> {
+        let mut if_expr = edit::IfTemplateExpr::new(
+            EditExpression::try_from(dir.cond_expr)?,
+            dir.true_template.try_into()?,
+        );
+        if_expr.strip = dir.if_strip;
+
+        let else_expr = match dir.false_template {
+            Some(false_template) => {
+                let mut else_expr = edit::ElseTemplateExpr::new(false_template.try_into()?);
+                else_expr.strip = dir.else_strip;
+                Some(else_expr)
+            }
+            None => None,
+        };
+
+        let mut endif_expr = edit::EndifTemplateExpr::new();
+        endif_expr.strip = dir.endif_strip;
+
+        Ok(edit::IfDirective::new(if_expr, else_expr, endif_expr))
+    }
+}
+
+impl From<edit::ForDirective> for ForDirective {
+    fn from(dir: edit::ForDirective) -> Self {
+        let for_expr = dir.for_expr;
+
+        ForDirective {
+            key_var: for_expr.key_var.map(|var| var.into_value().into()),
+            value_var: for_expr.value_var.into_value().into(),
+            collection_expr: for_expr.collection_expr.into(),
+            template: for_expr.template.into(),
+            for_strip: for_expr.strip,
+            endfor_strip: dir.endfor_expr.strip,
+        }
+    }
+}
+
+impl TryFrom<ForDirective> for edit::ForDirective {
+    type Error = Error;
+
+    fn try_from(dir: ForDirective) -> Result<Self> {
+        let mut for_expr = edit::ForTemplateExpr::new(
+            dir.key_var,
+            dir.value_var,
+            EditExpression::try_from(dir.collection_expr)?,
+            dir.template.try_into()?,
+        );
+        for_expr.strip = dir.for_strip;
+
+        let mut endfor_expr = edit::EndforTemplateExpr::new();
+        endfor_expr.strip = dir.endfor_strip;
+
+        Ok(edit::ForDirective::new(for_expr, endfor_expr))
+    }
+}
@@ -0,0 +1,619 @@
+//! A type system for HCL values and conversions between types.
+//!
+//! The [`Type`] enum models the types of [`Value`]s in the same way as
+//! [cty](https://github.com/zclconf/go-cty), the type system which is used by HCL and Terraform.
+//! In contrast to the [`ParamType`][crate::eval::ParamType] which only validates function
+//! arguments, types can describe tuples, sets and objects with named and optional attributes.
+//!
+//! The [`convert`] function converts a `Value` to a given `Type`, following cty's conversion
+//! rules:
+//!
+//! - `null` converts to any type and stays `null`.
+//! - Strings convert to numbers if they contain a valid number, and to booleans if they are one
+//!   of `true`, `false`, `1` and `0`. Numbers and booleans always convert to strings.
+//! - Arrays convert to lists, sets and tuples if their elements convert to the element type(s).
+//!   Sets are deduplicated, keeping the first occurrence of each element.
+//! - Objects convert to maps if their values convert to the element type, and to object types if
+//!   they contain all required attributes. Missing optional attributes are set to `null` and
+//!   attributes which are not part of the object type are dropped.
+//! - The elements of a `list(any)`, `set(any)` or `map(any)` must all have the same type. If they
+//!   are primitive values of mixed types, they are all converted to strings.
+//!
+//! # Example
+//!
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use hcl::types::{convert, ObjectType, Type};
+//! use hcl::{value, Value};
+//!
+//! let ty = Type::Object(
+//!     ObjectType::new()
+//!         .attr("name", Type::String)
+//!         .attr("ports", Type::list_of(Type::Number))
+//!         .optional_attr("enabled", Type::Bool),
+//! );
+//!
+//! let value = value!({
+//!     name = "api"
+//!     ports = ["80", 443]
+//! });
+//!
+//! let expected = value!({
+//!     name = "api"
+//!     ports = [80, 443]
+//!     enabled = null
+//! });
+//!
+//! assert_eq!(convert(value, &ty)?, expected);
+//! #   Ok(())
+//! # }
+//! ```
+
+use crate::value::{Map, Value};
+use crate::Number;
+use std::fmt;
+
+/// The type of an HCL [`Value`].
+///
+/// The type of a value is used to [`convert`] it to another type. See the [module-level
+/// documentation](crate::types) for the conversion rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    /// Any type. Values are not converted, this is cty's `DynamicPseudoType`.
+    Any,
+    /// A boolean value.
+    Bool,
+    /// A number.
+    Number,
+    /// A string value.
+    String,
+    /// An ordered sequence of values which are all of the given element type.
+    List(Box<Type>),
+    /// An unordered collection of unique values which are all of the given element type.
+    Set(Box<Type>),
+    /// A collection of values with string keys which are all of the given element type.
+    Map(Box<Type>),
+    /// A fixed-length sequence of values where each element has its own type.
+    Tuple(Vec<Type>),
+    /// A collection of named attributes where each attribute has its own type.
+    Object(ObjectType),
+}
+
+impl Type {
+    /// Creates a new `List` type with the given element type.
+    pub fn list_of(element: Type) -> Type {
+        Type::List(Box::new(element))
+    }
+
+    /// Creates a new `Set` type with the given element type.
+    pub fn set_of(element: Type) -> Type {
+        Type::Set(Box::new(element))
+    }
+
+    /// Creates a new `Map` type with the given element type.
+    pub fn map_of(element: Type) -> Type {
+        Type::Map(Box::new(element))
+    }
+
+    /// Creates a new `Tuple` type from the provided element types.
+    pub fn tuple<I>(elements: I) -> Type
+    where
+        I: IntoIterator<Item = Type>,
+    {
+        Type::Tuple(elements.into_iter().collect())
+    }
+
+    /// Returns `true` if the type is one of `Bool`, `Number` or `String`.
+    pub fn is_primitive(&self) -> bool {
+        matches!(self, Type::Bool | Type::Number | Type::String)
+    }
+}
+
+impl From<ObjectType> for Type {
+    fn from(object: ObjectType) -> Self {
+        Type::Object(object)
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Any => f.write_str("any"),
+            Type::Bool => f.write_str("bool"),
+            Type::Number => f.write_str("number"),
+            Type::String => f.write_str("string"),
+            Type::List(elem_type) => write!(f, "list({elem_type})"),
+            Type::Set(elem_type) => write!(f, "set({elem_type})"),
+            Type::Map(elem_type) => write!(f, "map({elem_type})"),
+            Type::Tuple(elem_types) => {
+                f.write_str("tuple([")?;
+                for (i, elem_type) in elem_types.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    fmt::Display::fmt(elem_type, f)?;
+                }
+                f.write_str("])")
+            }
+            Type::Object(object) => fmt::Display::fmt(object, f),
+        }
+    }
+}
+
+/// The type of an object with named attributes.
+///
+/// Attributes are either required or optional. When a value is converted to an object type,
+/// missing optional attributes are set to `null`.
+///
+/// # Example
+///
+/// ```
+/// use hcl::types::{ObjectType, Type};
+///
+/// let ty = ObjectType::new()
+///     .attr("name", Type::String)
+///     .optional_attr("port", Type::Number);
+///
+/// assert_eq!(ty.to_string(), "object({name = string, port = optional(number)})");
+/// assert!(ty.is_optional("port"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectType {
+    attrs: Map<String, ObjectAttr>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ObjectAttr {
+    ty: Type,
+    optional: bool,
+}
+
+impl ObjectType {
+    /// Creates a new `ObjectType` without any attributes.
+    pub fn new() -> ObjectType {
+        ObjectType::default()
+    }
+
+    /// Adds a required attribute to the object type.
+    ///
+    /// An existing attribute with the same name is replaced.
+    pub fn attr<K>(self, name: K, ty: Type) -> ObjectType
+    where
+        K: Into<String>,
+    {
+        self.insert_attr(name.into(), ty, false)
+    }
+
+    /// Adds an optional attribute to the object type.
+    ///
+    /// An existing attribute with the same name is replaced.
+    pub fn optional_attr<K>(self, name: K, ty: Type) -> ObjectType
+    where
+        K: Into<String>,
+    {
+        self.insert_attr(name.into(), ty, true)
+    }
+
+    fn insert_attr(mut self, name: String, ty: Type, optional: bool) -> ObjectType {
+        self.attrs.insert(name, ObjectAttr { ty, optional });
+        self
+    }
+
+    /// Returns the type of the attribute with the given name, if it exists.
+    pub fn attr_type(&self, name: &str) -> Option<&Type> {
+        self.attrs.get(name).map(|attr| &attr.ty)
+    }
+
+    /// Returns `true` if the object type has an optional attribute with the given name.
+    pub fn is_optional(&self, name: &str) -> bool {
+        self.attrs.get(name).is_some_and(|attr| attr.optional)
+    }
+
+    /// Returns an iterator over the names and types of all attributes.
+    pub fn attrs(&self) -> impl Iterator<Item = (&str, &Type)> {
+        self.attrs
+            .iter()
+            .map(|(name, attr)| (name.as_str(), &attr.ty))
+    }
+}
+
+impl fmt::Display for ObjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("object({")?;
+        for (i, (name, attr)) in self.attrs.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            if attr.optional {
+                write!(f, "{name} = optional({})", attr.ty)?;
+            } else {
+                write!(f, "{name} = {}", attr.ty)?;
+            }
+        }
+        f.write_str("})")
+    }
+}
+
+/// The error type returned by [`convert`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    path: String,
+    msg: String,
+}
+
+impl Error {
+    fn new<T>(msg: T) -> Error
+    where
+        T: fmt::Display,
+    {
+        Error {
+            path: String::new(),
+            msg: msg.to_string(),
+        }
+    }
+
+    fn at_attr(mut self, name: &str) -> Error {
+        self.path.insert_str(0, &format!(".{name}"));
+        self
+    }
+
+    fn at_index(mut self, index: usize) -> Error {
+        self.path.insert_str(0, &format!("[{index}]"));
+        self
+    }
+
+    fn at_key(mut self, key: &str) -> Error {
+        self.path.insert_str(0, &format!("[{key:?}]"));
+        self
+    }
+
+    /// Returns the path to the nested value that failed to convert, e.g. `.listeners[1].port`.
+    ///
+    /// The path is empty if the top-level value failed to convert.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the error message without the path.
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.msg)
+        } else {
+            write!(f, "{}: {}", self.path, self.msg)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Converts a [`Value`] to the given [`Type`].
+///
+/// See the [module-level documentation](crate::types) for the conversion rules.
+///
+/// # Errors
+///
+/// Returns an error if the value or one of its nested values cannot be converted. The error
+/// contains the path to the nested value.
+pub fn convert(value: Value, ty: &Type) -> Result<Value, Error> {
+    match (value, ty) {
+        (Value::Null, _) => Ok(Value::Null),
+        (value, Type::Any) => Ok(value),
+        (Value::Bool(b), Type::Bool) => Ok(Value::Bool(b)),
+        (Value::String(s), Type::Bool) => match s.as_str() {
+            "true" | "1" => Ok(Value::Bool(true)),
+            "false" | "0" => Ok(Value::Bool(false)),
+            _ => Err(Error::new(format!("cannot convert {s:?} to bool"))),
+        },
+        (Value::Number(n), Type::Number) => Ok(Value::Number(n)),
+        (Value::String(s), Type::Number) => parse_number(&s)
+            .map(Value::Number)
+            .ok_or_else(|| Error::new(format!("cannot convert {s:?} to number"))),
+        (Value::String(s), Type::String) => Ok(Value::String(s)),
+        (Value::Number(n), Type::String) => Ok(Value::String(n.to_string())),
+        (Value::Bool(b), Type::String) => Ok(Value::String(b.to_string())),
+        (Value::Array(array), Type::List(elem_type)) => {
+            convert_elements(array, elem_type).map(Value::Array)
+        }
+        (Value::Array(array), Type::Set(elem_type)) => {
+            let elements = convert_elements(array, elem_type)?;
+            let mut set: Vec<Value> = Vec::with_capacity(elements.len());
+
+            for elem in elements {
+                if !set.contains(&elem) {
+                    set.push(elem);
+                }
+            }
+
+            Ok(Value::Array(set))
+        }
+        (Value::Array(array), Type::Tuple(elem_types)) => {
+            if array.len() != elem_types.len() {
+                return Err(Error::new(format!(
+                    "a tuple of {} elements is required, got {}",
+                    elem_types.len(),
+                    array.len()
+                )));
+            }
+
+            array
+                .into_iter()
+                .zip(elem_types)
+                .enumerate()
+                .map(|(index, (elem, elem_type))| {
+                    convert(elem, elem_type).map_err(|err| err.at_index(index))
+                })
+                .collect()
+        }
+        (Value::Object(object), Type::Map(elem_type)) => {
+            let (keys, values): (Vec<_>, Vec<_>) = object.into_iter().unzip();
+            let values = values
+                .into_iter()
+                .zip(&keys)
+                .map(|(value, key)| convert(value, elem_type).map_err(|err| err.at_key(key)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let values = if **elem_type == Type::Any {
+                unify(values)?
+            } else {
+                values
+            };
+
+            Ok(Value::Object(keys.into_iter().zip(values).collect()))
+        }
+        (Value::Object(mut object), Type::Object(object_type)) => object_type
+            .attrs
+            .iter()
+            .map(|(name, attr)| match object.swap_remove(name) {
+                Some(value) => match convert(value, &attr.ty) {
+                    Ok(value) => Ok((name.clone(), value)),
+                    Err(err) => Err(err.at_attr(name)),
+                },
+                None if attr.optional => Ok((name.clone(), Value::Null)),
+                None => Err(Error::new(format!("attribute `{name}` is required"))),
+            })
+            .collect(),
+        (_, ty) => Err(Error::new(format!("{} is required", describe(ty)))),
+    }
+}
+
+// Converts all elements of an array to the element type of a list or set.
+fn convert_elements(array: Vec<Value>, elem_type: &Type) -> Result<Vec<Value>, Error> {
+    let elements = array
+        .into_iter()
+        .enumerate()
+        .map(|(index, elem)| convert(elem, elem_type).map_err(|err| err.at_index(index)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if *elem_type == Type::Any {
+        unify(elements)
+    } else {
+        Ok(elements)
+    }
+}
+
+// Ensures that all non-null collection elements have the same type, like cty does for collections
+// of `any`. Primitive values of mixed types are unified by converting them to strings.
+fn unify(values: Vec<Value>) -> Result<Vec<Value>, Error> {
+    let mut types = values.iter().filter_map(value_type);
+
+    let Some(first) = types.next() else {
+        return Ok(values);
+    };
+
+    let mut all_primitive = first.is_primitive();
+    let mut all_equal = true;
+
+    for ty in types {
+        all_primitive &= ty.is_primitive();
+        all_equal &= ty == first;
+    }
+
+    if all_equal {
+        Ok(values)
+    } else if all_primitive {
+        values
+            .into_iter()
+            .map(|value| convert(value, &Type::String))
+            .collect()
+    } else {
+        Err(Error::new("all elements must have the same type"))
+    }
+}
+
+// Returns the shallow type of a non-null value.
+fn value_type(value: &Value) -> Option<Type> {
+    match value {
+        Value::Null => None,
+        Value::Bool(_) => Some(Type::Bool),
+        Value::Number(_) => Some(Type::Number),
+        Value::String(_) => Some(Type::String),
+        Value::Array(_) => Some(Type::list_of(Type::Any)),
+        Value::Object(_) => Some(Type::map_of(Type::Any)),
+    }
+}
+
+fn describe(ty: &Type) -> &'static str {
+    match ty {
+        Type::Any => "a value",
+        Type::Bool => "a bool",
+        Type::Number => "a number",
+        Type::String => "a string",
+        Type::List(_) => "a list",
+        Type::Set(_) => "a set",
+        Type::Map(_) => "a map",
+        Type::Tuple(_) => "a tuple",
+        Type::Object(_) => "an object",
+    }
+}
+
+fn parse_number(s: &str) -> Option<Number> {
+    if let Ok(n) = s.parse::<i64>() {
+        Some(Number::from(n))
+    } else if let Ok(n) = s.parse::<u64>() {
+        Some(Number::from(n))
+    } else {
+        s.parse::<f64>().ok().and_then(Number::from_f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn convert_primitives() {
+        assert_eq!(convert(Value::from("5"), &Type::Number), Ok(Value::from(5)));
+        assert_eq!(
+            convert(Value::from("-1.5"), &Type::Number),
+            Ok(Value::from(-1.5))
+        );
+        assert_eq!(
+            convert(Value::from("true"), &Type::Bool),
+            Ok(Value::from(true))
+        );
+        assert_eq!(
+            convert(Value::from("1"), &Type::Bool),
+            Ok(Value::from(true))
+        );
+        assert_eq!(
+            convert(Value::from("0"), &Type::Bool),
+            Ok(Value::from(false))
+        );
+        assert_eq!(
+            convert(Value::from(42), &Type::String),
+            Ok(Value::from("42"))
+        );
+        assert_eq!(
+            convert(Value::from(false), &Type::String),
+            Ok(Value::from("false"))
+        );
+        assert_eq!(convert(Value::Null, &Type::Number), Ok(Value::Null));
+        assert_eq!(
+            convert(Value::from("foo"), &Type::Number)
+                .unwrap_err()
+                .to_string(),
+            "cannot convert \"foo\" to number"
+        );
+        assert_eq!(
+            convert(Value::from(1), &Type::Bool)
+                .unwrap_err()
+                .to_string(),
+            "a bool is required"
+        );
+    }
+
+    #[test]
+    fn convert_collections() {
+        let tuple = Value::from_iter([Value::from(1), Value::from("2"), Value::from(1)]);
+
+        assert_eq!(
+            convert(tuple.clone(), &Type::list_of(Type::Number)),
+            Ok(Value::from_iter([1, 2, 1]))
+        );
+        assert_eq!(
+            convert(tuple.clone(), &Type::set_of(Type::Number)),
+            Ok(Value::from_iter([1, 2]))
+        );
+        assert_eq!(
+            convert(tuple.clone(), &Type::list_of(Type::Any)),
+            Ok(Value::from_iter(["1", "2", "1"]))
+        );
+        assert_eq!(
+            convert(
+                tuple.clone(),
+                &Type::tuple([Type::String, Type::Number, Type::Bool])
+            )
+            .unwrap_err()
+            .to_string(),
+            "[2]: a bool is required"
+        );
+        assert_eq!(
+            convert(tuple, &Type::tuple([Type::Any]))
+                .unwrap_err()
+                .to_string(),
+            "a tuple of 1 elements is required, got 3"
+        );
+
+        let object = Value::from_iter([("a", Value::from(1)), ("b", Value::from_iter([1]))]);
+
+        assert_eq!(
+            convert(object.clone(), &Type::map_of(Type::Any))
+                .unwrap_err()
+                .to_string(),
+            "all elements must have the same type"
+        );
+        assert_eq!(
+            convert(object, &Type::map_of(Type::Number))
+                .unwrap_err()
+                .to_string(),
+            "[\"b\"]: a number is required"
+        );
+    }
+
+    #[test]
+    fn convert_objects() {
+        let ty = Type::from(
+            ObjectType::new()
+                .attr("name", Type::String)
+                .attr(
+                    "listeners",
+                    Type::list_of(Type::from(ObjectType::new().attr("port", Type::Number))),
+                )
+                .optional_attr("enabled", Type::Bool),
+        );
+
+        assert_eq!(
+            ty.to_string(),
+            "object({name = string, listeners = list(object({port = number})), enabled = optional(bool)})"
+        );
+
+        let value = Value::from_iter([
+            ("extra", Value::from(true)),
+            (
+                "listeners",
+                Value::from_iter([Value::from_iter([("port", "80")])]),
+            ),
+            ("name", Value::from("api")),
+        ]);
+
+        let expected = Value::from_iter([
+            ("name", Value::from("api")),
+            (
+                "listeners",
+                Value::from_iter([Value::from_iter([("port", 80)])]),
+            ),
+            ("enabled", Value::Null),
+        ]);
+
+        assert_eq!(convert(value, &ty), Ok(expected));
+
+        let value = Value::from_iter([
+            ("name", Value::from("api")),
+            (
+                "listeners",
+                Value::from_iter([
+                    Value::from_iter([("port", 80)]),
+                    Value::from_iter([("port", "http")]),
+                ]),
+            ),
+        ]);
+
+        let err = convert(value, &ty).unwrap_err();
+        assert_eq!(err.path(), ".listeners[1].port");
+        assert_eq!(err.message(), "cannot convert \"http\" to number");
+
+        let value = Value::from_iter([("enabled", true)]);
+
+        assert_eq!(
+            convert(value, &ty).unwrap_err().to_string(),
+            "attribute `name` is required"
+        );
+    }
+}
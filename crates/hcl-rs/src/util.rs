@@ -8,6 +8,7 @@ use std::str::Chars;
 // This is how the original HCL spec seems to handle it based on the original specsuite although it
 // is not formally defined. E.g. ' ' (space) and '\u{2003}' (unicode "em-space") are treated as one
 // unit of whitespace even though the former is 1 byte and the latter is 3 bytes long.
+#[cfg(feature = "json")]
 pub fn dedent(s: &str) -> Cow<str> {
     if s.is_empty() {
         return Cow::Borrowed(s);
@@ -126,6 +127,36 @@ pub fn try_unescape(s: &str) -> Cow<str> {
     }
 }
 
+/// Escapes the sequences in `s` that would introduce a template interpolation or directive, i.e.
+/// `${` becomes `$${` and `%{` becomes `%%{`. Sequences which are already escaped are copied
+/// verbatim.
+#[cfg(feature = "json")]
+pub fn escape_template(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(pos) = rest.find(['$', '%']) {
+        let (literal, tail) = rest.split_at(pos);
+        let marker = &tail[..1];
+        escaped.push_str(literal);
+
+        rest = if tail[1..].starts_with(marker) && tail[2..].starts_with('{') {
+            escaped.push_str(&tail[..3]);
+            &tail[3..]
+        } else if tail[1..].starts_with('{') {
+            escaped.push_str(marker);
+            escaped.push_str(&tail[..2]);
+            &tail[2..]
+        } else {
+            escaped.push_str(marker);
+            &tail[1..]
+        };
+    }
+
+    escaped.push_str(rest);
+    escaped
+}
+
 /// Scan `s` for sequences that introduce a template interpolation or directive. Returns `true`
 /// once it found one of these start markers, `false` otherwise.
 ///
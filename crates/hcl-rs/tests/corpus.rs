@@ -0,0 +1,64 @@
+use pretty_assertions::assert_eq;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+// Renders the structures of a body, one per line.
+fn snapshot(body: &hcl::Body) -> String {
+    body.iter().fold(String::new(), |mut out, structure| {
+        writeln!(out, "{structure:?}").unwrap();
+        out
+    })
+}
+
+// Parses the testdata files and specsuite inputs and compares the resulting bodies with the
+// snapshots in `tests/corpus`. The snapshots were checked against the output of the pest parser
+// which `hcl::parse` used before it was switched to the `hcl-edit` parser.
+#[test]
+fn parse_corpus() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let specsuite_dir = manifest_dir.join("../specsuite/tests");
+
+    let mut inputs: Vec<(String, String)> = testdata::load()
+        .unwrap()
+        .into_iter()
+        .map(|test| (format!("testdata-{}", test.name()), test.input))
+        .collect();
+
+    for path in ["empty.hcl", "expressions"].map(|name| specsuite_dir.join(name)) {
+        let paths = if path.is_dir() {
+            fs::read_dir(&path)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .collect()
+        } else {
+            vec![path]
+        };
+
+        for path in paths {
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+
+            if path.extension().is_some_and(|ext| ext == "hcl") {
+                let input = fs::read_to_string(&path).unwrap();
+
+                if name.starts_with("invalid-") {
+                    assert!(hcl::parse(&input).is_err(), "{name}");
+                } else {
+                    inputs.push((format!("specsuite-{name}"), input));
+                }
+            }
+        }
+    }
+
+    assert_eq!(inputs.len(), 10);
+
+    for (name, input) in inputs {
+        let path = manifest_dir
+            .join("tests/corpus")
+            .join(format!("{name}.txt"));
+        let expected = fs::read_to_string(&path).unwrap();
+
+        let body = hcl::parse(&input).unwrap();
+        assert_eq!(snapshot(&body), expected, "{name}");
+    }
+}
@@ -3,7 +3,8 @@ mod common;
 use common::assert_serialize;
 use hcl::{
     expr::{BinaryOp, BinaryOperator, Conditional, Expression, Traversal, Variable},
-    ser::{block, doubly_labeled_block, labeled_block},
+    ser::{block, doubly_labeled_block, labeled_block, Block, Commented, LabeledBlock},
+    structure::{Comment, CommentStyle},
     Map,
 };
 use indexmap::indexmap;
@@ -283,3 +284,70 @@ fn custom_terraform_blocks() {
 
     assert_serialize(config, expected);
 }
+
+#[test]
+fn commented_blocks() {
+    #[derive(Serialize)]
+    struct Config {
+        listener: Commented<Block<Vec<Commented<Listener>>>>,
+        service: LabeledBlock<Map<&'static str, Commented<Service>>>,
+    }
+
+    #[derive(Serialize)]
+    struct Listener {
+        port: Commented<u16>,
+    }
+
+    #[derive(Serialize)]
+    struct Service {
+        // Comments can not be attached to expressions and are dropped.
+        hosts: Vec<Commented<&'static str>>,
+    }
+
+    let config = Commented::new(Config {
+        listener: Commented::new(Block::new(vec![
+            Commented::new(Listener {
+                port: Commented::new(80).with_trailing_comment("http"),
+            })
+            .with_leading_comment("first"),
+            Commented::new(Listener {
+                port: Commented::new(443).with_leading_comment(
+                    Comment::new("https").with_style(CommentStyle::DoubleSlash),
+                ),
+            })
+            .with_trailing_comment("second"),
+        ]))
+        .with_leading_comment("Listeners")
+        .with_trailing_comment("last listener"),
+        service: LabeledBlock::new(indexmap! {
+            "api" => Commented::new(Service {
+                hosts: vec![Commented::new("a").with_leading_comment("dropped")],
+            })
+            .with_leading_comment(Comment::new("The API.").with_style(CommentStyle::SlashStar)),
+        }),
+    })
+    .with_leading_comment("Generated");
+
+    let expected = indoc! {r#"
+        # Generated
+        # Listeners
+        # first
+        listener {
+          port = 80 # http
+        }
+
+        listener {
+          // https
+          port = 443
+        } # last listener
+
+        /* The API. */
+        service "api" {
+          hosts = [
+            "a"
+          ]
+        }
+    "#};
+
+    assert_serialize(config, expected);
+}
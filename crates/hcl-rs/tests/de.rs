@@ -1,12 +1,14 @@
 mod common;
 
 use common::assert_deserialize;
+use hcl::de::Spanned;
 use hcl::expr::{
     BinaryOp, BinaryOperator, Expression, ForExpr, FuncCall, Heredoc, HeredocStripMode, ObjectKey,
     Operation, TemplateExpr, Traversal, UnaryOp, UnaryOperator, Variable,
 };
 use hcl::structure::{Block, Body};
 use hcl::{Identifier, Value};
+use indoc::indoc;
 use serde::Deserialize;
 use std::fmt::Debug;
 
@@ -455,3 +457,192 @@ fn terraform() {
 
     assert_deserialize(input, expected);
 }
+
+#[test]
+fn error_location() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Config {
+        name: String,
+        service: Service,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Service {
+        port: u16,
+    }
+
+    let input = indoc! {r#"
+        name = "foo"
+
+        service {
+          port = "eighty"
+        }
+    "#};
+
+    let err = hcl::from_str::<Config>(input).unwrap_err();
+    let location = err.location().unwrap();
+
+    assert_eq!((location.line, location.col), (4, 3));
+    assert_eq!(
+        err.to_string(),
+        r#"service > port: invalid type: string "eighty", expected u16 in line 4, col 3"#
+    );
+
+    let hcl::Error::AtPath { err, .. } = err else {
+        panic!("expected error with path, got {err:?}");
+    };
+    assert!(matches!(*err, hcl::Error::Message { .. }));
+}
+
+#[test]
+fn error_path() {
+    use hcl::error::PathSegment;
+    use std::collections::BTreeMap;
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Config {
+        service: BTreeMap<String, Service>,
+        resource: BTreeMap<String, BTreeMap<String, Resource>>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Service {
+        listener: Vec<Listener>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Listener {
+        port: u16,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Resource {
+        ami: String,
+    }
+
+    let input = indoc! {r#"
+        service "api" {
+          listener {
+            port = 80
+          }
+
+          listener {
+            port = "https"
+          }
+        }
+    "#};
+
+    let err = hcl::from_str::<Config>(input).unwrap_err();
+    let path = err.path().unwrap();
+
+    assert_eq!(
+        path.segments(),
+        [
+            PathSegment::Key("service".into()),
+            PathSegment::Label("api".into()),
+            PathSegment::Key("listener".into()),
+            PathSegment::Index(1),
+            PathSegment::Key("port".into()),
+        ]
+    );
+    assert_eq!(
+        err.to_string(),
+        r#"service "api" > listener[1] > port: invalid type: string "https", expected u16 in line 7, col 5"#
+    );
+
+    let input = indoc! {r#"
+        service "api" {
+          listener {
+            port = 80
+          }
+
+          listener {
+            port = 443
+          }
+        }
+
+        resource "aws_instance" "web" {}
+    "#};
+
+    let err = hcl::from_str::<Config>(input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"resource "aws_instance" "web": missing field `ami`"#
+    );
+}
+
+#[test]
+fn spanned() {
+    #[derive(Deserialize, Debug)]
+    struct Config {
+        name: Spanned<String>,
+        service: std::collections::BTreeMap<String, Spanned<Service>>,
+        rule: Vec<Spanned<Rule>>,
+        tags: Vec<Spanned<String>>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Service {
+        port: Spanned<u16>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Rule {
+        action: String,
+    }
+
+    let input = indoc! {r#"
+        name = "foo"
+        tags = ["a"]
+
+        service "api" {
+          port = 8080
+        }
+
+        rule {
+          action = "allow"
+        }
+
+        rule {
+          action = "deny"
+        }
+    "#};
+
+    let config: Config = hcl::from_str(input).unwrap();
+
+    let span = config.name.span().unwrap();
+    assert_eq!(&input[span.range()], r#"name = "foo""#);
+    assert_eq!(config.name.as_str(), "foo");
+
+    let service = &config.service["api"];
+    let span = service.span().unwrap();
+    assert_eq!((span.start().line, span.start().col), (4, 1));
+    assert_eq!((span.end().line, span.end().col), (6, 2));
+
+    let span = service.port.span().unwrap();
+    assert_eq!(&input[span.range()], "port = 8080");
+    assert_eq!(*service.port, 8080);
+
+    let lines: Vec<_> = config
+        .rule
+        .iter()
+        .map(|rule| rule.span().unwrap().start().line)
+        .collect();
+    assert_eq!(lines, [8, 12]);
+
+    // Array elements do not map to an attribute or block.
+    assert_eq!(config.tags, [Spanned::new("a".to_owned())]);
+    assert!(config.tags[0].span().is_none());
+
+    // Other deserializers just deserialize the wrapped value.
+    let port: Spanned<u16> = serde_json::from_str("80").unwrap();
+    assert_eq!(*port, 80);
+    assert!(port.span().is_none());
+}
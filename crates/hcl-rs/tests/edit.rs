@@ -0,0 +1,216 @@
+use hcl::expr::{
+    Expression, Heredoc, HeredocStripMode, RawExpression, TemplateExpr, Traversal,
+    TraversalOperator, Variable,
+};
+use hcl::template::{ForDirective, Strip, Template};
+use hcl::{Body, Identifier};
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const INPUT: &str = indoc! {r#"
+    // A comment.
+    resource "aws_s3_bucket" bucket {
+      name   = "${var.prefix}-bucket" # Trailing comment.
+      count  = (var.count + 1) * 2
+      tags   = { for k, v in var.tags : upper(k) => v... if v != null }
+      arns   = var.buckets[*].arn
+      first  = var.buckets.0.name
+      items  = [for item in local.items : item.id]
+      sign   = !(var.count > 0) ? -1 : 1
+      args   = format("%s-%d", ["a", 1]...)
+      escape = "quote: \", newline: \n, literal: $${var}"
+
+      policy = <<EOT
+    {
+      "Version": "${var.version}"
+    }
+    EOT
+
+      script = <<-EOS
+        %{ for k, v in var.envs ~}
+        export ${k}=${v}
+        %{ endfor ~}
+      EOS
+
+      lifecycle {
+        prevent_destroy = true
+        ignore          = null
+      }
+    }
+"#};
+
+#[test]
+fn from_edit_body() {
+    let edit_body: hcl_edit::structure::Body = INPUT.parse().unwrap();
+    let body = Body::from(edit_body);
+
+    assert_eq!(body, hcl::parse(INPUT).unwrap());
+}
+
+#[test]
+fn into_edit_body() {
+    let body = hcl::parse(INPUT).unwrap();
+    let edit_body = hcl_edit::structure::Body::try_from(body.clone()).unwrap();
+
+    assert_eq!(Body::from(edit_body), body);
+}
+
+#[test]
+fn default_decor() {
+    let body = hcl::parse(INPUT).unwrap();
+    let edit_body = hcl_edit::structure::Body::try_from(body.clone()).unwrap();
+    let output = edit_body.to_string();
+
+    assert!(!output.contains("comment"));
+    assert_eq!(hcl::parse(&output).unwrap(), body);
+}
+
+#[test]
+fn parenthesis() {
+    let edit_expr = hcl_edit::parser::parse_expr("(a + b) * c").unwrap();
+
+    let expr = Expression::from(edit_expr);
+    let Expression::Operation(op) = &expr else {
+        panic!("expected operation, got {expr:?}");
+    };
+    let hcl::expr::Operation::Binary(op) = &**op else {
+        panic!("expected binary operation, got {op:?}");
+    };
+
+    assert!(matches!(op.lhs_expr, Expression::Parenthesis(_)));
+
+    let edit_expr = hcl_edit::expr::Expression::try_from(expr).unwrap();
+    assert_eq!(edit_expr.to_string(), "(a + b) * c");
+}
+
+#[test]
+fn traversal() {
+    let expr = Expression::from(
+        Traversal::builder(Variable::unchecked("var"))
+            .attr("list")
+            .attr_splat()
+            .attr("name")
+            .index(0)
+            .build(),
+    );
+
+    let edit_expr = hcl_edit::expr::Expression::try_from(expr.clone()).unwrap();
+    assert_eq!(edit_expr.to_string(), "var.list.*.name[0]");
+    assert_eq!(Expression::from(edit_expr), expr);
+
+    let edit_expr = hcl_edit::parser::parse_expr("var.list[*].name.1").unwrap();
+
+    assert_eq!(
+        Expression::from(edit_expr),
+        Expression::from(Traversal::new(
+            Variable::unchecked("var"),
+            [
+                TraversalOperator::GetAttr(Identifier::unchecked("list")),
+                TraversalOperator::FullSplat,
+                TraversalOperator::GetAttr(Identifier::unchecked("name")),
+                TraversalOperator::LegacyIndex(1),
+            ]
+        ))
+    );
+}
+
+#[test]
+fn heredocs() {
+    let edit_expr = hcl_edit::parser::parse_expr("<<-EOT\n    foo\n      ${bar}\n  EOT").unwrap();
+
+    let expected = Heredoc::new(Identifier::unchecked("EOT"), "foo\n  ${bar}\n")
+        .with_strip_mode(HeredocStripMode::Indent);
+
+    let expr = Expression::from(edit_expr);
+    assert_eq!(expr, Expression::from(expected));
+
+    let edit_expr = hcl_edit::expr::Expression::try_from(expr.clone()).unwrap();
+    assert_eq!(edit_expr.to_string(), "<<-EOT\nfoo\n  ${bar}\nEOT");
+    assert_eq!(Expression::from(edit_expr), expr);
+}
+
+#[test]
+fn templates() {
+    let expr = Expression::from(TemplateExpr::from(r#"\"${a}\" \\ literal"#));
+
+    let edit_expr = hcl_edit::expr::Expression::try_from(expr.clone()).unwrap();
+    assert!(edit_expr.is_template());
+    assert_eq!(Expression::from(edit_expr), expr);
+
+    // Templates without interpolations stay templates.
+    let expr = Expression::from(TemplateExpr::from("plain"));
+    let edit_expr = hcl_edit::expr::Expression::try_from(expr.clone()).unwrap();
+    assert!(edit_expr.is_template());
+    assert_eq!(Expression::from(edit_expr), expr);
+
+    let template = Template::new().add_directive(
+        ForDirective::new(
+            Identifier::unchecked("item"),
+            Variable::unchecked("items"),
+            Template::new()
+                .add_literal("- ")
+                .add_interpolation(Variable::unchecked("item")),
+        )
+        .with_for_strip(Strip::End),
+    );
+
+    let edit_template = hcl_edit::template::Template::try_from(template.clone()).unwrap();
+    assert_eq!(
+        edit_template.to_string(),
+        "%{for item in items ~}- ${ item }%{endfor}"
+    );
+    assert_eq!(Template::from(edit_template), template);
+}
+
+#[test]
+fn invalid_raw_expression() {
+    let expr = Expression::from(RawExpression::new("var.foo +"));
+    assert!(hcl_edit::expr::Expression::try_from(expr).is_err());
+
+    let expr = Expression::from(RawExpression::new("var.foo + 1"));
+    let edit_expr = hcl_edit::expr::Expression::try_from(expr).unwrap();
+    assert!(matches!(edit_expr, hcl_edit::expr::Expression::BinaryOp(_)));
+}
+
+#[test]
+fn serialize_hcl_blocks_to_edit_body() {
+    #[derive(Serialize)]
+    struct Config {
+        provider: hcl::ser::Block<Vec<Provider>>,
+        #[serde(serialize_with = "hcl::ser::labeled_block")]
+        variable: BTreeMap<&'static str, Provider>,
+    }
+
+    #[derive(Serialize)]
+    struct Provider {
+        region: &'static str,
+    }
+
+    let config = Config {
+        provider: hcl::ser::Block::new(vec![Provider {
+            region: "eu-west-1",
+        }]),
+        variable: BTreeMap::from([(
+            "region",
+            Provider {
+                region: "us-east-1",
+            },
+        )]),
+    };
+
+    let expected = indoc! {r#"
+        provider {
+          region = "eu-west-1"
+        }
+
+        variable "region" {
+          region = "us-east-1"
+        }
+    "#};
+
+    let body = hcl_edit::ser::to_body(&config).unwrap();
+    assert_eq!(body.to_string(), expected);
+    assert_eq!(hcl::to_string(&config).unwrap(), expected);
+}
@@ -1,12 +1,15 @@
 mod common;
 
 use common::{assert_eval, assert_eval_ctx, assert_eval_error};
-use hcl::eval::{Context, ErrorKind, EvalResult, FuncArgs, FuncDef, ParamType};
+use hcl::eval::{
+    Context, ErrorKind, EvalResult, Evaluate, FuncArgs, FuncDef, LazyFuncArgs, OrderedEvaluator,
+    ParamType, PartialEvaluate, PathSegment,
+};
 use hcl::expr::{
     BinaryOp, BinaryOperator, Conditional, Expression, ForExpr, FuncCall, TemplateExpr, Traversal,
     TraversalOperator, Variable,
 };
-use hcl::structure::Body;
+use hcl::structure::{BlockLabel, Body};
 use hcl::template::Template;
 use hcl::{Identifier, Number, Value};
 use indoc::indoc;
@@ -320,6 +323,426 @@ fn eval_func_call() {
     )
 }
 
+#[test]
+fn eval_func_call_closure() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let prefix = String::from("config");
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+
+    let mut ctx = Context::new();
+    ctx.declare_func(
+        "path",
+        FuncDef::builder().param(ParamType::String).build(
+            move |args: FuncArgs| -> EvalResult<Value, String> {
+                counter.fetch_add(1, Ordering::Relaxed);
+                Ok(Value::from(format!(
+                    "{prefix}/{}",
+                    args[0].as_str().unwrap()
+                )))
+            },
+        ),
+    );
+
+    assert_eval_ctx(
+        &ctx,
+        FuncCall::builder("path").arg("main.hcl").build(),
+        Value::from("config/main.hcl"),
+    );
+
+    // Argument validation still happens before the closure is invoked.
+    let err = FuncCall::builder("path")
+        .arg(1)
+        .build()
+        .evaluate(&ctx)
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::FuncCall(..)));
+
+    // Cloned contexts share the same function instance.
+    let cloned = ctx.clone();
+    assert_eval_ctx(
+        &cloned,
+        FuncCall::builder("path").arg("other.hcl").build(),
+        Value::from("config/other.hcl"),
+    );
+
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn eval_lazy_func_call() {
+    // Returns the first argument that evaluates to a non-null value.
+    fn first(args: LazyFuncArgs, ctx: &Context) -> EvalResult<Value, String> {
+        for arg in args.iter() {
+            match arg.evaluate(ctx) {
+                Ok(Value::Null) | Err(_) => continue,
+                Ok(value) => return Ok(value),
+            }
+        }
+
+        Ok(Value::Null)
+    }
+
+    let mut ctx = Context::new();
+    ctx.declare_var("a", Value::Null);
+    ctx.declare_var("b", 2);
+    ctx.declare_func(
+        "first",
+        FuncDef::builder()
+            .param(ParamType::Any)
+            .variadic_param(ParamType::Any)
+            .build_lazy(first),
+    );
+
+    let expr = |input: &str| -> Expression {
+        let body = hcl::parse(&format!("expr = {input}")).unwrap();
+        body.into_attributes().next().unwrap().expr
+    };
+
+    assert_eval_ctx(&ctx, expr("first(undefined, a, b)"), Value::from(2));
+    assert_eval_ctx(&ctx, expr("first([null, 3]...)"), Value::from(3));
+
+    // The number of arguments is still validated.
+    let err = expr("first()").evaluate(&ctx).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::FuncCall(..)));
+
+    // Errors of arguments are left to the lazy function during partial evaluation.
+    let partial = expr("first([][0], unknown, b)")
+        .partial_evaluate(&ctx)
+        .unwrap();
+    assert_eq!(partial, expr("first([][0], unknown, 2)"));
+}
+
+#[test]
+fn eval_typed_func_params() {
+    use hcl::types::{ObjectType, Type};
+
+    // Returns the arguments unchanged to expose the conversions.
+    fn identity(args: FuncArgs) -> EvalResult<Value, String> {
+        Ok(Value::from_iter(args.into_values()))
+    }
+
+    let mut ctx = Context::new();
+    ctx.declare_func(
+        "identity",
+        FuncDef::builder()
+            .typed_param(Type::from(
+                ObjectType::new()
+                    .attr("port", Type::Number)
+                    .optional_attr("tls", Type::Bool),
+            ))
+            .typed_variadic_param(Type::set_of(Type::String))
+            .build(identity),
+    );
+
+    let expr = |input: &str| -> Expression {
+        let body = hcl::parse(&format!("expr = {input}")).unwrap();
+        body.into_attributes().next().unwrap().expr
+    };
+
+    assert_eval_ctx(
+        &ctx,
+        expr(r#"identity({ port = "80" }, [1, "1", true])"#),
+        Value::from_iter([
+            Value::from_iter([("port", Value::from(80)), ("tls", Value::Null)]),
+            Value::from_iter(["1", "true"]),
+        ]),
+    );
+
+    let err = expr(r#"identity({ port = "http" })"#)
+        .evaluate(&ctx)
+        .unwrap_err();
+    assert_eq!(
+        err.kind(),
+        &ErrorKind::FuncCall(
+            "identity".into(),
+            "invalid argument at position 0: .port: cannot convert \"http\" to number".into()
+        )
+    );
+
+    let err = expr("identity(null)").evaluate(&ctx).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::FuncCall(..)));
+}
+
+#[test]
+fn eval_ordered() {
+    // The `local` variable of the `for` expression in `doubled` shadows the namespace, so it
+    // does not reference all attributes.
+    let input = indoc! {r#"
+        doubled = [for local in local.numbers : local * 2]
+        scaled  = [for n in local.numbers : n * local.factor]
+        factor  = local.base + 1
+        numbers = [1, 2]
+        base    = 1
+        label   = "%{ for n in local.numbers }${n}%{ endfor }"
+
+        output "total" {
+          value = sum
+        }
+    "#};
+
+    let body: Body = hcl::parse(input).unwrap();
+    let mut ctx = Context::new();
+    ctx.declare_var("sum", 3);
+
+    let evaluated = OrderedEvaluator::with_namespace("local")
+        .evaluate(&body, &mut ctx)
+        .unwrap();
+
+    let expected = Body::builder()
+        .add_attribute(("doubled", vec![2, 4]))
+        .add_attribute(("scaled", vec![2, 4]))
+        .add_attribute(("factor", 2))
+        .add_attribute(("numbers", vec![1, 2]))
+        .add_attribute(("base", 1))
+        .add_attribute(("label", "12"))
+        .add_block(
+            hcl::Block::builder("output")
+                .add_label("total")
+                .add_attribute(("value", 3))
+                .build(),
+        )
+        .build();
+
+    assert_eq!(evaluated, expected);
+
+    // Further bodies are merged into the namespace.
+    let body: Body = hcl::parse("tripled = local.factor * 3").unwrap();
+    OrderedEvaluator::with_namespace("local")
+        .evaluate(&body, &mut ctx)
+        .unwrap();
+
+    assert_eval_ctx(
+        &ctx,
+        Traversal::builder(Variable::unchecked("local"))
+            .attr("tripled")
+            .build(),
+        Value::from(6),
+    );
+}
+
+#[test]
+fn eval_ordered_dynamic_index() {
+    // A dynamic index into the namespace depends on all other attributes, but not on itself.
+    let input = indoc! {r#"
+        c   = local[local.key]
+        a   = 1
+        key = "a"
+    "#};
+
+    let body: Body = hcl::parse(input).unwrap();
+    let evaluated = OrderedEvaluator::with_namespace("local")
+        .evaluate(&body, &mut Context::new())
+        .unwrap();
+
+    let expected = Body::builder()
+        .add_attribute(("c", 1))
+        .add_attribute(("a", 1))
+        .add_attribute(("key", "a"))
+        .build();
+
+    assert_eq!(evaluated, expected);
+}
+
+#[test]
+fn eval_ordered_multiple_dynamic_indexes() {
+    // Dynamic indexes do not depend on each other, so they do not form a cycle.
+    let input = indoc! {r#"
+        b = local[var.k2]
+        c = local[var.k3]
+        a = 1
+    "#};
+
+    let mut ctx = Context::new();
+    ctx.declare_var("var", hcl::value!({ k2 = "a" k3 = "a" }));
+
+    let body: Body = hcl::parse(input).unwrap();
+    let evaluated = OrderedEvaluator::with_namespace("local")
+        .evaluate(&body, &mut ctx)
+        .unwrap();
+
+    let expected = Body::builder()
+        .add_attribute(("b", 1))
+        .add_attribute(("c", 1))
+        .add_attribute(("a", 1))
+        .build();
+
+    assert_eq!(evaluated, expected);
+}
+
+#[test]
+fn eval_ordered_cycle() {
+    let body: Body = hcl::parse(indoc! {r#"
+        a = 1
+        b = c + a
+        c = [for x in d : x]
+        d = { key = b }
+    "#})
+    .unwrap();
+
+    let err = OrderedEvaluator::new()
+        .evaluate(&body, &mut Context::new())
+        .unwrap_err();
+
+    assert_eq!(
+        err.kind(),
+        &ErrorKind::ReferenceCycle(vec!["b".into(), "c".into(), "d".into()])
+    );
+    assert_eq!(err.to_string(), "reference cycle: `b` -> `c` -> `d` -> `b`");
+
+    let body: Body = hcl::parse("a = local.a").unwrap();
+    let err = OrderedEvaluator::with_namespace("local")
+        .evaluate(&body, &mut Context::new())
+        .unwrap_err();
+
+    assert_eq!(err.kind(), &ErrorKind::ReferenceCycle(vec!["a".into()]));
+}
+
+#[test]
+fn eval_var_resolver() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let traversals = Arc::new(Mutex::new(Vec::new()));
+    let (counter, seen) = (calls.clone(), traversals.clone());
+
+    let mut ctx = Context::new();
+    ctx.declare_var("name", "declared");
+    ctx.set_var_resolver(
+        move |name: &Identifier, traversal: Option<&Traversal>| -> Result<Option<Value>, String> {
+            counter.fetch_add(1, Ordering::Relaxed);
+            seen.lock().unwrap().push(traversal.cloned());
+
+            match name.as_str() {
+                "data" => Ok(Some(hcl::value!({ a = { b = 1 } }))),
+                "broken" => Err("backend unavailable".into()),
+                _ => Ok(None),
+            }
+        },
+    );
+
+    let traversal = Traversal::builder(Variable::unchecked("data"))
+        .attr("a")
+        .attr("b")
+        .build();
+
+    assert_eval_ctx(&ctx, traversal.clone(), Value::from(1));
+    assert_eq!(*traversals.lock().unwrap(), [Some(traversal.clone())]);
+
+    // Resolved values are cached.
+    assert_eval_ctx(
+        &ctx,
+        Traversal::builder(Variable::unchecked("data"))
+            .attr("a")
+            .build(),
+        Value::from_iter([("b", 1)]),
+    );
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    // Declared variables take precedence.
+    assert_eval_ctx(
+        &ctx,
+        Expression::from(Variable::unchecked("name")),
+        Value::from("declared"),
+    );
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    let err = Expression::from(Variable::unchecked("undefined"))
+        .evaluate(&ctx)
+        .unwrap_err();
+    assert_eq!(
+        err.kind(),
+        &ErrorKind::UndefinedVar(Identifier::unchecked("undefined"))
+    );
+
+    let err = Expression::from(Variable::unchecked("broken"))
+        .evaluate(&ctx)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "error resolving variable `broken`: backend unavailable in expression `broken`"
+    );
+
+    // Variables the resolver does not know are treated as unknown during partial evaluation.
+    let body = hcl::parse("a = data.a.b\nb = other.c").unwrap();
+    let partial = body.partial_evaluate(&ctx).unwrap();
+    assert_eq!(partial, hcl::parse("a = 1\nb = other.c").unwrap());
+
+    // Clearing the cache resolves variables anew.
+    let calls_before = calls.load(Ordering::Relaxed);
+    ctx.clear_resolver_cache();
+    assert_eval_ctx(&ctx, traversal, Value::from(1));
+    assert_eq!(calls.load(Ordering::Relaxed), calls_before + 1);
+}
+
+#[test]
+fn eval_shared_context() {
+    use std::sync::Arc;
+
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+    assert_send_sync::<Context<'static>>();
+
+    let mut base = Context::new();
+    base.declare_var("env", "prod");
+    base.declare_var("region", "eu");
+    base.declare_func(
+        "upper",
+        FuncDef::new(
+            |args: FuncArgs| -> EvalResult<Value, String> {
+                Ok(Value::from(args[0].as_str().unwrap().to_uppercase()))
+            },
+            [ParamType::String],
+        ),
+    );
+    let base = Arc::new(base);
+
+    let mut file = Context::with_base(base.clone());
+    file.declare_var("region", "us");
+    let file = Arc::new(file);
+
+    let mut block = Context::with_base(file.clone());
+    block.declare_var("name", "api");
+
+    let expr = TemplateExpr::from("${upper(env)}-${region}-${name}");
+    assert_eval_ctx(&block, expr.clone(), Value::from("PROD-us-api"));
+
+    // The base context is not affected by declarations in scopes on top of it.
+    assert_eval_ctx(
+        &base,
+        TemplateExpr::from("${env}-${region}"),
+        Value::from("prod-eu"),
+    );
+
+    let results: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = ["a", "b", "c"]
+            .into_iter()
+            .map(|name| {
+                let mut ctx = Context::with_base(file.clone());
+                ctx.declare_var("name", name);
+                let expr = expr.clone();
+                scope.spawn(move || expr.evaluate(&ctx).unwrap())
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    assert_eq!(
+        results,
+        [
+            Value::from("PROD-us-a"),
+            Value::from("PROD-us-b"),
+            Value::from("PROD-us-c")
+        ]
+    );
+}
+
 #[test]
 fn eval_template() {
     use std::str::FromStr;
@@ -380,8 +803,11 @@ fn expr_error_context() {
 
     assert_eq!(
         err.to_string(),
-        r#"eval error: undefined variable `cond` in expression `cond ? "yes" : "no"`"#,
-    )
+        r#"eval error: undefined variable `cond` in expression `cond ? "yes" : "no"` in line 2, col 5"#,
+    );
+
+    let location = err.location().unwrap();
+    assert_eq!((location.line, location.col), (2, 5));
 }
 
 #[test]
@@ -423,3 +849,286 @@ fn interpolation_unwrapping() {
         Value::from("true"),
     );
 }
+
+#[test]
+fn partial_eval() {
+    let input = indoc! {r#"
+        known     = a + 1
+        unknown   = a + b * 2
+        cond      = b ? a : a * 2
+        pick      = (a == 1) ? "one" : b
+        call      = add(a, b)
+        call_done = add(a, 41)
+        array     = [a, b, [for x in [1, 2]: x + b]]
+        object    = { (b) = a, foo = b.bar[a] }
+        for_obj   = { for k, v in { x = 1, y = 2 }: k => v + a }
+        tmpl      = "${b}"
+        tmpl_str  = "hello ${b}"
+        tmpl_done = "hello ${a}"
+        for_cond  = [for x in [1, 2]: x if x > b]
+
+        block "label" {
+          nested = b.attr
+        }
+    "#};
+
+    let mut ctx = Context::new();
+    ctx.declare_var("a", 1);
+    ctx.declare_func(
+        "add",
+        FuncDef::builder()
+            .params([ParamType::Number, ParamType::Number])
+            .build(|args: FuncArgs| -> EvalResult<Value, String> {
+                Ok(Value::Number(
+                    *args[0].as_number().unwrap() + *args[1].as_number().unwrap(),
+                ))
+            }),
+    );
+
+    let body = hcl::parse(input).unwrap();
+    let partial = body.partial_evaluate(&ctx).unwrap();
+
+    let expected = indoc! {r#"
+        known = 2
+        unknown = 1 + (b * 2)
+        cond = b ? 1 : 2
+        pick = "one"
+        call = add(1, b)
+        call_done = 42
+        array = [
+          1,
+          b,
+          [for x in [
+            1,
+            2
+          ] : x + b]
+        ]
+        object = {
+          (b) = 1
+          foo = b.bar[1]
+        }
+        for_obj = {
+          "x" = 2
+          "y" = 3
+        }
+        tmpl = b
+        tmpl_str = "hello ${b}"
+        tmpl_done = "hello 1"
+        for_cond = [for x in [
+          1,
+          2
+        ] : x if x > b]
+
+        block "label" {
+          nested = b.attr
+        }
+    "#};
+
+    assert_eq!(hcl::format::to_string(&partial).unwrap(), expected);
+
+    // Once all variables are known, the partially evaluated body evaluates to the same result as
+    // the original body.
+    let input = "value = [for x in xs: x * factor if x > min]";
+    let body = hcl::parse(input).unwrap();
+
+    let mut ctx = Context::new();
+    ctx.declare_var("xs", vec![1, 2, 3]);
+    let partial = body.partial_evaluate(&ctx).unwrap();
+
+    ctx.declare_var("factor", 10);
+    ctx.declare_var("min", 1);
+    assert_eq!(
+        partial.evaluate(&ctx).unwrap(),
+        body.evaluate(&ctx).unwrap()
+    );
+    assert_eq!(
+        partial.evaluate(&ctx).unwrap(),
+        Body::builder()
+            .add_attribute(("value", vec![20, 30]))
+            .build()
+    );
+}
+
+#[test]
+fn partial_eval_nested_for() {
+    // Elements of an unrolled `for` expression that depend on unknown values must not leak the
+    // `for` expression's variables into the result.
+    let body = hcl::parse("value = [for x in [1, 2]: [for y in unknown: x + y]]").unwrap();
+    let partial = body.partial_evaluate(&Context::new()).unwrap();
+
+    let mut ctx = Context::new();
+    ctx.declare_var("x", 100);
+    ctx.declare_var("unknown", vec![10]);
+
+    let expected = Body::builder()
+        .add_attribute(("value", vec![vec![11], vec![12]]))
+        .build();
+
+    assert_eq!(partial.evaluate(&ctx).unwrap(), expected);
+    assert_eq!(body.evaluate(&ctx).unwrap(), expected);
+
+    // The same applies to branches of conditionals that fail to evaluate for some elements.
+    let body = hcl::parse("value = [for x in [0, 1]: cond ? [10][x] : x]").unwrap();
+    let partial = body.partial_evaluate(&Context::new()).unwrap();
+
+    let mut ctx = Context::new();
+    ctx.declare_var("x", 0);
+    ctx.declare_var("cond", true);
+
+    assert!(body.evaluate(&ctx).is_err());
+    assert!(partial.evaluate(&ctx).is_err());
+}
+
+#[test]
+fn partial_eval_templates() {
+    let input = indoc! {r#"
+        mixed  = "${name}-${count}"
+        if_dir = "%{ if enabled }on%{ else }off%{ endif } ${name}"
+        unknown_if = "a %{ if name == "x" }x%{ endif } ${count}"
+        for_dir = "%{ for x in [1, 2] }${x}:${count},%{ endfor }${name}"
+        for_unknown = "%{ for x in xs }${x + y}%{ endfor }${count}"
+        strip = <<EOT
+        ${count}
+        %{~ if enabled ~}
+          ${name}
+        %{~ endif ~}
+        done
+        EOT
+        unwrap = "${name}${empty}"
+    "#};
+
+    let mut ctx = Context::new();
+    ctx.declare_var("count", 3);
+    ctx.declare_var("enabled", true);
+    ctx.declare_var("empty", "");
+    ctx.declare_var("xs", vec![1, 2]);
+
+    let body = hcl::parse(input).unwrap();
+    let partial = body.partial_evaluate(&ctx).unwrap();
+
+    let expected = indoc! {r#"
+        mixed = "${name}-3"
+        if_dir = "on ${name}"
+        unknown_if = "a %{ if name == "x" }x%{ endif } 3"
+        for_dir = "1:3,2:3,${name}"
+        for_unknown = "%{ for x in xs }${x + y}%{ endfor }3"
+        strip = "3  ${name}done
+        "
+        unwrap = "${name}${empty}"
+    "#};
+
+    assert_eq!(hcl::format::to_string(&partial).unwrap(), expected);
+
+    // Once all variables are known, the partially evaluated body evaluates to the same result as
+    // the original body.
+    ctx.declare_var("name", "x");
+    ctx.declare_var("y", 10);
+
+    assert_eq!(
+        partial.evaluate(&ctx).unwrap(),
+        body.evaluate(&ctx).unwrap()
+    );
+}
+
+#[test]
+fn partial_eval_errors() {
+    let ctx = Context::new();
+
+    // Errors unrelated to unknown values are still reported.
+    let body = hcl::parse("attr = undefined_func(a)").unwrap();
+    let err = body.partial_evaluate(&ctx).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        &ErrorKind::UndefinedFunc(Identifier::unchecked("undefined_func"))
+    );
+
+    let body = hcl::parse(r#"attr = 1 + "foo""#).unwrap();
+    assert!(body.partial_evaluate(&ctx).is_err());
+
+    // Errors in branches of conditionals with unknown condition are deferred.
+    let body = hcl::parse("attr = cond ? [][0] : 1").unwrap();
+    let partial = body.partial_evaluate(&ctx).unwrap();
+    assert_eq!(
+        hcl::format::to_string(&partial).unwrap(),
+        "attr = cond ? [][0] : 1\n"
+    );
+}
+
+#[test]
+fn eval_in_place() {
+    let input = r#"
+        a = 1 + 1
+        b = [1 + 2, foo, bar.baz]
+        c = { (key) = "value", d = 2 * 2 }
+
+        block "label" {
+          e = upper("e")
+          f = true
+        }
+    "#;
+
+    let mut ctx = Context::new();
+    ctx.declare_var("bar", Value::from_iter([("baz", 4)]));
+
+    let mut body = hcl::parse(input).unwrap();
+    let errors = body.evaluate_in_place(&ctx).unwrap_err();
+
+    assert_eq!(errors.len(), 3);
+
+    let kinds: Vec<_> = errors.iter().map(|err| err.kind().clone()).collect();
+    assert_eq!(
+        kinds,
+        [
+            ErrorKind::UndefinedVar(Identifier::unchecked("foo")),
+            ErrorKind::UndefinedVar(Identifier::unchecked("key")),
+            ErrorKind::UndefinedFunc(Identifier::unchecked("upper")),
+        ]
+    );
+
+    let paths: Vec<_> = errors.iter().map(|err| err.path().to_vec()).collect();
+    assert_eq!(
+        paths,
+        [
+            vec![PathSegment::Attribute(Identifier::unchecked("b"))],
+            vec![PathSegment::Attribute(Identifier::unchecked("c"))],
+            vec![
+                PathSegment::Block(
+                    Identifier::unchecked("block"),
+                    vec![BlockLabel::from("label")]
+                ),
+                PathSegment::Attribute(Identifier::unchecked("e")),
+            ],
+        ]
+    );
+
+    let expected = indoc! {r#"
+        a = 2
+        b = [
+          3,
+          foo,
+          4
+        ]
+        c = {
+          (key) = "value"
+          d = 4
+        }
+
+        block "label" {
+          e = upper("e")
+          f = true
+        }
+    "#};
+
+    assert_eq!(hcl::format::to_string(&body).unwrap(), expected);
+
+    // Successful evaluation leaves nothing unevaluated.
+    let mut body = hcl::parse("a = 1 + 1\nb = bar.baz").unwrap();
+    body.evaluate_in_place(&ctx).unwrap();
+    assert_eq!(
+        body,
+        Body::builder()
+            .add_attribute(("a", 2))
+            .add_attribute(("b", 4))
+            .build()
+    );
+}
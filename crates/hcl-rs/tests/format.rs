@@ -5,7 +5,8 @@ use hcl::expr::{
     BinaryOp, BinaryOperator, Conditional, Expression, ForExpr, FuncCall, Heredoc,
     HeredocStripMode, Traversal, TraversalOperator, Variable,
 };
-use hcl::format::Formatter;
+use hcl::format::{BodyOrder, Format, Formatter};
+use hcl::structure::{Attribute, Block, Comment, CommentStyle};
 use hcl::template::{ForDirective, IfDirective, Strip, Template};
 use hcl::Identifier;
 use indoc::indoc;
@@ -74,6 +75,300 @@ fn compact_objects() {
     );
 }
 
+#[test]
+fn align_attributes() {
+    let body = hcl::body!({
+        name = "web"
+        instance_type = "t2.micro"
+        tags = {
+            env = "prod"
+            "cost center" = 42
+            empty = {}
+        }
+        count = 1
+        user_data = (Heredoc::new(Identifier::unchecked("EOT"), "echo hello\n"))
+        ami = "ami-a1b2c3d4"
+        network_interface {
+            device_index = 0
+            network_interface_id = "eni-1234"
+        }
+        monitoring = true
+    });
+
+    assert_format_builder(
+        Formatter::builder().align_attributes(true),
+        body,
+        indoc! {r#"
+            name          = "web"
+            instance_type = "t2.micro"
+            tags = {
+              env           = "prod"
+              "cost center" = 42
+              empty         = {}
+            }
+            count     = 1
+            user_data = <<EOT
+            echo hello
+            EOT
+            ami       = "ami-a1b2c3d4"
+
+            network_interface {
+              device_index         = 0
+              network_interface_id = "eni-1234"
+            }
+
+            monitoring = true
+        "#},
+    );
+
+    assert_format_builder(
+        Formatter::builder().align_attributes(true).compact(true),
+        hcl::body!({
+            a = { foo = 1, barbaz = 2 }
+            bcd = [1, 2]
+        }),
+        indoc! {r#"
+            a   = { foo = 1, barbaz = 2 }
+            bcd = [1, 2]
+        "#},
+    );
+}
+
+#[test]
+fn max_width() {
+    let cidrsubnet = |netnum: u64| {
+        FuncCall::builder("cidrsubnet")
+            .arg(Variable::unchecked("vpc_cidr"))
+            .arg(8)
+            .arg(netnum)
+            .build()
+    };
+
+    let body = hcl::body!({
+        tags = { env = "prod", owner = "ops" }
+        subnets = [(cidrsubnet(1)), (cidrsubnet(2))]
+        policy = (FuncCall::builder("jsonencode")
+            .arg(hcl::expression!({
+                Version = "2012-10-17"
+                Statement = [{ Action = "s3:GetObject", Effect = "Allow" }]
+            }))
+            .build())
+        names = (Conditional::new(
+            Variable::unchecked("enabled"),
+            hcl::expression!(["aaaaaaaaaaaaaaaaaaaaaaaaa", "bbbbbbbbbbbbbbbbbbbbbbbbb"]),
+            hcl::expression!([]),
+        ))
+        now = (FuncCall::builder("timestamp").build())
+        block {
+            all = (FuncCall::builder("concat")
+                .arg(Variable::unchecked("aaaaaaaaaaaaaaaaaaaaaaa"))
+                .arg(Variable::unchecked("bbbbbbbbbbbbbbbbbbbbbbb"))
+                .expand_final(true)
+                .build())
+        }
+    });
+
+    assert_format_builder(
+        Formatter::builder().prefer_ident_keys(true).max_width(52),
+        &body,
+        indoc! {r#"
+            tags = { env = "prod", owner = "ops" }
+            subnets = [
+              cidrsubnet(vpc_cidr, 8, 1),
+              cidrsubnet(vpc_cidr, 8, 2)
+            ]
+            policy = jsonencode(
+              {
+                Version = "2012-10-17"
+                Statement = [
+                  { Action = "s3:GetObject", Effect = "Allow" }
+                ]
+              },
+            )
+            names = enabled ? [
+              "aaaaaaaaaaaaaaaaaaaaaaaaa",
+              "bbbbbbbbbbbbbbbbbbbbbbbbb"
+            ] : []
+            now = timestamp()
+
+            block {
+              all = concat(
+                aaaaaaaaaaaaaaaaaaaaaaa,
+                bbbbbbbbbbbbbbbbbbbbbbb...
+              )
+            }
+        "#},
+    );
+
+    // Everything fits into a wide line.
+    assert_format_builder(
+        Formatter::builder().max_width(120),
+        hcl::body!({ subnets = [(cidrsubnet(1)), (cidrsubnet(2))] }),
+        "subnets = [cidrsubnet(vpc_cidr, 8, 1), cidrsubnet(vpc_cidr, 8, 2)]\n",
+    );
+}
+
+#[test]
+fn prefer_heredocs() {
+    let body = hcl::body!({
+        script = "#!/bin/bash\necho \"hello\"\n\nexit 0\n"
+        indented = "  foo\n    bar\n"
+        collision = "EOT\n  EOT1\nbar\n"
+        single_line = "foo\n"
+        no_trailing_newline = "foo\nbar"
+        control_chars = "foo\r\nbar\n"
+        nested = {
+            text = "foo\nbar\n"
+        }
+        list = ["foo\nbar\n"]
+    });
+
+    let expected = indoc! {r#"
+        script = <<-EOT
+          #!/bin/bash
+          echo "hello"
+
+          exit 0
+        EOT
+        indented = <<EOT
+          foo
+            bar
+        EOT
+        collision = <<-EOT2
+          EOT
+            EOT1
+          bar
+        EOT2
+        single_line = "foo\n"
+        no_trailing_newline = "foo\nbar"
+        control_chars = "foo\r\nbar\n"
+        nested = {
+          text = <<-EOT
+            foo
+            bar
+          EOT
+        }
+        list = [
+          "foo\nbar\n"
+        ]
+    "#};
+
+    assert_format_builder(Formatter::builder().prefer_heredocs(true), &body, expected);
+
+    assert_eq!(
+        hcl::from_str::<hcl::Value>(expected).unwrap(),
+        hcl::from_body::<hcl::Value>(body).unwrap()
+    );
+}
+
+#[test]
+fn prefer_heredocs_roundtrip() {
+    let strings = [
+        "#!/bin/bash\necho \"hello\"\n\nexit 0\n",
+        "  foo\n    bar\n",
+        "EOT\n  EOT1\nbar\n",
+        "a ${x}\nb\n",
+        "a $${x}\nb\n",
+        "#!/bin/bash\necho ${HOME} $${HOME}\n%%{ literal }\n",
+    ];
+
+    let mut formatter = Formatter::builder().prefer_heredocs(true).build_vec();
+
+    for string in strings {
+        let body = hcl::body!({ value = (string) });
+        let formatted = body.format_string(&mut formatter).unwrap();
+        let parsed: hcl::Value = hcl::from_str(&formatted).unwrap();
+
+        assert_eq!(parsed, hcl::value!({ value = (string) }), "{formatted}");
+
+        // `${` and `$${` would not be distinguishable in a heredoc, so these strings are quoted.
+        let templated = string.contains("${") || string.contains("%{");
+        assert_eq!(formatted.contains("<<"), !templated, "{formatted}");
+    }
+}
+
+#[test]
+fn body_order() {
+    let body = hcl::body!({
+        zone = "b"
+        resource "b" "a" {
+            z = 1
+            a = 2
+        }
+        resource "a" "b" {}
+        data "c" {}
+        resource "a" "a" {}
+        name = "foo"
+        count = 2
+    });
+
+    assert_format_builder(
+        Formatter::builder().body_order(BodyOrder::canonical()),
+        &body,
+        indoc! {r#"
+            count = 2
+            name = "foo"
+            zone = "b"
+
+            data "c" {}
+
+            resource "a" "a" {}
+
+            resource "a" "b" {}
+
+            resource "b" "a" {
+              a = 2
+              z = 1
+            }
+        "#},
+    );
+
+    assert_format_builder(
+        Formatter::builder().body_order(BodyOrder::new().sort_attributes(true).sort_blocks(true)),
+        &body,
+        indoc! {r#"
+            zone = "b"
+
+            data "c" {}
+
+            resource "a" "a" {}
+
+            resource "a" "b" {}
+
+            resource "b" "a" {
+              a = 2
+              z = 1
+            }
+
+            count = 2
+            name = "foo"
+        "#},
+    );
+
+    assert_format_builder(
+        Formatter::builder()
+            .body_order(BodyOrder::new().attributes_first(true))
+            .body_order_at_depth(1, BodyOrder::canonical()),
+        &body,
+        indoc! {r#"
+            zone = "b"
+            name = "foo"
+            count = 2
+
+            resource "b" "a" {
+              a = 2
+              z = 1
+            }
+
+            resource "a" "b" {}
+
+            data "c" {}
+
+            resource "a" "a" {}
+        "#},
+    );
+}
+
 #[test]
 fn compact_func_args() {
     assert_format(
@@ -283,3 +578,95 @@ fn traversal() {
 fn empty_block() {
     assert_format(hcl::block!(empty {}), "empty {}\n");
 }
+
+#[test]
+fn comments() {
+    let body = hcl::Body::builder()
+        .add_attribute(
+            Attribute::new("name", "web")
+                .with_leading_comment("The name.\nMust be unique.")
+                .with_trailing_comment(
+                    Comment::new("required").with_style(CommentStyle::DoubleSlash),
+                ),
+        )
+        .add_attribute(Attribute::new("count", 1).with_trailing_comment(""))
+        .add_block(
+            Block::builder("network")
+                .add_attribute(
+                    Attribute::new("cidr", "10.0.0.0/16")
+                        .with_leading_comment(
+                            Comment::new("").with_style(CommentStyle::DoubleSlash),
+                        )
+                        .with_leading_comment(
+                            Comment::new("spans\nlines */").with_style(CommentStyle::SlashStar),
+                        ),
+                )
+                .build()
+                .with_leading_comment(
+                    Comment::new("Networking").with_style(CommentStyle::SlashStar),
+                )
+                .with_trailing_comment("end of network"),
+        )
+        .build();
+
+    let expected = indoc! {r#"
+        # The name.
+        # Must be unique.
+        name = "web" // required
+        count = 1 #
+
+        /* Networking */
+        network {
+          //
+          /* spans
+             lines * / */
+          cidr = "10.0.0.0/16"
+        } # end of network
+    "#};
+
+    assert_format(&body, expected);
+
+    // The comments are not part of the data model.
+    assert_eq!(hcl::parse(expected).unwrap(), body);
+
+    assert_format_builder(
+        Formatter::builder().align_attributes(true),
+        hcl::Body::builder()
+            .add_attribute(Attribute::new("a", 1).with_trailing_comment("one"))
+            .add_attribute(Attribute::new("bcd", 2).with_leading_comment("two"))
+            .build(),
+        indoc! {r#"
+            a = 1 # one
+            # two
+            bcd = 2
+        "#},
+    );
+}
+
+#[test]
+fn roundtrip_testdata() {
+    for test in testdata::load().unwrap() {
+        let body = hcl::parse(&test.input).unwrap();
+        let formatted = hcl::format::to_string(&body).unwrap();
+
+        assert_eq!(
+            hcl::parse(&formatted).unwrap(),
+            body,
+            "{}",
+            test.path.display()
+        );
+
+        let mut formatter = Formatter::builder()
+            .align_attributes(true)
+            .max_width(80)
+            .build_vec();
+        let formatted = body.format_string(&mut formatter).unwrap();
+
+        assert_eq!(
+            hcl::parse(&formatted).unwrap(),
+            body,
+            "{}",
+            test.path.display()
+        );
+    }
+}
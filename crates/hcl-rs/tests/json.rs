@@ -0,0 +1,361 @@
+#![cfg(feature = "json")]
+
+use hcl::eval::{Context, Evaluate};
+use hcl::json::{self, Schema};
+use hcl::{Block, Body, Expression, ObjectKey, Value};
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn attributes() {
+    let input = r#"{
+        "null": null,
+        "bool": true,
+        "number": 1.5,
+        "string": "foo",
+        "array": [1, "two"],
+        "object": {"a": 1, "not an ident": 2},
+        "//": "this is a comment"
+    }"#;
+
+    let body = json::parse(input, &Schema::new()).unwrap();
+
+    let expected = indoc! {r#"
+        null = null
+        bool = true
+        number = 1.5
+        string = "foo"
+        array = [
+          1,
+          "two"
+        ]
+        object = {
+          a = 1
+          "not an ident" = 2
+        }
+    "#};
+
+    assert_eq!(hcl::to_string(&body).unwrap(), expected);
+}
+
+#[test]
+fn templates() {
+    let input = r#"{
+        "name": "${var.name}",
+        "greeting": "Hello, \"${var.name}\"!\n",
+        "escaped": "$${literal}",
+        "upper": "${upper(\"x\")}",
+        "cond": "%{ if var.enabled }on%{ else }off%{ endif }",
+        "object": {"${var.name}": 1}
+    }"#;
+
+    let body = json::parse(input, &Schema::new()).unwrap();
+
+    let expected = indoc! {r#"
+        name = "${var.name}"
+        greeting = "Hello, \"${var.name}\"!\n"
+        escaped = "$${literal}"
+        upper = "${upper("x")}"
+        cond = "%{ if var.enabled }on%{ else }off%{ endif }"
+        object = {
+          "${var.name}" = 1
+        }
+    "#};
+
+    assert_eq!(hcl::to_string(&body).unwrap(), expected);
+
+    let mut ctx = Context::new();
+    ctx.declare_var(
+        "var",
+        Value::from_iter([
+            ("name", Value::from("world")),
+            ("enabled", Value::from(true)),
+        ]),
+    );
+    ctx.declare_func(
+        "upper",
+        hcl::eval::FuncDef::builder()
+            .param(hcl::eval::ParamType::String)
+            .build(|args: hcl::eval::FuncArgs| -> Result<Value, String> {
+                Ok(Value::from(args[0].as_str().unwrap().to_uppercase()))
+            }),
+    );
+
+    let evaluated = body.evaluate(&ctx).unwrap();
+    let attr = |key: &str| {
+        evaluated
+            .attributes()
+            .find(|attr| attr.key() == key)
+            .map(|attr| Value::from(attr.expr().clone()))
+            .unwrap()
+    };
+
+    assert_eq!(attr("name"), Value::from("world"));
+    assert_eq!(attr("upper"), Value::from("X"));
+    assert_eq!(attr("cond"), Value::from("on"));
+    assert_eq!(attr("object"), Value::from_iter([("world", 1)]));
+
+    let err = json::parse(r#"{"invalid": "${"}"#, &Schema::new()).unwrap_err();
+    assert!(err.to_string().contains("expected"), "{err}");
+}
+
+#[test]
+fn blocks() {
+    let input = r#"{
+        "variable": {
+            "a": {"default": 1},
+            "b": [{"default": 2}, {"default": 3}]
+        },
+        "resource": {
+            "aws_instance": {
+                "web": {
+                    "ami": "ami-123",
+                    "lifecycle": {"create_before_destroy": true},
+                    "tags": {"lifecycle": "not a block"}
+                }
+            }
+        },
+        "locals": [{"a": 1}, {"b": 2}],
+        "terraform": {}
+    }"#;
+
+    let schema = Schema::new()
+        .add_block("variable", 1)
+        .add_nested_block("resource", 2, Schema::new().add_block("lifecycle", 0))
+        .add_block("locals", 0)
+        .add_block("terraform", 0);
+
+    let body = json::parse(input, &schema).unwrap();
+
+    let expected = Body::builder()
+        .add_block(
+            Block::builder("variable")
+                .add_label("a")
+                .add_attribute(("default", 1))
+                .build(),
+        )
+        .add_block(
+            Block::builder("variable")
+                .add_label("b")
+                .add_attribute(("default", 2))
+                .build(),
+        )
+        .add_block(
+            Block::builder("variable")
+                .add_label("b")
+                .add_attribute(("default", 3))
+                .build(),
+        )
+        .add_block(
+            Block::builder("resource")
+                .add_labels(["aws_instance", "web"])
+                .add_attribute(("ami", "ami-123"))
+                .add_block(
+                    Block::builder("lifecycle")
+                        .add_attribute(("create_before_destroy", true))
+                        .build(),
+                )
+                .add_attribute(("tags", hcl::expression!({ lifecycle = "not a block" })))
+                .build(),
+        )
+        .add_block(Block::builder("locals").add_attribute(("a", 1)).build())
+        .add_block(Block::builder("locals").add_attribute(("b", 2)).build())
+        .add_block(Block::builder("terraform").build())
+        .build();
+
+    assert_eq!(body, expected);
+}
+
+#[test]
+fn errors() {
+    let schema = Schema::new().add_block("service", 1);
+
+    let err = json::parse(r#"{"service": "foo"}"#, &schema).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "expected object or array for block `service`, got string"
+    );
+
+    let err = json::parse(r#"{"service": {"api": 1}}"#, &schema).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "expected object or array for block `service`, got number"
+    );
+
+    let err = json::parse("[]", &schema).unwrap_err();
+    assert_eq!(err.to_string(), "expected JSON object as top-level value");
+
+    let err = json::parse("{\n  \"a\": 1,\n}", &schema).unwrap_err();
+    let location = err.location().unwrap();
+    assert_eq!((location.line, location.col), (3, 1));
+}
+
+#[test]
+fn deserialize() {
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Config {
+        name: String,
+        service: Vec<Service>,
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Service {
+        port: u16,
+    }
+
+    let input = r#"{"name": "app", "service": [{"port": 80}, {"port": 443}]}"#;
+    let schema = Schema::new().add_block("service", 0);
+
+    let config: Config = json::from_str(input, &schema).unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            name: "app".into(),
+            service: vec![Service { port: 80 }, Service { port: 443 }],
+        }
+    );
+}
+
+#[test]
+fn serialize() {
+    let input = indoc! {r#"
+        // comments are dropped
+        name    = "literal $${not_a_template} and %%{ if true }"
+        greet   = "Hello, \"${var.name}\"!"
+        upper   = upper(var.name)
+        cond    = var.enabled ? 1 : 2
+        objects = { (var.key) = [null, true, 1.5] }
+        doc     = <<-EOT
+          indented
+            heredoc
+        EOT
+
+        variable "a" {
+          default = 1
+        }
+
+        variable "b" {
+          default = 2
+        }
+
+        locals {
+          a = 1
+        }
+
+        locals {
+          b = 2
+        }
+    "#};
+
+    let body = hcl::parse(input).unwrap();
+
+    let expected = serde_json::json!({
+        "name": "literal $${not_a_template} and %%{ if true }",
+        "greet": "Hello, \"${var.name}\"!",
+        "upper": "${upper(var.name)}",
+        "cond": "${var.enabled ? 1 : 2}",
+        "objects": {"${var.key}": [null, true, 1.5]},
+        "doc": "indented\n  heredoc\n",
+        "variable": {
+            "a": {"default": 1},
+            "b": {"default": 2}
+        },
+        "locals": [{"a": 1}, {"b": 2}]
+    });
+
+    let json = json::to_string(&body).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value, expected);
+
+    let pretty = json::to_string_pretty(&body).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&pretty).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn roundtrip() {
+    let input = indoc! {r#"
+        name = "literal $${escaped}"
+        greet = "Hello, \"${var.name}\"!\n"
+        call = "${upper("a\"b")}"
+        expr = var.a + 1
+
+        resource "aws_instance" "web" {
+          ami = "ami-123"
+
+          lifecycle {
+            create_before_destroy = true
+          }
+        }
+
+        locals {
+          a = 1
+        }
+
+        locals {
+          b = 2
+        }
+    "#};
+
+    let schema = Schema::new()
+        .add_nested_block("resource", 2, Schema::new().add_block("lifecycle", 0))
+        .add_block("locals", 0);
+
+    let body = hcl::parse(input).unwrap();
+    let json = json::to_string(&body).unwrap();
+    let parsed = json::parse(&json, &schema).unwrap();
+
+    let expected = indoc! {r#"
+        name = "literal $${escaped}"
+        greet = "Hello, \"${var.name}\"!\n"
+        call = "${upper("a\"b")}"
+        expr = "${var.a + 1}"
+
+        resource "aws_instance" "web" {
+          ami = "ami-123"
+
+          lifecycle {
+            create_before_destroy = true
+          }
+        }
+
+        locals {
+          a = 1
+        }
+
+        locals {
+          b = 2
+        }
+    "#};
+
+    assert_eq!(hcl::to_string(&parsed).unwrap(), expected);
+}
+
+#[test]
+fn roundtrip_literal_strings() {
+    let body = Body::builder()
+        .add_attribute(("a", "literal ${not_a_var} and %{ if x }"))
+        .add_attribute((
+            "b",
+            Expression::from_iter([(ObjectKey::from(Expression::from("${key}")), "$${value}")]),
+        ))
+        .build();
+
+    let json = json::to_string(&body).unwrap();
+    let parsed = json::parse(&json, &Schema::new()).unwrap();
+
+    // The literals are not parsed as templates.
+    assert!(parsed.evaluate(&Context::new()).is_ok());
+
+    let expected = indoc! {r#"
+        a = "literal $${not_a_var} and %%{ if x }"
+        b = {
+          "$${key}" = "$${value}"
+        }
+    "#};
+
+    assert_eq!(hcl::to_string(&parsed).unwrap(), expected);
+}
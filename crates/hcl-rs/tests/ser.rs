@@ -199,3 +199,29 @@ fn identifiers_with_hyphens() {
         "hyphen-ated = null\n",
     );
 }
+
+#[test]
+fn deterministic_body_order() {
+    use hcl::format::{BodyOrder, Formatter};
+    use hcl::ser::Serializer;
+    use std::collections::HashMap;
+
+    let tags: HashMap<&str, &str> = [("d", "z"), ("b", "x"), ("a", "w"), ("c", "y")]
+        .into_iter()
+        .collect();
+
+    let formatter = Formatter::builder()
+        .body_order(BodyOrder::canonical())
+        .build_vec();
+    let mut serializer = Serializer::with_formatter(formatter);
+
+    assert_eq!(
+        serializer.serialize_string(&tags).unwrap(),
+        indoc! {r#"
+            a = "w"
+            b = "x"
+            c = "y"
+            d = "z"
+        "#}
+    );
+}
@@ -0,0 +1,264 @@
+#![cfg(feature = "stdlib")]
+
+use hcl::eval::{Context, ErrorKind, Evaluate};
+use hcl::{Expression, Value};
+use pretty_assertions::assert_eq;
+
+#[track_caller]
+fn parse_expr(input: &str) -> Expression {
+    let body = hcl::parse(&format!("expr = {input}")).unwrap();
+    body.into_attributes().next().unwrap().expr
+}
+
+#[track_caller]
+fn eval(input: &str) -> Value {
+    eval_ctx(&Context::with_stdlib(), input)
+}
+
+#[track_caller]
+fn eval_ctx(ctx: &Context, input: &str) -> Value {
+    let expr = parse_expr(input);
+    expr.evaluate(ctx).unwrap()
+}
+
+#[track_caller]
+fn eval_error(input: &str) -> String {
+    let expr = parse_expr(input);
+    let err = expr.evaluate(&Context::with_stdlib()).unwrap_err();
+
+    match err.kind() {
+        ErrorKind::FuncCall(_, msg) => msg.clone(),
+        other => panic!("expected function call error, got: {other:?}"),
+    }
+}
+
+#[test]
+fn numeric() {
+    assert_eq!(eval("abs(-1.5)"), Value::from(1.5));
+    assert_eq!(eval("abs(3)"), Value::from(3));
+    assert_eq!(eval("ceil(1.2)"), Value::from(2));
+    assert_eq!(eval("floor(-1.2)"), Value::from(-2));
+    assert_eq!(eval("signum(-7)"), Value::from(-1));
+    assert_eq!(eval("signum(0)"), Value::from(0));
+    assert_eq!(eval("max(3, 12.5, 9)"), Value::from(12.5));
+    assert_eq!(eval("min(3, -2, 9)"), Value::from(-2));
+    assert_eq!(eval("max([1, 5, 2]...)"), Value::from(5));
+    assert_eq!(eval_error("min()"), "must pass at least one number");
+}
+
+#[test]
+fn strings() {
+    assert_eq!(eval(r#"upper("hello")"#), Value::from("HELLO"));
+    assert_eq!(eval(r#"lower("HeLLo")"#), Value::from("hello"));
+    assert_eq!(eval(r#"title("hello world")"#), Value::from("Hello World"));
+    assert_eq!(eval(r#"chomp("hello\n\r\n")"#), Value::from("hello"));
+    assert_eq!(eval(r#"trimspace("  hello\n")"#), Value::from("hello"));
+    assert_eq!(eval(r#"trim("?!hello?!", "!?")"#), Value::from("hello"));
+    assert_eq!(
+        eval(r#"trimprefix("helloworld", "hello")"#),
+        Value::from("world")
+    );
+    assert_eq!(
+        eval(r#"trimsuffix("helloworld", "world")"#),
+        Value::from("hello")
+    );
+    assert_eq!(eval(r#"strrev("hello")"#), Value::from("olleh"));
+    assert_eq!(
+        eval(r#"replace("1 + 2 + 3", "+", "-")"#),
+        Value::from("1 - 2 - 3")
+    );
+    assert_eq!(eval(r#"substr("hello world", 1, 4)"#), Value::from("ello"));
+    assert_eq!(
+        eval(r#"substr("hello world", -5, -1)"#),
+        Value::from("world")
+    );
+    assert_eq!(eval(r#"substr("hello", 3, 10)"#), Value::from("lo"));
+    assert_eq!(
+        eval(r#"split(",", "foo,bar,baz")"#),
+        Value::from_iter(["foo", "bar", "baz"])
+    );
+    assert_eq!(eval(r#"split(",", "")"#), Value::Array(vec![]));
+    assert_eq!(
+        eval(r#"join(", ", ["foo", "bar"], ["baz"])"#),
+        Value::from("foo, bar, baz")
+    );
+    assert_eq!(eval_error(r#"join(",")"#), "at least one list is required");
+}
+
+#[test]
+fn format() {
+    assert_eq!(
+        eval(r#"format("Hello, %s!", "Ander")"#),
+        Value::from("Hello, Ander!")
+    );
+    assert_eq!(
+        eval(r#"format("There are %d lights", 4)"#),
+        Value::from("There are 4 lights")
+    );
+    assert_eq!(eval(r#"format("%.2f", 3.14159)"#), Value::from("3.14"));
+    assert_eq!(
+        eval(r#"format("%5d|%-5d|%05d", 1, 2, -3)"#),
+        Value::from("    1|2    |-0003")
+    );
+    assert_eq!(eval(r#"format("%+d", 42)"#), Value::from("+42"));
+    assert_eq!(eval(r#"format("%q", "a\"b")"#), Value::from(r#""a\"b""#));
+    assert_eq!(
+        eval(r#"format("%t %v %v", true, 1, "x")"#),
+        Value::from("true 1 x")
+    );
+    assert_eq!(eval(r#"format("%.3s", "abcdef")"#), Value::from("abc"));
+    assert_eq!(eval(r#"format("100%%")"#), Value::from("100%"));
+
+    assert_eq!(
+        eval_error(r#"format("%s %s", "a")"#),
+        "not enough arguments for %s at index 1"
+    );
+    assert_eq!(
+        eval_error(r#"format("%s", "a", "b")"#),
+        "too many arguments; only 1 used by format string"
+    );
+    assert_eq!(
+        eval_error(r#"format("%d", 1.5)"#),
+        "unsupported value for %d: `1.5`"
+    );
+    assert_eq!(
+        eval_error(r#"format("%x", 1)"#),
+        "unsupported format verb %x"
+    );
+}
+
+#[test]
+fn collections() {
+    assert_eq!(eval(r#"length("héllo")"#), Value::from(5));
+    assert_eq!(eval("length([1, 2, 3])"), Value::from(3));
+    assert_eq!(eval("length({a = 1})"), Value::from(1));
+    assert_eq!(eval("keys({b = 1, a = 2})"), Value::from_iter(["a", "b"]));
+    assert_eq!(eval("values({b = 1, a = 2})"), Value::from_iter([2, 1]));
+    assert_eq!(
+        eval("merge({a = 1, b = 2}, null, {b = 3, c = 4})"),
+        Value::from_iter([("a", 1), ("b", 3), ("c", 4)])
+    );
+    assert_eq!(eval(r#"lookup({a = 1}, "a")"#), Value::from(1));
+    assert_eq!(eval(r#"lookup({a = 1}, "b", 2)"#), Value::from(2));
+    assert_eq!(
+        eval_error(r#"lookup({a = 1}, "b")"#),
+        "lookup failed to find key `b`"
+    );
+    assert_eq!(eval("concat([1, 2], [], [3])"), Value::from_iter([1, 2, 3]));
+    assert_eq!(eval(r#"coalesce(null, "a", "b")"#), Value::from("a"));
+    assert_eq!(eval_error("coalesce(null)"), "no non-null arguments");
+    assert_eq!(
+        eval("coalescelist([], null, [1], [2])"),
+        Value::from_iter([1])
+    );
+    assert_eq!(
+        eval(r#"compact(["a", "", null, "b"])"#),
+        Value::from_iter(["a", "b"])
+    );
+    assert_eq!(eval("contains([1, 2], 2)"), Value::from(true));
+    assert_eq!(eval("distinct([1, 2, 1, 3])"), Value::from_iter([1, 2, 3]));
+    assert_eq!(eval("element([1, 2, 3], 4)"), Value::from(2));
+    assert_eq!(
+        eval("flatten([1, [2, [3]], []])"),
+        Value::from_iter([1, 2, 3])
+    );
+    assert_eq!(eval(r#"index(["a", "b"], "b")"#), Value::from(1));
+    assert_eq!(eval("reverse([1, 2, 3])"), Value::from_iter([3, 2, 1]));
+    assert_eq!(eval("slice([1, 2, 3, 4], 1, 3)"), Value::from_iter([2, 3]));
+    assert_eq!(
+        eval(r#"sort(["c", "a", "b"])"#),
+        Value::from_iter(["a", "b", "c"])
+    );
+    assert_eq!(
+        eval(r#"zipmap(["a", "b"], [1, 2])"#),
+        Value::from_iter([("a", 1), ("b", 2)])
+    );
+}
+
+#[test]
+fn range() {
+    assert_eq!(eval("range(3)"), Value::from_iter([0, 1, 2]));
+    assert_eq!(eval("range(1, 4)"), Value::from_iter([1, 2, 3]));
+    assert_eq!(eval("range(4, 1)"), Value::from_iter([4, 3, 2]));
+    assert_eq!(
+        eval("range(0, 1, 0.25)"),
+        Value::from_iter([0.0, 0.25, 0.5, 0.75])
+    );
+    assert_eq!(eval_error("range(0, 1, 0)"), "step must not be zero");
+    assert_eq!(
+        eval_error("range(0, 1, -1)"),
+        "step must be positive when start is less than limit"
+    );
+    assert!(eval_error("range(2000)").starts_with("more than 1024 values were generated"));
+}
+
+#[test]
+fn encoding() {
+    assert_eq!(
+        eval(r#"jsonencode({a = [1, true, null], b = "c"})"#),
+        Value::from(r#"{"a":[1,true,null],"b":"c"}"#)
+    );
+    assert_eq!(
+        eval(r#"jsondecode("{\"a\": [1, 2.5]}")"#),
+        Value::from_iter([("a", Value::from_iter([Value::from(1), Value::from(2.5)]))])
+    );
+}
+
+#[test]
+fn type_conversion() {
+    assert_eq!(eval(r#"tobool("true")"#), Value::from(true));
+    assert_eq!(eval("tobool(null)"), Value::Null);
+    assert_eq!(eval(r#"tonumber("1.5")"#), Value::from(1.5));
+    assert_eq!(eval("tostring(42)"), Value::from("42"));
+    assert_eq!(eval("tostring(false)"), Value::from("false"));
+    assert_eq!(
+        eval_error(r#"tonumber("foo")"#),
+        "cannot convert `foo` to number"
+    );
+}
+
+#[test]
+fn error_handling() {
+    let mut ctx = Context::with_stdlib();
+    ctx.declare_var("config", Value::from_iter([("port", 8080)]));
+
+    assert_eq!(eval_ctx(&ctx, "try(config.port, 80)"), Value::from(8080));
+    assert_eq!(
+        eval_ctx(&ctx, "try(config.host, \"localhost\")"),
+        Value::from("localhost")
+    );
+    assert_eq!(eval_ctx(&ctx, "try(tonumber(\"foo\"), 0)"), Value::from(0));
+    assert_eq!(eval_ctx(&ctx, "can(config.port)"), Value::from(true));
+    assert_eq!(eval_ctx(&ctx, "can(undefined)"), Value::from(false));
+    assert!(eval_error("try(undefined)").starts_with("no expression succeeded:\n- "));
+    assert_eq!(eval_error("try()"), "at least one argument is required");
+    assert_eq!(
+        eval_error("can()"),
+        "expected 1 positional arguments, got 0"
+    );
+}
+
+#[test]
+fn argument_validation() {
+    let expr = parse_expr("upper(1)");
+    let err = expr.evaluate(&Context::with_stdlib()).unwrap_err();
+
+    assert!(matches!(err.kind(), ErrorKind::FuncCall(name, _) if name.as_str() == "upper"));
+}
+
+#[test]
+fn user_functions_take_precedence() {
+    use hcl::eval::{FuncArgs, FuncDef, ParamType};
+
+    let mut ctx = Context::with_stdlib();
+    ctx.declare_func(
+        "upper",
+        FuncDef::new(
+            |_: FuncArgs| -> Result<Value, String> { Ok(Value::from("custom")) },
+            [ParamType::Any],
+        ),
+    );
+
+    assert_eq!(eval_ctx(&ctx, r#"upper("a")"#), Value::from("custom"));
+    assert_eq!(eval_ctx(&ctx, r#"lower("A")"#), Value::from("a"));
+}
@@ -1,4 +1,4 @@
 // EOF must be immediately followed by a newline
 diagnostics {
-  error = " --> 2:12\n  |\n2 |   source = <<-EOF # This is invalid\n  |            ^---\n  |\n  = expected Expression in line 2, col 12"
+  error = " --> 2:18\n  |\n2 |   source = <<-EOF # This is invalid\n  |                  ^---\n  |\n  = expected newline in line 2, col 18"
 }